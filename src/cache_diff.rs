@@ -0,0 +1,198 @@
+//! Compares two tag caches, eg. from before and after a game patch
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::Path;
+
+use anyhow::Context;
+use destiny_pkg::TagHash;
+use itertools::Itertools;
+use log::{info, warn};
+
+use crate::scanner::{ScanResult, TagCache};
+
+#[derive(serde::Serialize, Default)]
+pub struct CacheDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<TagDiff>,
+}
+
+/// Differences in the scan results of a tag that exists in both caches. Empty lists are left out of the JSON output
+#[derive(serde::Serialize)]
+pub struct TagDiff {
+    pub tag: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub added_references: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub removed_references: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub added_string_hashes: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub removed_string_hashes: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub added_raw_strings: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub removed_raw_strings: Vec<String>,
+}
+
+impl TagDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_references.is_empty()
+            && self.removed_references.is_empty()
+            && self.added_string_hashes.is_empty()
+            && self.removed_string_hashes.is_empty()
+            && self.added_raw_strings.is_empty()
+            && self.removed_raw_strings.is_empty()
+    }
+}
+
+/// Outgoing references, both 32 and 64-bit. 64-bit hashes are kept as-is, as mapping them requires the package manager
+fn outgoing_references(scan: &ScanResult) -> BTreeSet<String> {
+    scan.file_hashes
+        .iter()
+        .map(|h| h.hash.to_string())
+        .chain(scan.file_hashes64.iter().map(|h| h.hash.to_string()))
+        .collect()
+}
+
+fn string_hashes(scan: &ScanResult) -> BTreeSet<String> {
+    scan.string_hashes
+        .iter()
+        .map(|h| format!("{:08X}", h.hash))
+        .collect()
+}
+
+fn raw_strings(scan: &ScanResult) -> BTreeSet<String> {
+    scan.raw_strings.iter().cloned().collect()
+}
+
+/// Returns the values only in `new`, and the values only in `old`
+fn set_diff(old: BTreeSet<String>, new: BTreeSet<String>) -> (Vec<String>, Vec<String>) {
+    (
+        new.difference(&old).cloned().collect(),
+        old.difference(&new).cloned().collect(),
+    )
+}
+
+fn sorted_tags<'a>(tags: impl Iterator<Item = &'a TagHash>) -> Vec<TagHash> {
+    tags.copied()
+        .sorted_by_key(|t| (t.pkg_id(), t.entry_index()))
+        .collect()
+}
+
+/// Compares the scan results of a single tag. References are formatted the same way as [`TagHash`] and `TagHash64` display them
+pub fn diff_scan_results(tag: TagHash, old: &ScanResult, new: &ScanResult) -> TagDiff {
+    let (added_references, removed_references) =
+        set_diff(outgoing_references(old), outgoing_references(new));
+    let (added_string_hashes, removed_string_hashes) =
+        set_diff(string_hashes(old), string_hashes(new));
+    let (added_raw_strings, removed_raw_strings) = set_diff(raw_strings(old), raw_strings(new));
+
+    TagDiff {
+        tag: tag.to_string(),
+        added_references,
+        removed_references,
+        added_string_hashes,
+        removed_string_hashes,
+        added_raw_strings,
+        removed_raw_strings,
+    }
+}
+
+pub fn diff_caches(old: &TagCache, new: &TagCache) -> CacheDiff {
+    let mut diff = CacheDiff::default();
+
+    for tag in sorted_tags(new.hashes.keys()) {
+        let new_scan = &new.hashes[&tag];
+        let Some(old_scan) = old.hashes.get(&tag) else {
+            diff.added.push(tag.to_string());
+            continue;
+        };
+
+        let tag_diff = diff_scan_results(tag, old_scan, new_scan);
+        if !tag_diff.is_empty() {
+            diff.changed.push(tag_diff);
+        }
+    }
+
+    diff.removed = sorted_tags(old.hashes.keys().filter(|t| !new.hashes.contains_key(t)))
+        .into_iter()
+        .map(|t| t.to_string())
+        .collect();
+
+    diff
+}
+
+impl CacheDiff {
+    /// Readable summary, listing added and removed tags and the changes to each changed tag
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "{} added, {} removed, {} changed tags",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        )
+        .ok();
+
+        for tag in &self.added {
+            writeln!(out, "+ {tag}").ok();
+        }
+
+        for tag in &self.removed {
+            writeln!(out, "- {tag}").ok();
+        }
+
+        for d in &self.changed {
+            writeln!(out, "~ {}", d.tag).ok();
+            let sections = [
+                ("reference", &d.added_references, &d.removed_references),
+                (
+                    "string hash",
+                    &d.added_string_hashes,
+                    &d.removed_string_hashes,
+                ),
+                ("raw string", &d.added_raw_strings, &d.removed_raw_strings),
+            ];
+
+            for (label, added, removed) in sections {
+                for v in added {
+                    writeln!(out, "    + {label} {v}").ok();
+                }
+                for v in removed {
+                    writeln!(out, "    - {label} {v}").ok();
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// Loads two cache files and prints the differences, optionally writing them as JSON
+pub fn run(old_path: &Path, new_path: &Path, json_path: Option<&Path>) -> anyhow::Result<()> {
+    let old = TagCache::read_from(old_path)
+        .with_context(|| format!("Failed to read cache {}", old_path.display()))?;
+    let new = TagCache::read_from(new_path)
+        .with_context(|| format!("Failed to read cache {}", new_path.display()))?;
+
+    if old.version != new.version {
+        warn!(
+            "Caches were created by different quicktag versions (v{} and v{}), the diff may include changes in scanning",
+            old.version, new.version
+        );
+    }
+
+    let diff = diff_caches(&old, &new);
+    print!("{}", diff.summary());
+
+    if let Some(json_path) = json_path {
+        let f = std::io::BufWriter::new(std::fs::File::create(json_path)?);
+        serde_json::to_writer_pretty(f, &diff)?;
+        info!("Diff written to {}", json_path.display());
+    }
+
+    Ok(())
+}
@@ -24,6 +24,8 @@ pub struct TagClass {
     pub pretty_parser: Option<fn(&[u8], Endian) -> String>,
     /// Should this type block tag scanning? Useful for eliminating false positives in byte/vec4 blobs
     pub block_tags: bool,
+    /// Should tags of this class be skipped during traversal and tag searches?
+    pub skip_traversal: bool,
 }
 
 impl TagClass {
@@ -47,34 +49,39 @@ impl TagClass {
 macro_rules! class {
     // class!(0x80800009 byte)
     ($id:literal $name:ident) => {
-        class_internal!($id $name @size(None) @parse(None) @block_tags(false))
+        class_internal!($id $name @size(None) @parse(None) @block_tags(false) @skip_traversal(false))
+    };
+    // class!(0x808099F1 s_localized_strings_data @skip_traversal)
+    ($id:literal $name:ident @skip_traversal) => {
+        class_internal!($id $name @size(None) @parse(None) @block_tags(false) @skip_traversal(true))
     };
     // class!(0x80800009 byte @size(1))
     ($id:literal $name:ident @size($size:expr)) => {
-        class_internal!($id $name @size(Some($size)) @parse(Some(parse_hex)) @block_tags(false))
+        class_internal!($id $name @size(Some($size)) @parse(Some(parse_hex)) @block_tags(false) @skip_traversal(false))
     };
     // class!(0x80800009 byte @size(1) @block_tags)
     ($id:literal $name:ident @size($size:expr) @block_tags) => {
-        class_internal!($id $name @size(Some($size)) @parse(Some(parse_hex)) @block_tags(true))
+        class_internal!($id $name @size(Some($size)) @parse(Some(parse_hex)) @block_tags(true) @skip_traversal(false))
     };
     // class!(0x80800009 byte @size(1) @parse(parse_u8))
     ($id:literal $name:ident @size($size:expr) @parse($parsefn:expr)) => {
-        class_internal!($id $name @size(Some($size)) @parse(Some($parsefn)) @block_tags(false))
+        class_internal!($id $name @size(Some($size)) @parse(Some($parsefn)) @block_tags(false) @skip_traversal(false))
     };
     // class!(0x80800009 byte @size(1) @parse(parse_u8) @block_tags)
     ($id:literal $name:ident @size($size:expr) @parse($parsefn:expr) @block_tags) => {
-        class_internal!($id $name @size(Some($size)) @parse(Some($parsefn)) @block_tags(true))
+        class_internal!($id $name @size(Some($size)) @parse(Some($parsefn)) @block_tags(true) @skip_traversal(false))
     };
 }
 
 macro_rules! class_internal {
-    ($id:literal $name:ident @size($size:expr) @parse($parsefn:expr) @block_tags($block_tags:expr)) => {
+    ($id:literal $name:ident @size($size:expr) @parse($parsefn:expr) @block_tags($block_tags:expr) @skip_traversal($skip_traversal:expr)) => {
         TagClass {
             id: $id,
             name: Cow::Borrowed(stringify!($name)),
             size: $size,
             pretty_parser: $parsefn,
             block_tags: $block_tags,
+            skip_traversal: $skip_traversal,
         }
     };
 }
@@ -157,7 +164,7 @@ pub const CLASSES_SK: &[TagClass] = &[
     class!(0x808071F3 s_scope),
     class!(0x80809802 s_wwise_event),
     class!(0x80809A88 s_localized_strings),
-    class!(0x80809A8A s_localized_strings_data),
+    class!(0x80809A8A s_localized_strings_data @skip_traversal),
 ];
 
 pub const CLASSES_BL: &[TagClass] = &[
@@ -188,6 +195,9 @@ pub const CLASSES_BL: &[TagClass] = &[
     class!(0x80808703 s_map_container_entry),
     class!(0x80808707 s_map_container),
     class!(0x80808709 s_map_data_table_entry),
+    // Pesky material impact/footstep tags
+    class!(0x8080873D s_unk8080873d @skip_traversal),
+    class!(0x8080873F s_unk8080873f @skip_traversal),
     class!(0x8080891E s_bubble_parent),
     class!(0x80808BE0 s_animation_clip),
     class!(0x80808E8E s_activity),
@@ -200,7 +210,7 @@ pub const CLASSES_BL: &[TagClass] = &[
     class!(0x80809883 s_map_data_table),
     class!(0x80809885 s_map_data_entry),
     class!(0x808099EF s_localized_strings),
-    class!(0x808099F1 s_localized_strings_data),
+    class!(0x808099F1 s_localized_strings_data @skip_traversal),
     class!(0x808099F5 s_string_part_definition),
     class!(0x808099F7 s_string_part),
     class!(0x80809AD8 s_entity),
@@ -226,6 +236,24 @@ pub fn get_class_by_id(id: u32) -> Option<TagClass> {
         .or_else(|| CLASS_MAP_FROM_FILE.load().get(&id).cloned())
 }
 
+/// Looks up a class by its (case-insensitive) name, checking built-in classes before the schema file
+pub fn get_class_by_name(name: &str) -> Option<TagClass> {
+    let find = |map: &FxHashMap<u32, TagClass>| {
+        map.values()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+            .cloned()
+    };
+
+    find(&CLASS_MAP.load()).or_else(|| find(&CLASS_MAP_FROM_FILE.load()))
+}
+
+/// Returns true if tags of the given class should not be followed during traversal/search
+pub fn is_traversal_skipped(id: u32) -> bool {
+    get_class_by_id(id)
+        .map(|c| c.skip_traversal)
+        .unwrap_or_default()
+}
+
 pub fn load_schemafile() {
     let Ok(schemafile) = std::fs::read_to_string("schema.txt") else {
         return;
@@ -246,20 +274,33 @@ pub fn was_schemafile_refreshed() -> bool {
     REFRESHED_THIS_FRAME.load(std::sync::atomic::Ordering::Relaxed)
 }
 
+/// Should be called at the start of every frame, before the schema file is reloaded
+pub fn reset_schemafile_refreshed() {
+    REFRESHED_THIS_FRAME.store(false, std::sync::atomic::Ordering::Relaxed);
+}
+
 fn parse_schemafile(s: &str) -> anyhow::Result<FxHashMap<u32, TagClass>> {
     let mut schema: FxHashMap<u32, TagClass> = Default::default();
 
     // schema.txt lines can either be formatted as:
     // 8080XXXX <name>
     // 8080XXXX <name> <size>
+    // Either form can be followed by flags, eg. `8080XXXX <name> @skip_traversal`
     for l in s.lines() {
         let mut parts = l.split_whitespace();
         let id = u32::from_str_radix(parts.next().context("Missing class ID")?, 16)?;
         let name = parts.next().context("Missing name")?;
-        let size = parts
-            .next()
-            .map(|s| s.parse().context("Failed to parse size"))
-            .transpose()?;
+        let mut size = None;
+        let mut skip_traversal = false;
+        for part in parts {
+            match part {
+                "@skip_traversal" => skip_traversal = true,
+                flag if flag.starts_with('@') => {
+                    anyhow::bail!("Unknown flag '{flag}' for class {id:08X}")
+                }
+                _ => size = Some(part.parse().context("Failed to parse size")?),
+            }
+        }
 
         schema.insert(
             id,
@@ -269,6 +310,7 @@ fn parse_schemafile(s: &str) -> anyhow::Result<FxHashMap<u32, TagClass>> {
                 size,
                 pretty_parser: Some(parse_hex),
                 block_tags: false,
+                skip_traversal,
             },
         );
     }
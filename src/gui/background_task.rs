@@ -0,0 +1,164 @@
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use eframe::egui::{self, Align2, Color32, Rounding, Vec2};
+use lazy_static::lazy_static;
+use log::error;
+use parking_lot::Mutex;
+use poll_promise::Promise;
+
+use super::TOASTS;
+
+/// Progress and cancellation state shared between a background task and the UI
+#[derive(Default)]
+pub struct TaskProgress {
+    current: AtomicUsize,
+    total: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+impl TaskProgress {
+    pub fn set_total(&self, total: usize) {
+        self.total.store(total, Ordering::Relaxed);
+    }
+
+    /// Marks one more unit of work as done
+    pub fn advance(&self) {
+        self.current.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Returns a `TaskCancelled` error if the user pressed cancel, meant to be used with `?` between units of work
+    pub fn check_cancelled(&self) -> anyhow::Result<()> {
+        if self.is_cancelled() {
+            Err(TaskCancelled.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// (current, total), with total being 0 if unknown
+    fn get(&self) -> (usize, usize) {
+        (
+            self.current.load(Ordering::Relaxed),
+            self.total.load(Ordering::Relaxed),
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct TaskCancelled;
+
+impl Display for TaskCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Task was cancelled")
+    }
+}
+
+impl std::error::Error for TaskCancelled {}
+
+struct BackgroundTask {
+    name: String,
+    progress: Arc<TaskProgress>,
+    promise: Promise<anyhow::Result<()>>,
+}
+
+lazy_static! {
+    static ref TASKS: Mutex<Vec<BackgroundTask>> = Mutex::new(vec![]);
+}
+
+/// Runs a batch operation on a worker thread, showing a progress dialog with a cancel button until it finishes.
+/// Failures are logged and reported with a toast, successful tasks should report their own result
+pub fn spawn_task(
+    name: impl Into<String>,
+    f: impl FnOnce(&TaskProgress) -> anyhow::Result<()> + Send + 'static,
+) {
+    let name = name.into();
+    let progress = Arc::new(TaskProgress::default());
+    let progress_thread = progress.clone();
+    let promise = Promise::spawn_thread(name.clone(), move || f(&progress_thread));
+
+    TASKS.lock().push(BackgroundTask {
+        name,
+        progress,
+        promise,
+    });
+}
+
+/// Shows the progress dialog for any running tasks and reports finished ones. Returns true if tasks are still running
+pub fn show_tasks(ctx: &egui::Context) -> bool {
+    let mut tasks = TASKS.lock();
+    tasks.retain(|task| {
+        let Some(result) = task.promise.ready() else {
+            return true;
+        };
+
+        if let Err(e) = result {
+            if e.is::<TaskCancelled>() {
+                TOASTS.lock().info(format!("{} cancelled", task.name));
+            } else {
+                error!("{} failed: {e:?}", task.name);
+                TOASTS.lock().error(format!("{} failed: {e}", task.name));
+            }
+        }
+
+        false
+    });
+
+    if tasks.is_empty() {
+        return false;
+    }
+
+    {
+        let painter = ctx.layer_painter(egui::LayerId::background());
+        painter.rect_filled(
+            egui::Rect::EVERYTHING,
+            Rounding::default(),
+            Color32::from_black_alpha(127),
+        );
+    }
+    egui::Window::new("Background tasks")
+        .collapsible(false)
+        .resizable(false)
+        .title_bar(false)
+        .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+        .show(ctx, |ui| {
+            for task in tasks.iter() {
+                let (current, total) = task.progress.get();
+                let (fraction, text) = if total == 0 {
+                    (0.0, task.name.clone())
+                } else {
+                    (
+                        current as f32 / total as f32,
+                        format!("{} ({current}/{total})", task.name),
+                    )
+                };
+
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::ProgressBar::new(fraction)
+                            .animate(true)
+                            .desired_width(320.0)
+                            .text(text),
+                    );
+
+                    if task.progress.is_cancelled() {
+                        ui.spinner();
+                    } else if ui.button("Cancel").clicked() {
+                        task.progress.cancel();
+                    }
+                });
+            }
+        });
+
+    ctx.request_repaint();
+    true
+}
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use destiny_pkg::{package::UEntryHeader, TagHash};
+use eframe::egui::{self, RichText};
+
+use crate::classes::TagClass;
+use crate::gui::common::TagSelection;
+use crate::gui::tag::tag_search_results_ui;
+use crate::package_manager::package_manager;
+use crate::scanner::TagCache;
+use crate::texture::TextureCache;
+
+use super::ViewAction;
+
+/// Lists all tags in the cache that are instances of a given class
+pub struct ClassInstancesView {
+    pub class: TagClass,
+    cache: Arc<TagCache>,
+    results: Vec<(TagHash, UEntryHeader)>,
+    selection: TagSelection,
+}
+
+impl ClassInstancesView {
+    pub fn new(class: TagClass, cache: Arc<TagCache>) -> Self {
+        let mut results: Vec<(TagHash, UEntryHeader)> = cache
+            .hashes
+            .keys()
+            .filter_map(|tag| {
+                let entry = package_manager().get_entry(*tag)?;
+                (entry.reference == class.id).then_some((*tag, entry))
+            })
+            .collect();
+
+        results.sort_by_key(|(tag, _)| tag.0);
+
+        Self {
+            class,
+            cache,
+            results,
+            selection: TagSelection::default(),
+        }
+    }
+
+    pub fn view(
+        &mut self,
+        _ctx: &egui::Context,
+        ui: &mut egui::Ui,
+        texture_cache: &TextureCache,
+    ) -> Option<ViewAction> {
+        ui.heading(format!("{} ({:08X})", self.class.name, self.class.id));
+        ui.label(format!("{} instances", self.results.len()));
+        ui.separator();
+
+        if self.results.is_empty() {
+            ui.label(RichText::new("No tags found for this class").italics());
+            return None;
+        }
+
+        tag_search_results_ui(
+            ui,
+            &self.results,
+            &mut self.selection,
+            &self.cache,
+            texture_cache,
+        )
+        .map(ViewAction::OpenTag)
+    }
+}
@@ -1,18 +1,24 @@
 use std::fs::File;
+use std::path::{Path, PathBuf};
 
+use anyhow::Context;
 use destiny_pkg::TagHash;
 use eframe::egui;
 use eframe::egui::RichText;
+use eframe::egui_wgpu::RenderState;
 use image::{DynamicImage, GenericImage, ImageFormat};
 use lazy_static::lazy_static;
 use log::{error, info, warn};
 use std::io::{Cursor, Write};
 use std::num::NonZeroU32;
 
+use rustc_hash::FxHashSet;
+
 use crate::package_manager::get_hash64;
 use crate::texture::{Texture, TextureCache};
 use crate::{package_manager::package_manager, tagtypes::TagType};
 
+use super::background_task::{spawn_task, TaskProgress};
 use super::TOASTS;
 
 lazy_static! {
@@ -47,40 +53,11 @@ impl ResponseExt for egui::Response {
         self.context_menu(|ui| {
             if is_texture {
                 if ui.selectable_label(false, "📷 Copy texture").clicked() {
-                    match Texture::load(&texture_cache.render_state, tag, false) {
-                        Ok(o) => {
-                            let image = o.to_image(&texture_cache.render_state, 0).unwrap();
-                            let mut png_data = vec![];
-                            let mut png_writer = Cursor::new(&mut png_data);
-                            image.write_to(&mut png_writer, ImageFormat::Png).unwrap();
-
-                            let _clipboard = clipboard_win::Clipboard::new();
-                            if let Err(e) = clipboard_win::raw::set(CF_PNG.get(), &png_data) {
-                                error!("Failed to copy texture to clipboard: {e}");
-                            }
-
-                            // Save to temp
-                            let path = std::env::temp_dir().join(format!("{tag}.png"));
-                            let mut file = File::create(&path).unwrap();
-                            file.write_all(&png_data).unwrap();
-
-                            let mut path_utf16 =
-                                path.to_string_lossy().encode_utf16().collect::<Vec<u16>>();
-                            path_utf16.push(0);
-
-                            if let Err(e) = clipboard_win::raw::set_without_clear(
-                                CF_FILENAME.get(),
-                                bytemuck::cast_slice(&path_utf16),
-                            ) {
-                                error!("Failed to copy texture path to clipboard: {e}");
-                            } else {
-                                TOASTS.lock().success("Texture copied to clipboard");
-                            }
-                        }
-                        Err(e) => {
-                            error!("Failed to load texture: {e}");
-                        }
-                    }
+                    spawn_texture_export(
+                        texture_cache.render_state.clone(),
+                        tag,
+                        TextureExport::Copy,
+                    );
                     ui.close_menu();
                 }
 
@@ -89,28 +66,41 @@ impl ResponseExt for egui::Response {
                     .on_hover_text("Texture(s) will be saved to the textures/ directory")
                     .clicked()
                 {
-                    match Texture::load(&texture_cache.render_state, tag, false) {
-                        Ok(o) => {
-                            std::fs::create_dir_all("textures/").unwrap();
-                            let mut images = vec![];
-                            for layer in 0..(o.desc.array_size.max(o.desc.depth)) {
-                                let image = o.to_image(&texture_cache.render_state, layer).unwrap();
-                                image.save(format!("textures/{tag}_{layer}.png")).unwrap();
-                                images.push(image);
-                            }
-
-                            if images.len() == 6 {
-                                let cubemap_image = assemble_cubemap(images);
-                                cubemap_image
-                                    .save(format!("textures/{tag}_cubemap.png"))
-                                    .unwrap();
-                            }
-                            TOASTS.lock().success("Texture saved");
-                        }
-                        Err(e) => {
-                            error!("Failed to load texture: {e}");
-                        }
-                    }
+                    spawn_texture_export(
+                        texture_cache.render_state.clone(),
+                        tag,
+                        TextureExport::Save,
+                    );
+                    ui.close_menu();
+                }
+
+                if ui
+                    .selectable_label(false, "📷 Save texture (full precision)")
+                    .on_hover_text(
+                        "Float/HDR textures will be saved as EXR, other high precision formats as 16-bit PNG",
+                    )
+                    .clicked()
+                {
+                    spawn_texture_export(
+                        texture_cache.render_state.clone(),
+                        tag,
+                        TextureExport::SaveFullPrecision,
+                    );
+                    ui.close_menu();
+                }
+
+                if ui
+                    .selectable_label(false, "📷 Save texture atlas")
+                    .on_hover_text(
+                        "Saves all layers of an array texture as a single grid image (cross layout for cubemaps)",
+                    )
+                    .clicked()
+                {
+                    spawn_texture_export(
+                        texture_cache.render_state.clone(),
+                        tag,
+                        TextureExport::SaveAtlas,
+                    );
                     ui.close_menu();
                 }
             }
@@ -126,6 +116,163 @@ impl ResponseExt for egui::Response {
     }
 }
 
+#[derive(Clone, Copy)]
+enum TextureExport {
+    Copy,
+    Save,
+    SaveFullPrecision,
+    SaveAtlas,
+}
+
+/// Exports a texture as a background task, so the GPU readback doesn't block the UI
+fn spawn_texture_export(rs: RenderState, tag: TagHash, kind: TextureExport) {
+    spawn_task(format!("Exporting texture {tag}"), move |progress| {
+        let texture = Texture::load(&rs, tag, false)?;
+        match kind {
+            TextureExport::Copy => copy_texture(&rs, &texture, tag),
+            TextureExport::Save => save_texture(&rs, &texture, tag, progress),
+            TextureExport::SaveFullPrecision => {
+                save_texture_full_precision(&rs, &texture, tag, progress)
+            }
+            TextureExport::SaveAtlas => save_texture_atlas(&rs, &texture, tag, progress),
+        }
+    });
+}
+
+fn copy_texture(rs: &RenderState, texture: &Texture, tag: TagHash) -> anyhow::Result<()> {
+    let image = texture.to_image(rs, 0)?;
+    let mut png_data = vec![];
+    let mut png_writer = Cursor::new(&mut png_data);
+    image.write_to(&mut png_writer, ImageFormat::Png)?;
+
+    let _clipboard = clipboard_win::Clipboard::new();
+    if let Err(e) = clipboard_win::raw::set(CF_PNG.get(), &png_data) {
+        error!("Failed to copy texture to clipboard: {e}");
+    }
+
+    // Save to temp
+    let path = std::env::temp_dir().join(format!("{tag}.png"));
+    let mut file = File::create(&path)?;
+    file.write_all(&png_data)?;
+
+    let mut path_utf16 = path.to_string_lossy().encode_utf16().collect::<Vec<u16>>();
+    path_utf16.push(0);
+
+    if let Err(e) =
+        clipboard_win::raw::set_without_clear(CF_FILENAME.get(), bytemuck::cast_slice(&path_utf16))
+    {
+        error!("Failed to copy texture path to clipboard: {e}");
+    } else {
+        TOASTS.lock().success("Texture copied to clipboard");
+    }
+
+    Ok(())
+}
+
+fn save_texture(
+    rs: &RenderState,
+    texture: &Texture,
+    tag: TagHash,
+    progress: &TaskProgress,
+) -> anyhow::Result<()> {
+    std::fs::create_dir_all("textures/")?;
+    let layer_count = texture.desc.array_size.max(texture.desc.depth);
+    progress.set_total(layer_count as usize);
+    let mut images = vec![];
+    for layer in 0..layer_count {
+        progress.check_cancelled()?;
+        let image = texture.to_image(rs, layer)?;
+        image.save(format!("textures/{tag}_{layer}.png"))?;
+        images.push(image);
+        progress.advance();
+    }
+
+    if images.len() == 6 {
+        let cubemap_image = assemble_cubemap(images);
+        cubemap_image.save(format!("textures/{tag}_cubemap.png"))?;
+    }
+    TOASTS.lock().success("Texture saved");
+
+    Ok(())
+}
+
+fn save_texture_full_precision(
+    rs: &RenderState,
+    texture: &Texture,
+    tag: TagHash,
+    progress: &TaskProgress,
+) -> anyhow::Result<()> {
+    if !texture.is_high_precision() {
+        TOASTS.lock().info(format!(
+            "{:?} has no more than 8 bits per channel, saving as a regular PNG",
+            texture.desc.format
+        ));
+    }
+
+    let extension = if texture.is_hdr() { "exr" } else { "png" };
+    std::fs::create_dir_all("textures/")?;
+    let layer_count = texture.desc.array_size.max(texture.desc.depth);
+    progress.set_total(layer_count as usize);
+    let mut images = vec![];
+    for layer in 0..layer_count {
+        progress.check_cancelled()?;
+        let image = texture.to_image_hdr(rs, layer)?;
+        let image = if texture.is_hdr() {
+            image
+        } else if texture.is_high_precision() {
+            DynamicImage::ImageRgba16(image.to_rgba16())
+        } else {
+            DynamicImage::ImageRgba8(image.to_rgba8())
+        };
+        image.save(format!("textures/{tag}_{layer}.{extension}"))?;
+        images.push(image);
+        progress.advance();
+    }
+
+    if images.len() == 6 {
+        let cubemap_image = assemble_cubemap(images);
+        cubemap_image.save(format!("textures/{tag}_cubemap.{extension}"))?;
+    }
+    TOASTS.lock().success("Texture saved");
+
+    Ok(())
+}
+
+/// Saves all layers of a texture into a single image instead of one file per layer
+fn save_texture_atlas(
+    rs: &RenderState,
+    texture: &Texture,
+    tag: TagHash,
+    progress: &TaskProgress,
+) -> anyhow::Result<()> {
+    let layer_count = texture.desc.array_size.max(texture.desc.depth);
+    if layer_count <= 1 {
+        TOASTS
+            .lock()
+            .info("Texture only has a single layer, use 'Save texture' instead");
+        return Ok(());
+    }
+
+    std::fs::create_dir_all("textures/")?;
+    progress.set_total(layer_count as usize);
+    let mut images = vec![];
+    for layer in 0..layer_count {
+        progress.check_cancelled()?;
+        images.push(texture.to_image(rs, layer)?);
+        progress.advance();
+    }
+
+    let atlas = if texture.desc.array_size == 6 {
+        assemble_cubemap(images)
+    } else {
+        assemble_atlas(images)
+    };
+    atlas.save(format!("textures/{tag}_atlas.png"))?;
+    TOASTS.lock().success("Texture atlas saved");
+
+    Ok(())
+}
+
 fn tag_hover_ui(ui: &mut egui::Ui, tag: TagHash) {
     if let Some(path) = package_manager().package_paths.get(&tag.pkg_id()) {
         ui.label(format!("Package: {}", path.filename));
@@ -218,6 +365,29 @@ pub fn tag_context(ui: &mut egui::Ui, tag: TagHash) {
         }
     }
 
+    ui.menu_button("📋 Copy for other tools", |ui| {
+        let formats = [
+            ("Alkahest / Charm", tag.to_string()),
+            (
+                "Package ID + entry index",
+                format!("{:04X}-{:04X}", tag.pkg_id(), tag.entry_index()),
+            ),
+            ("Byte-flipped (deprecated)", format!("{:08X}", tag.0)),
+        ];
+
+        for (label, text) in formats {
+            if ui
+                .selectable_label(false, format!("{label}: {text}"))
+                .clicked()
+            {
+                ui.output_mut(|o| o.copied_text = text);
+                ui.close_menu();
+            }
+        }
+    })
+    .response
+    .on_hover_text("The byte-flipped form is only understood by older tools");
+
     if let Some(entry) = package_manager().get_entry(tag) {
         let shift = ui.input(|i| i.modifiers.shift);
 
@@ -269,8 +439,17 @@ pub fn tag_context(ui: &mut egui::Ui, tag: TagHash) {
 }
 
 pub fn open_tag_in_default_application(tag: TagHash) {
-    let data = package_manager().read_tag(tag).unwrap();
-    let entry = package_manager().get_entry(tag).unwrap();
+    if let Ok(path) = dump_tag_data(tag, &std::env::temp_dir()) {
+        opener::open(path).ok();
+    }
+}
+
+/// Writes the data of a tag to the given directory, returning the path of the written file
+pub fn dump_tag_data(tag: TagHash, directory: &Path) -> anyhow::Result<PathBuf> {
+    let data = package_manager().read_tag(tag)?;
+    let entry = package_manager()
+        .get_entry(tag)
+        .context("Tag entry not found")?;
 
     let filename = format!(
         "{tag}_ref-{:08X}_{}_{}.bin",
@@ -279,10 +458,181 @@ pub fn open_tag_in_default_application(tag: TagHash) {
         entry.file_subtype,
     );
 
-    let path = std::env::temp_dir().join(filename);
-    std::fs::write(&path, data).ok();
+    let path = directory.join(filename);
+    std::fs::write(&path, data)?;
+
+    Ok(path)
+}
+
+/// Multi-selection state for a list of tags
+#[derive(Default)]
+pub struct TagSelection {
+    selected: FxHashSet<TagHash>,
+    /// Index of the last clicked item, used as the start of shift-click ranges
+    anchor: Option<usize>,
+}
+
+impl TagSelection {
+    pub fn is_selected(&self, tag: TagHash) -> bool {
+        self.selected.contains(&tag)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.selected.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.selected.clear();
+        self.anchor = None;
+    }
+
+    /// Handles a click on the item at `index` in `tags`
+    ///
+    /// Returns true if the click modified the selection (ctrl/shift held), in which case it shouldn't be treated as a regular click
+    pub fn handle_click(
+        &mut self,
+        modifiers: egui::Modifiers,
+        index: usize,
+        mut tags: impl Iterator<Item = TagHash>,
+    ) -> bool {
+        if modifiers.shift {
+            let anchor = self.anchor.unwrap_or(index);
+            let range = anchor.min(index)..=anchor.max(index);
+            self.selected.extend(
+                tags.enumerate()
+                    .filter(|(i, _)| range.contains(i))
+                    .map(|(_, t)| t),
+            );
+            true
+        } else if modifiers.command {
+            if let Some(tag) = tags.nth(index) {
+                if !self.selected.remove(&tag) {
+                    self.selected.insert(tag);
+                }
+            }
+            self.anchor = Some(index);
+            true
+        } else {
+            self.clear();
+            self.anchor = Some(index);
+            false
+        }
+    }
+
+    /// The item that was last clicked or moved to with the keyboard
+    pub fn cursor(&self) -> Option<usize> {
+        self.anchor
+    }
+
+    /// Moves the cursor with the arrow keys, see [`list_keyboard_input`]
+    pub fn handle_keyboard(
+        &mut self,
+        ui: &egui::Ui,
+        list_rect: egui::Rect,
+        len: usize,
+    ) -> ListKeyboardInput {
+        list_keyboard_input(ui, list_rect, &mut self.anchor, len)
+    }
+
+    /// Selected tags, in the order they appear in `tags`
+    pub fn selected_in(&self, tags: impl Iterator<Item = TagHash>) -> Vec<TagHash> {
+        tags.filter(|t| self.selected.contains(t)).collect()
+    }
+
+    /// Draws the batch actions for the current selection
+    pub fn actions_ui(&mut self, ui: &mut egui::Ui, tags: impl Iterator<Item = TagHash>) {
+        if self.is_empty() {
+            ui.weak("Ctrl/Shift-click to select multiple tags");
+            return;
+        }
+
+        let selected = self.selected_in(tags);
+        ui.horizontal(|ui| {
+            ui.label(format!("{} selected", selected.len()));
 
-    opener::open(path).ok();
+            if ui.button("📤 Open selected").clicked() {
+                for &tag in &selected {
+                    open_tag_in_default_application(tag);
+                }
+            }
+
+            if ui
+                .button("💾 Dump selected")
+                .on_hover_text("Tag data will be saved to the dump/selection/ directory")
+                .clicked()
+            {
+                let directory = PathBuf::from("dump").join("selection");
+                let result = std::fs::create_dir_all(&directory)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|_| {
+                        selected
+                            .iter()
+                            .try_for_each(|&tag| dump_tag_data(tag, &directory).map(|_| ()))
+                    });
+
+                match result {
+                    Ok(()) => {
+                        TOASTS
+                            .lock()
+                            .success(format!("Dumped {} tags", selected.len()));
+                    }
+                    Err(e) => error!("Failed to dump selected tags: {e:?}"),
+                }
+            }
+
+            if ui.button("Clear").clicked() {
+                self.clear();
+            }
+        });
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct ListKeyboardInput {
+    /// The cursor was moved this frame, the list should scroll to it
+    pub moved: bool,
+    /// Enter was pressed, the item under the cursor should be opened
+    pub activated: bool,
+}
+
+/// Moves `cursor` through a list of `len` items with the arrow keys (and Home/End).
+///
+/// Keys are only handled while the pointer is over `list_rect` and no other widget (eg. a text field) has keyboard focus
+pub fn list_keyboard_input(
+    ui: &egui::Ui,
+    list_rect: egui::Rect,
+    cursor: &mut Option<usize>,
+    len: usize,
+) -> ListKeyboardInput {
+    let mut result = ListKeyboardInput::default();
+    if len == 0 || !ui.rect_contains_pointer(list_rect) || ui.memory(|m| m.focused().is_some()) {
+        return result;
+    }
+
+    let last = len - 1;
+    let new_cursor = ui.input_mut(|i| {
+        if i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown) {
+            Some(cursor.map_or(0, |c| (c + 1).min(last)))
+        } else if i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp) {
+            Some(cursor.map_or(0, |c| c.saturating_sub(1).min(last)))
+        } else if i.consume_key(egui::Modifiers::NONE, egui::Key::Home) {
+            Some(0)
+        } else if i.consume_key(egui::Modifiers::NONE, egui::Key::End) {
+            Some(last)
+        } else {
+            None
+        }
+    });
+
+    if let Some(new_cursor) = new_cursor {
+        result.moved = *cursor != Some(new_cursor);
+        *cursor = Some(new_cursor);
+    }
+
+    result.activated = cursor.is_some_and(|c| c < len)
+        && ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Enter));
+
+    result
 }
 
 #[cfg(not(feature = "audio"))]
@@ -373,12 +723,28 @@ pub fn dump_wwise_info(package_id: u16) {
     });
 }
 
+/// Arranges layers in a roughly square grid, left to right and top to bottom
+fn assemble_atlas(images: Vec<DynamicImage>) -> DynamicImage {
+    let tile_w = images[0].width();
+    let tile_h = images[0].height();
+    let columns = (images.len() as f32).sqrt().ceil() as u32;
+    let rows = (images.len() as u32).div_ceil(columns);
+
+    let mut atlas = DynamicImage::new(tile_w * columns, tile_h * rows, images[0].color());
+    for (i, image) in images.iter().enumerate() {
+        let i = i as u32;
+        let _ = atlas.copy_from(image, (i % columns) * tile_w, (i / columns) * tile_h);
+    }
+
+    atlas
+}
+
 #[allow(clippy::erasing_op)]
 fn assemble_cubemap(images: Vec<DynamicImage>) -> DynamicImage {
     let tile_w = images[0].width();
     let tile_h = images[0].height();
 
-    let mut cubemap = DynamicImage::new_rgba8(tile_w * 4, tile_h * 3);
+    let mut cubemap = DynamicImage::new(tile_w * 4, tile_h * 3, images[0].color());
 
     let x_pos = images[0].rotate90();
     let x_neg = images[1].rotate270();
@@ -0,0 +1,358 @@
+use binrw::Endian;
+use eframe::egui::{self, pos2, vec2, Color32, RichText, Sense, Stroke};
+
+use crate::util::u32_from_endian;
+
+/// Runs with fewer keyframes are too likely to be coincidental
+const MIN_KEYFRAMES: usize = 4;
+/// Allowed deviation from unit length for quaternion keyframes
+const QUATERNION_EPSILON: f32 = 0.01;
+
+const TIME_VALUE_COLOR: Color32 = Color32::from_rgb(249, 168, 71);
+const QUATERNION_COLORS: [(&str, Color32); 4] = [
+    ("x", Color32::from_rgb(230, 80, 80)),
+    ("y", Color32::from_rgb(80, 200, 80)),
+    ("z", Color32::from_rgb(90, 140, 255)),
+    ("w", Color32::GRAY),
+];
+
+/// Any very low/high values (with exponents) are likely not floats
+pub fn is_plausible_float(v: f32) -> bool {
+    (v.is_normal() && v.abs() < 1e7 && v.abs() > 1e-10) || v == 0.0
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CurveKind {
+    /// (time, value) pairs with strictly increasing times
+    TimeValue,
+    /// Normalized (x, y, z, w) quaternions
+    Quaternion,
+}
+
+impl CurveKind {
+    /// Number of floats per keyframe
+    fn stride(self) -> usize {
+        match self {
+            CurveKind::TimeValue => 2,
+            CurveKind::Quaternion => 4,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            CurveKind::TimeValue => "time/value",
+            CurveKind::Quaternion => "quaternion",
+        }
+    }
+}
+
+struct Curve {
+    offset: u64,
+    kind: CurveKind,
+    /// `kind.stride()` floats per keyframe
+    values: Vec<f32>,
+}
+
+impl Curve {
+    fn keyframes(&self) -> std::slice::ChunksExact<'_, f32> {
+        self.values.chunks_exact(self.kind.stride())
+    }
+
+    fn keyframe_count(&self) -> usize {
+        self.values.len() / self.kind.stride()
+    }
+
+    /// The plotted lines, as (label, color, points)
+    fn series(&self) -> Vec<(&'static str, Color32, Vec<[f32; 2]>)> {
+        match self.kind {
+            CurveKind::TimeValue => vec![(
+                "value",
+                TIME_VALUE_COLOR,
+                self.keyframes().map(|k| [k[0], k[1]]).collect(),
+            )],
+            CurveKind::Quaternion => QUATERNION_COLORS
+                .iter()
+                .enumerate()
+                .map(|(c, (label, color))| {
+                    (
+                        *label,
+                        *color,
+                        self.keyframes()
+                            .enumerate()
+                            .map(|(i, k)| [i as f32, k[c]])
+                            .collect(),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Number of consecutive keyframes of the given kind at the start of `floats`
+fn run_length(floats: &[f32], kind: CurveKind) -> usize {
+    let keyframes = floats.chunks_exact(kind.stride());
+    let mut len = 0;
+    let mut previous: Option<&[f32]> = None;
+    for k in keyframes {
+        if !k.iter().all(|&v| is_plausible_float(v)) {
+            break;
+        }
+
+        let valid = match kind {
+            CurveKind::TimeValue => k[0] >= 0.0 && previous.map_or(true, |p| k[0] > p[0]),
+            CurveKind::Quaternion => {
+                let length = k.iter().map(|v| v * v).sum::<f32>().sqrt();
+                (length - 1.0).abs() < QUATERNION_EPSILON
+            }
+        };
+        if !valid {
+            break;
+        }
+
+        previous = Some(k);
+        len += 1;
+    }
+
+    len
+}
+
+/// Runs where every keyframe has the same value (eg. arrays of identity quaternions) aren't animation
+fn is_flat(run: &[f32], kind: CurveKind) -> bool {
+    match kind {
+        CurveKind::TimeValue => run.chunks_exact(2).all(|k| k[1] == 0.0),
+        CurveKind::Quaternion => run.chunks_exact(4).all(|k| k == &run[..4]),
+    }
+}
+
+/// Shows runs of float data that look like animation curves, without needing to know the structure of the tag
+pub struct TagCurveView {
+    curves: Vec<Curve>,
+    selected: usize,
+}
+
+impl TagCurveView {
+    pub fn detect(data: &[u8], endian: Endian) -> Self {
+        let floats: Vec<f32> = data
+            .chunks_exact(4)
+            .map(|c| f32::from_bits(u32_from_endian(endian, c.try_into().unwrap())))
+            .collect();
+
+        let mut curves = vec![];
+        let mut i = 0;
+        while i < floats.len() {
+            // Floats covered by runs that were long enough but flat, skipped as a whole so shifted copies of the same run aren't checked again
+            let mut skip = 1;
+            for kind in [CurveKind::TimeValue, CurveKind::Quaternion] {
+                let len = run_length(&floats[i..], kind);
+                if len < MIN_KEYFRAMES {
+                    continue;
+                }
+
+                let end = i + len * kind.stride();
+                if is_flat(&floats[i..end], kind) {
+                    skip = skip.max(len * kind.stride());
+                    continue;
+                }
+
+                curves.push(Curve {
+                    offset: i as u64 * 4,
+                    kind,
+                    values: floats[i..end].to_vec(),
+                });
+                skip = end - i;
+                break;
+            }
+
+            i += skip;
+        }
+
+        Self {
+            curves,
+            selected: 0,
+        }
+    }
+
+    pub fn curve_count(&self) -> usize {
+        self.curves.len()
+    }
+
+    /// Returns the offset of the curve to show in the hex view, if requested
+    #[must_use]
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Option<u64> {
+        if self.curves.is_empty() {
+            ui.label(RichText::new("No curve data detected").italics());
+            return None;
+        }
+
+        let count_of = |kind| self.curves.iter().filter(|c| c.kind == kind).count();
+        let (min_keyframes, max_keyframes) = self
+            .curves
+            .iter()
+            .map(|c| c.keyframe_count())
+            .fold((usize::MAX, 0), |(min, max), n| (min.min(n), max.max(n)));
+        ui.label(format!(
+            "Looks like {} curves of {min_keyframes}-{max_keyframes} keyframes ({} time/value, {} quaternion)",
+            self.curves.len(),
+            count_of(CurveKind::TimeValue),
+            count_of(CurveKind::Quaternion),
+        ));
+        ui.label(
+            RichText::new("Detected heuristically from runs of increasing (time, value) pairs and unit quaternions")
+                .weak(),
+        );
+        ui.separator();
+
+        let mut jump_to = None;
+        egui::SidePanel::left("curves_list")
+            .resizable(true)
+            .min_width(200.0)
+            .show_inside(ui, |ui| {
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        for (i, curve) in self.curves.iter().enumerate() {
+                            let response = ui.selectable_value(
+                                &mut self.selected,
+                                i,
+                                format!(
+                                    "0x{:X}: {} x{}",
+                                    curve.offset,
+                                    curve.kind.name(),
+                                    curve.keyframe_count()
+                                ),
+                            );
+                            if response.double_clicked() {
+                                jump_to = Some(curve.offset);
+                            }
+                            response.on_hover_text("Double-click to show in hex view");
+                        }
+                    });
+            });
+
+        egui::CentralPanel::default().show_inside(ui, |ui| {
+            let Some(curve) = self.curves.get(self.selected) else {
+                return;
+            };
+
+            ui.horizontal(|ui| {
+                ui.strong(format!("0x{:X}", curve.offset));
+                for (label, color, _) in curve.series() {
+                    ui.colored_label(color, label);
+                }
+                if ui.link("Show in hex view").clicked() {
+                    jump_to = Some(curve.offset);
+                }
+            });
+            plot_ui(ui, curve);
+        });
+
+        jump_to
+    }
+}
+
+/// Draws the curve as a line plot, with the keyframe closest to the pointer highlighted
+fn plot_ui(ui: &mut egui::Ui, curve: &Curve) {
+    let series = curve.series();
+    let points = || series.iter().flat_map(|(_, _, p)| p.iter());
+    let (mut x_min, mut x_max, mut y_min, mut y_max) = points().fold(
+        (f32::MAX, f32::MIN, f32::MAX, f32::MIN),
+        |(x0, x1, y0, y1), [x, y]| (x0.min(*x), x1.max(*x), y0.min(*y), y1.max(*y)),
+    );
+    if x_max <= x_min {
+        x_min -= 1.0;
+        x_max += 1.0;
+    }
+    if y_max <= y_min {
+        y_min -= 1.0;
+        y_max += 1.0;
+    }
+
+    let (response, painter) = ui.allocate_painter(
+        vec2(ui.available_width(), ui.available_height().max(200.0)),
+        Sense::hover(),
+    );
+    let rect = response.rect.shrink(24.0);
+    let to_screen = |[x, y]: [f32; 2]| {
+        pos2(
+            egui::remap(x, x_min..=x_max, rect.x_range()),
+            egui::remap(y, y_min..=y_max, rect.bottom()..=rect.top()),
+        )
+    };
+
+    painter.rect_filled(response.rect, 0.0, Color32::from_gray(20));
+    if (y_min..=y_max).contains(&0.0) {
+        painter.hline(
+            rect.x_range(),
+            to_screen([x_min, 0.0]).y,
+            Stroke::new(1.0, Color32::from_gray(60)),
+        );
+    }
+
+    let font = egui::FontId::monospace(10.0);
+    let text_color = Color32::from_gray(160);
+    painter.text(
+        rect.left_top(),
+        egui::Align2::LEFT_BOTTOM,
+        format!("{y_max:.4}"),
+        font.clone(),
+        text_color,
+    );
+    painter.text(
+        rect.left_bottom(),
+        egui::Align2::LEFT_TOP,
+        format!("{y_min:.4}"),
+        font.clone(),
+        text_color,
+    );
+    let x_label = |x: f32| match curve.kind {
+        CurveKind::TimeValue => format!("t={x:.4}"),
+        CurveKind::Quaternion => format!("#{x}"),
+    };
+    painter.text(
+        rect.right_bottom(),
+        egui::Align2::RIGHT_TOP,
+        x_label(x_max),
+        font,
+        text_color,
+    );
+
+    for (_, color, points) in &series {
+        let screen_points: Vec<_> = points.iter().map(|p| to_screen(*p)).collect();
+        painter.add(egui::Shape::line(
+            screen_points.clone(),
+            Stroke::new(1.5, *color),
+        ));
+        for p in screen_points {
+            painter.circle_filled(p, 2.0, *color);
+        }
+    }
+
+    let Some(pointer) = response.hover_pos() else {
+        return;
+    };
+
+    // All series share the same x values, so the first one is enough to find the closest keyframe
+    let Some((index, [x, _])) = series[0].2.iter().enumerate().min_by(|(_, a), (_, b)| {
+        (to_screen(**a).x - pointer.x)
+            .abs()
+            .total_cmp(&(to_screen(**b).x - pointer.x).abs())
+    }) else {
+        return;
+    };
+
+    painter.vline(
+        to_screen([*x, 0.0]).x,
+        rect.y_range(),
+        Stroke::new(1.0, Color32::from_white_alpha(60)),
+    );
+    let values = series
+        .iter()
+        .map(|(label, _, points)| format!("{label}: {:.4}", points[index][1]))
+        .collect::<Vec<_>>()
+        .join("\n");
+    response.on_hover_text(format!(
+        "Keyframe {index} @ 0x{:X}\n{}\n{values}",
+        curve.offset + (index * curve.kind.stride() * 4) as u64,
+        x_label(*x)
+    ));
+}
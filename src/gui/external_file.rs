@@ -8,21 +8,28 @@ use crate::scanner::ScannerContext;
 use crate::tagtypes::TagType;
 use crate::texture::TextureCache;
 use eframe::egui;
+use std::path::{Path, PathBuf};
 
 pub struct ExternalFileScanView {
     pub filename: String,
+    pub path: PathBuf,
     file_hashes: Vec<ScannedHashWithEntry<ExtendedTagHash>>,
 }
 
 impl ExternalFileScanView {
-    pub fn new(filename: String, scancontext: &ScannerContext, data: &[u8]) -> Self {
-        let scanresult = scanner::scan_file(scancontext, data, true);
+    pub fn open(path: &Path, scancontext: &ScannerContext) -> std::io::Result<Self> {
+        let data = std::fs::read(path)?;
+        let scanresult = scanner::scan_file(scancontext, &data, true);
         let scanresult_ext = ExtendedScanResult::from_scanresult(scanresult);
 
-        Self {
-            filename,
+        Ok(Self {
+            filename: path
+                .file_name()
+                .map(|f| f.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            path: path.to_path_buf(),
             file_hashes: scanresult_ext.file_hashes,
-        }
+        })
     }
 
     pub fn view(
@@ -0,0 +1,264 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use eframe::egui::{self, RichText};
+use eframe::epaint::Color32;
+use egui_extras::{Column, TableBuilder};
+
+use crate::text::{RawStringHashCache, StringCache};
+
+use super::ViewAction;
+
+#[derive(Clone, Copy, PartialEq)]
+enum HashSource {
+    /// Localized string from the string containers
+    Localized,
+    /// String found in a scanned tag
+    RawString,
+    Wordlist,
+}
+
+impl HashSource {
+    fn label(&self) -> &'static str {
+        match self {
+            HashSource::Localized => "Localized",
+            HashSource::RawString => "Raw string",
+            HashSource::Wordlist => "Wordlist",
+        }
+    }
+
+    fn color(&self) -> Color32 {
+        match self {
+            HashSource::Localized => Color32::from_rgb(110, 200, 255),
+            HashSource::RawString => Color32::from_rgb(255, 190, 90),
+            HashSource::Wordlist => Color32::from_rgb(150, 220, 110),
+        }
+    }
+}
+
+struct ResolvedHash {
+    hash: u32,
+    candidates: Vec<(HashSource, String)>,
+}
+
+/// Resolves a pasted list of 32-bit string hashes against all known string sources at once
+pub struct HashResolverView {
+    strings: Arc<StringCache>,
+    raw_strings: Arc<RawStringHashCache>,
+
+    input: String,
+    /// Tokens in the input that couldn't be parsed as a hash
+    invalid: Vec<String>,
+    results: Vec<ResolvedHash>,
+    hide_unresolved: bool,
+}
+
+impl HashResolverView {
+    pub fn new(strings: Arc<StringCache>, raw_strings: Arc<RawStringHashCache>) -> Self {
+        Self {
+            strings,
+            raw_strings,
+            input: String::new(),
+            invalid: vec![],
+            results: vec![],
+            hide_unresolved: false,
+        }
+    }
+
+    /// Parses a hash in hex, with or without a `0x` prefix
+    fn parse_hash(s: &str) -> Option<u32> {
+        let s = s.trim();
+        let s = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .unwrap_or(s);
+        if s.is_empty() || s.len() > 8 {
+            return None;
+        }
+
+        u32::from_str_radix(s, 16).ok()
+    }
+
+    fn resolve(&mut self) {
+        self.results.clear();
+        self.invalid.clear();
+
+        for token in self
+            .input
+            .split(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '|'))
+            .filter(|s| !s.is_empty())
+        {
+            let Some(hash) = Self::parse_hash(token) else {
+                self.invalid.push(token.to_string());
+                continue;
+            };
+
+            if self.results.iter().any(|r| r.hash == hash) {
+                continue;
+            }
+
+            let mut candidates = vec![];
+            if let Some(strings) = self.strings.get(&hash) {
+                candidates.extend(strings.iter().map(|s| (HashSource::Localized, s.clone())));
+            }
+
+            if let Some(strings) = self.raw_strings.get(&hash) {
+                candidates.extend(strings.iter().map(|(s, is_wordlist)| {
+                    (
+                        if *is_wordlist {
+                            HashSource::Wordlist
+                        } else {
+                            HashSource::RawString
+                        },
+                        s.clone(),
+                    )
+                }));
+            }
+
+            self.results.push(ResolvedHash { hash, candidates });
+        }
+    }
+
+    /// Formats the results as `hash|source|string`, with an empty source and string for unresolved hashes
+    fn results_csv(&self) -> String {
+        let mut csv = String::from("hash|source|string\n");
+        for r in &self.results {
+            if r.candidates.is_empty() {
+                if !self.hide_unresolved {
+                    writeln!(csv, "{:08X}||", r.hash).ok();
+                }
+                continue;
+            }
+
+            for (source, string) in &r.candidates {
+                writeln!(csv, "{:08X}|{}|{}", r.hash, source.label(), string).ok();
+            }
+        }
+
+        csv
+    }
+
+    pub fn view(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) -> Option<ViewAction> {
+        egui::SidePanel::left("hash_resolver_left_panel")
+            .resizable(true)
+            .min_width(256.0)
+            .show_inside(ui, |ui| {
+                ui.label("Paste hashes (hex, separated by whitespace, commas or newlines):");
+                if ui.button("Resolve").clicked() {
+                    self.resolve();
+                }
+
+                egui::ScrollArea::vertical()
+                    .max_width(f32::INFINITY)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut self.input)
+                                .font(egui::TextStyle::Monospace)
+                                .desired_width(f32::INFINITY)
+                                .desired_rows(24),
+                        );
+                    });
+            });
+
+        egui::CentralPanel::default().show_inside(ui, |ui| {
+            let resolved = self
+                .results
+                .iter()
+                .filter(|r| !r.candidates.is_empty())
+                .count();
+            ui.horizontal(|ui| {
+                ui.label(format!("{resolved}/{} hashes resolved", self.results.len()));
+                ui.checkbox(&mut self.hide_unresolved, "Hide unresolved");
+                if ui
+                    .add_enabled(
+                        !self.results.is_empty(),
+                        egui::Button::new("📋 Copy as CSV"),
+                    )
+                    .clicked()
+                {
+                    ctx.output_mut(|o| o.copied_text = self.results_csv());
+                }
+            });
+
+            if !self.invalid.is_empty() {
+                ui.label(
+                    RichText::new(format!(
+                        "Skipped {} invalid hashes: {}",
+                        self.invalid.len(),
+                        self.invalid.join(", ")
+                    ))
+                    .color(Color32::YELLOW),
+                );
+            }
+
+            ui.separator();
+
+            let rows: Vec<(u32, Option<&(HashSource, String)>)> = self
+                .results
+                .iter()
+                .flat_map(|r| {
+                    if r.candidates.is_empty() {
+                        if self.hide_unresolved {
+                            vec![]
+                        } else {
+                            vec![(r.hash, None)]
+                        }
+                    } else {
+                        r.candidates.iter().map(|c| (r.hash, Some(c))).collect()
+                    }
+                })
+                .collect();
+
+            TableBuilder::new(ui)
+                .striped(true)
+                .column(Column::auto().at_least(96.0))
+                .column(Column::auto().at_least(96.0))
+                .column(Column::remainder())
+                .header(20.0, |mut header| {
+                    header.col(|ui| {
+                        ui.strong("Hash");
+                    });
+                    header.col(|ui| {
+                        ui.strong("Source");
+                    });
+                    header.col(|ui| {
+                        ui.strong("String");
+                    });
+                })
+                .body(|body| {
+                    body.rows(18.0, rows.len(), |mut row| {
+                        let (hash, candidate) = rows[row.index()];
+                        row.col(|ui| {
+                            ui.monospace(format!("{hash:08X}"));
+                        });
+                        match candidate {
+                            Some((source, string)) => {
+                                row.col(|ui| {
+                                    ui.label(RichText::new(source.label()).color(source.color()));
+                                });
+                                row.col(|ui| {
+                                    if ui
+                                        .selectable_label(false, string)
+                                        .on_hover_text("Click to copy")
+                                        .clicked()
+                                    {
+                                        ui.output_mut(|o| o.copied_text = string.clone());
+                                    }
+                                });
+                            }
+                            None => {
+                                row.col(|ui| {
+                                    ui.label(RichText::new("-").weak());
+                                });
+                                row.col(|ui| {
+                                    ui.label(RichText::new("Unresolved").italics().weak());
+                                });
+                            }
+                        }
+                    });
+                });
+        });
+
+        None
+    }
+}
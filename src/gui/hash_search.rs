@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use destiny_pkg::{package::UEntryHeader, TagHash};
+use eframe::egui::{self, RichText};
+use poll_promise::Promise;
+
+use crate::gui::common::TagSelection;
+use crate::gui::tag::tag_search_results_ui;
+use crate::package_manager::package_manager;
+use crate::scanner::TagCache;
+use crate::texture::TextureCache;
+
+use super::ViewAction;
+
+/// Lists all tags whose 32-bit hash contains a partial hex string, for when only part of a hash is known
+pub struct PartialHashSearchView {
+    pub query: String,
+    cache: Arc<TagCache>,
+    /// Every entry is checked, so the search runs on a separate thread
+    results: Promise<Vec<(TagHash, UEntryHeader)>>,
+    /// Set once the results have been checked for a single match, which is opened directly
+    checked_single_result: bool,
+    selection: TagSelection,
+}
+
+impl PartialHashSearchView {
+    pub fn new(query: String, cache: Arc<TagCache>) -> Self {
+        let search_query = query.clone();
+        let results = Promise::spawn_thread("partial_hash_search", move || {
+            find_partial_hash_matches(&search_query)
+        });
+
+        Self {
+            query,
+            cache,
+            results,
+            checked_single_result: false,
+            selection: TagSelection::default(),
+        }
+    }
+
+    pub fn view(
+        &mut self,
+        _ctx: &egui::Context,
+        ui: &mut egui::Ui,
+        texture_cache: &TextureCache,
+    ) -> Option<ViewAction> {
+        ui.heading(format!("Tags matching '{}'", self.query));
+        let Some(results) = self.results.ready() else {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Searching…");
+            });
+            return None;
+        };
+
+        if !self.checked_single_result {
+            self.checked_single_result = true;
+            if let [(tag, _)] = results.as_slice() {
+                return Some(ViewAction::OpenTag(*tag));
+            }
+        }
+
+        ui.label(format!("{} tags", results.len()));
+        ui.separator();
+
+        if results.is_empty() {
+            ui.label(RichText::new("No tags found containing these digits").italics());
+            return None;
+        }
+
+        tag_search_results_ui(ui, results, &mut self.selection, &self.cache, texture_cache)
+            .map(ViewAction::OpenTag)
+    }
+}
+
+/// Every tag whose formatted hash contains `query`, with prefix matches first as that's usually what's remembered of a hash
+fn find_partial_hash_matches(query: &str) -> Vec<(TagHash, UEntryHeader)> {
+    let mut results = vec![];
+    for (pkg_id, entries) in package_manager().package_entry_index.iter() {
+        for (i, entry) in entries.iter().enumerate() {
+            let tag = TagHash::new(*pkg_id, i as u16);
+            let formatted = tag.to_string();
+            if let Some(position) = formatted.find(query) {
+                results.push((position != 0, tag, entry.clone()));
+            }
+        }
+    }
+
+    results.sort_unstable_by_key(|(not_prefix, tag, _)| (*not_prefix, tag.0));
+    results
+        .into_iter()
+        .map(|(_, tag, entry)| (tag, entry))
+        .collect()
+}
@@ -1,7 +1,8 @@
 use crate::classes::{self, get_class_by_id};
 use crate::gui::common::ResponseExt;
-use crate::gui::tag::{format_tag_entry, ExtendedScanResult};
+use crate::gui::tag::{format_tag_entry, ExtendedScanResult, ExtendedTagHash};
 use crate::package_manager::package_manager;
+use crate::settings::{settings, settings_mut};
 use crate::swap_to_ne;
 use crate::tagtypes::TagType;
 use binrw::{binread, BinReaderExt, Endian};
@@ -13,8 +14,12 @@ use eframe::egui::{
 };
 use itertools::Itertools;
 use log::warn;
+use std::cell::Cell;
 use std::io::{Cursor, Seek, SeekFrom};
 
+/// Highlight color for 64-bit tag references, to tell them apart from 32-bit ones
+const HASH64_COLOR: Color32 = Color32::from_rgb(255, 160, 60);
+
 pub struct TagHexView {
     data: Vec<u8>,
     rows: Vec<DataRow>,
@@ -24,6 +29,11 @@ pub struct TagHexView {
     detect_floats: bool,
     split_arrays: bool,
     raw_array_data: bool,
+
+    /// Offset to scroll to on the next frame
+    scroll_to_offset: Cell<Option<u64>>,
+    /// 4-byte chunk that was last jumped to, outlined until the next jump
+    highlighted_offset: Option<u64>,
 }
 
 impl TagHexView {
@@ -45,9 +55,22 @@ impl TagHexView {
             detect_floats: true,
             split_arrays: true,
             raw_array_data: false,
+            scroll_to_offset: Cell::new(None),
+            highlighted_offset: None,
         }
     }
 
+    /// Scrolls the row containing `offset` into view the next time the view is shown
+    pub fn scroll_to(&self, offset: u64) {
+        self.scroll_to_offset.set(Some(offset & !0xf));
+    }
+
+    /// Scrolls to `offset` and outlines the 4 bytes at that offset
+    pub fn jump_to(&mut self, offset: u64) {
+        self.scroll_to(offset);
+        self.highlighted_offset = Some(offset & !0x3);
+    }
+
     pub fn show(&mut self, ui: &mut Ui, scan: &ExtendedScanResult) -> Option<TagHash> {
         if self.data.len() > 1024 * 1024 * 16 {
             ui.label("Data too large to display");
@@ -58,21 +81,48 @@ impl TagHexView {
             self.array_ranges = find_all_array_ranges(&self.data);
         }
 
-        ui.checkbox(&mut self.raw_array_data, "Show raw array data");
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.raw_array_data, "Show raw array data");
+            ui.separator();
+
+            let mut settings = settings_mut();
+            ui.label("Bytes per row:");
+            for bytes_per_row in [8, 16, 32] {
+                ui.radio_value(
+                    &mut settings.hex_bytes_per_row,
+                    bytes_per_row,
+                    bytes_per_row.to_string(),
+                );
+            }
+            ui.separator();
+            ui.label("Group by:");
+            for group_size in [4, 8] {
+                ui.radio_value(
+                    &mut settings.hex_group_size,
+                    group_size,
+                    group_size.to_string(),
+                );
+            }
+            ui.separator();
+            ui.checkbox(&mut settings.hex_show_ascii, "ASCII");
+        });
         ui.separator();
 
+        egui::SidePanel::right("hexview_minimap")
+            .exact_width(24.0)
+            .resizable(false)
+            .show_inside(ui, |ui| {
+                self.minimap_ui(ui, scan);
+            });
+
         let mut open_tag = None;
         ScrollArea::vertical()
             .auto_shrink([false, false])
             .show(ui, |ui| {
                 if self.split_arrays && !self.array_ranges.is_empty() {
                     let first_array_offset = self.array_ranges[0].start as usize;
-                    open_tag = open_tag.or(self.show_row_block(
-                        ui,
-                        &self.rows[..first_array_offset / 16],
-                        0,
-                        scan,
-                    ));
+                    open_tag =
+                        open_tag.or(self.show_row_block(ui, 0..first_array_offset & !0xf, 0, scan));
 
                     for (i, array) in self.array_ranges.iter().enumerate() {
                         ui.add_space(16.0);
@@ -100,7 +150,7 @@ impl TagHexView {
                             if !self.raw_array_data && !array.pretty_rows.is_empty() {
                                 let class_size = get_class_by_id(array.class).and_then(|c| c.size);
                                 for (i, row) in array.pretty_rows.iter().enumerate() {
-                                    ui.horizontal(|ui| {
+                                    let response = ui.horizontal(|ui| {
                                         if let Some(class_size) = class_size {
                                             let offset = array.data_start as usize + i * class_size;
                                             ui.strong(format!("{:08X}:", offset));
@@ -109,12 +159,20 @@ impl TagHexView {
                                         ui.style_mut().spacing.item_spacing.x = 14.0;
                                         ui.monospace(row);
                                     });
+
+                                    if let Some(class_size) = class_size {
+                                        let offset = array.data_start as usize + i * class_size;
+                                        self.scroll_if_target(
+                                            ui,
+                                            offset..offset + class_size,
+                                            response.response.rect,
+                                        );
+                                    }
                                 }
                             } else {
                                 open_tag = open_tag.or(self.show_row_block(
                                     ui,
-                                    &self.rows
-                                        [array.data_start as usize / 16..array.end as usize / 16],
+                                    array.data_start as usize & !0xf..array.end as usize & !0xf,
                                     array.data_start as usize,
                                     scan,
                                 ));
@@ -122,139 +180,309 @@ impl TagHexView {
                         });
                     }
                 } else {
-                    open_tag = open_tag.or(self.show_row_block(ui, &self.rows, 0, scan));
+                    open_tag = open_tag.or(self.show_row_block(ui, 0..self.data.len(), 0, scan));
                 }
             });
 
         open_tag
     }
 
+    /// Shows the rows for the (16-byte aligned) data in `range`, labeled starting at `base_offset`
     #[must_use]
     fn show_row_block(
         &self,
         ui: &mut Ui,
-        rows: &[DataRow],
+        range: std::ops::Range<usize>,
         base_offset: usize,
         scan: &ExtendedScanResult,
     ) -> Option<TagHash> {
         let mut open_tag = None;
-        for (i, row) in rows.iter().enumerate() {
-            let offset = base_offset + i * 16;
-            ui.horizontal(|ui| {
-                ui.strong(format!("{:08X}:", base_offset + i * 16));
-                ui.style_mut().spacing.item_spacing.x = 14.0;
-                match row {
-                    DataRow::Raw(data) => {
-                        for (bi, b) in data.chunks_exact(4).enumerate() {
-                            let chunk_offset = offset + bi * 4;
-                            let hash = scan
-                                .file_hashes
-                                .iter()
-                                .find(|v| v.offset == chunk_offset as u64);
-                            let color = if hash.is_some() {
-                                Color32::GOLD
-                            } else {
-                                Color32::GRAY
-                            };
-
-                            let response = ui.monospace(
-                                RichText::new(format!(
-                                    "{:02X} {:02X} {:02X} {:02X}",
-                                    b[0], b[1], b[2], b[3]
-                                ))
-                                .color(color),
-                            );
-                            if let Some(e) = hash {
-                                let hash32 = e.hash.hash32();
-                                let tagline_color = e
-                                    .entry
-                                    .as_ref()
-                                    .map(|e| {
-                                        TagType::from_type_subtype(e.file_type, e.file_subtype)
-                                            .display_color()
-                                    })
-                                    .unwrap_or(Color32::GRAY);
-                                let response = response
-                                    .on_hover_text(
-                                        RichText::new(format_tag_entry(hash32, e.entry.as_ref()))
-                                            .color(tagline_color),
-                                    )
-                                    .tag_context(hash32)
-                                    .interact(Sense::click())
-                                    .on_hover_cursor(CursorIcon::PointingHand);
-
-                                if response.hovered() {
-                                    ui.painter().rect(
-                                        response.rect,
-                                        0.0,
-                                        Color32::from_white_alpha(30),
-                                        Stroke::NONE,
-                                    );
-                                }
+        let bytes_per_row = settings().hex_bytes_per_row.clamp(8, 32);
+        let row_count = range.len().div_ceil(bytes_per_row);
+        if row_count == 0 {
+            return None;
+        }
 
-                                if response.clicked() {
-                                    open_tag = Some(hash32);
-                                }
-                            }
+        let show_row_index = |ui: &mut Ui, i: usize, open_tag: &mut Option<TagHash>| {
+            let start = range.start + i * bytes_per_row;
+            let end = (start + bytes_per_row).min(range.end);
+            // Float detection works on 16-byte rows
+            let row = if bytes_per_row == 16 {
+                self.rows[start / 16]
+            } else {
+                DataRow::Raw
+            };
+            self.show_row(
+                ui,
+                row,
+                &self.data[start..end],
+                base_offset + i * bytes_per_row,
+                scan,
+                open_tag,
+            )
+        };
+
+        // The first row is always drawn so we know how tall rows are
+        let first_rect = show_row_index(ui, 0, &mut open_tag);
+        self.scroll_if_target(ui, base_offset..base_offset + bytes_per_row, first_rect);
+        let row_stride = first_rect.height() + ui.spacing().item_spacing.y;
+
+        // Only draw the rows that are within the visible area, the rest is allocated as empty space
+        let clip_rect = ui.clip_rect();
+        let visible_start = (((clip_rect.top() - first_rect.top()) / row_stride)
+            .floor()
+            .max(1.0) as usize)
+            .min(row_count);
+        let visible_end = (((clip_rect.bottom() - first_rect.top()) / row_stride)
+            .ceil()
+            .max(0.0) as usize
+            + 1)
+        .clamp(visible_start, row_count);
+
+        if let Some(target) = self.scroll_to_offset.get() {
+            let target = target as usize;
+            let row_index = target.wrapping_sub(base_offset) / bytes_per_row;
+            if target >= base_offset
+                && row_index < row_count
+                && !(visible_start..visible_end).contains(&row_index)
+            {
+                let rect = egui::Rect::from_min_size(
+                    first_rect.min + vec2(0.0, row_index as f32 * row_stride),
+                    first_rect.size(),
+                );
+                ui.scroll_to_rect(rect, Some(egui::Align::Center));
+                self.scroll_to_offset.set(None);
+            }
+        }
+
+        let skip_rows = |ui: &mut Ui, count: usize| {
+            if count > 0 {
+                ui.allocate_space(vec2(
+                    0.0,
+                    count as f32 * row_stride - ui.spacing().item_spacing.y,
+                ));
+            }
+        };
+
+        skip_rows(ui, visible_start - 1);
+        for i in visible_start..visible_end {
+            let offset = base_offset + i * bytes_per_row;
+            let rect = show_row_index(ui, i, &mut open_tag);
+            self.scroll_if_target(ui, offset..offset + bytes_per_row, rect);
+        }
+        skip_rows(ui, row_count - visible_end);
+
+        open_tag
+    }
+
+    /// `bytes` are the raw bytes of the row, which are also shown for float rows in the ASCII column
+    fn show_row(
+        &self,
+        ui: &mut Ui,
+        row: DataRow,
+        bytes: &[u8],
+        offset: usize,
+        scan: &ExtendedScanResult,
+        open_tag: &mut Option<TagHash>,
+    ) -> egui::Rect {
+        let (group_size, show_ascii) = {
+            let settings = settings();
+            (settings.hex_group_size, settings.hex_show_ascii)
+        };
+
+        ui.horizontal(|ui| {
+            ui.strong(format!("{:08X}:", offset));
+            // Groups of 8 are drawn as two 4-byte chunks with half the usual spacing in between
+            let chunk_spacing = if group_size == 8 { 7.0 } else { 14.0 };
+            ui.style_mut().spacing.item_spacing.x = chunk_spacing;
+            match row {
+                DataRow::Raw => {
+                    for (bi, b) in bytes.chunks_exact(4).enumerate() {
+                        if group_size == 8 && bi > 0 && bi % 2 == 0 {
+                            ui.add_space(14.0 - chunk_spacing);
                         }
-                    }
-                    DataRow::Float(data) => {
-                        let string = data.iter().map(|f| format!("{f:<11.6}")).join("  ");
-                        ui.monospace(string);
-                        ui.add_space(16.0);
 
-                        if data.iter().all(|&v| v >= 0.0) {
-                            let needs_normalization = data.iter().any(|&v| v > 1.0);
-                            let floats = if needs_normalization {
-                                let factor = (*data).into_iter().reduce(f32::max).unwrap();
-                                [
-                                    data[0] / factor,
-                                    data[1] / factor,
-                                    data[2] / factor,
-                                    data[3] / factor,
-                                ]
-                            } else {
-                                *data
-                            };
+                        let chunk_offset = offset + bi * 4;
+                        // 64-bit references span two chunks
+                        let hash = scan.file_hashes.iter().find(|v| {
+                            (v.offset..v.offset + v.hash.size()).contains(&(chunk_offset as u64))
+                        });
+                        let color = match hash.map(|h| &h.hash) {
+                            Some(ExtendedTagHash::Hash32(_)) => Color32::GOLD,
+                            Some(ExtendedTagHash::Hash64(_)) => HASH64_COLOR,
+                            None => Color32::GRAY,
+                        };
 
-                            let color =
-                                Rgba::from_rgb(floats[0].abs(), floats[1].abs(), floats[2].abs());
+                        let response = ui.monospace(
+                            RichText::new(format!(
+                                "{:02X} {:02X} {:02X} {:02X}",
+                                b[0], b[1], b[2], b[3]
+                            ))
+                            .color(color),
+                        );
+                        if self.highlighted_offset == Some(chunk_offset as u64) {
+                            ui.painter().rect_stroke(
+                                response.rect.expand(2.0),
+                                2.0,
+                                Stroke::new(1.5, Color32::LIGHT_BLUE),
+                            );
+                        }
 
-                            let (response, painter) =
-                                ui.allocate_painter(vec2(16.0, 16.0), Sense::hover());
+                        if let Some(e) = hash {
+                            let hash32 = e.hash.hash32();
+                            let tagline_color = e
+                                .entry
+                                .as_ref()
+                                .map(|e| {
+                                    TagType::from_type_subtype(e.file_type, e.file_subtype)
+                                        .display_color()
+                                })
+                                .unwrap_or(Color32::GRAY);
+                            let mut tagline = format_tag_entry(hash32, e.entry.as_ref());
+                            if let ExtendedTagHash::Hash64(h) = &e.hash {
+                                tagline = if hash32 == TagHash::NONE {
+                                    format!("{h} (unresolved 64-bit reference)")
+                                } else {
+                                    format!("{h} → {tagline}")
+                                };
+                            }
+
+                            let response = response
+                                .on_hover_text(RichText::new(tagline).color(tagline_color))
+                                .tag_context(hash32)
+                                .interact(Sense::click())
+                                .on_hover_cursor(CursorIcon::PointingHand);
+
+                            if response.hovered() {
+                                ui.painter().rect(
+                                    response.rect,
+                                    0.0,
+                                    Color32::from_white_alpha(30),
+                                    Stroke::NONE,
+                                );
+                            }
 
-                            painter.rect_filled(response.rect, 0.0, color);
+                            if response.clicked() && hash32 != TagHash::NONE {
+                                *open_tag = Some(hash32);
+                            }
                         }
                     }
                 }
-
-                if let Some(bytes) = row.as_raw() {
+                DataRow::Float(data) => {
+                    let string = data.iter().map(|f| format!("{f:<11.6}")).join("  ");
+                    ui.monospace(string);
                     ui.add_space(16.0);
-                    let (_response, painter) =
-                        ui.allocate_painter(vec2(16.0 * 16.0, 16.0), Sense::hover());
 
-                    ui.style_mut().spacing.item_spacing.x = 4.0;
-                    for (i, &b) in bytes.iter().enumerate() {
-                        let (c, color) = if b.is_ascii_graphic() {
-                            (b as char, Color32::from_rgb(90, 120, 255))
+                    if data.iter().all(|&v| v >= 0.0) {
+                        let needs_normalization = data.iter().any(|&v| v > 1.0);
+                        let floats = if needs_normalization {
+                            let factor = data.into_iter().reduce(f32::max).unwrap();
+                            [
+                                data[0] / factor,
+                                data[1] / factor,
+                                data[2] / factor,
+                                data[3] / factor,
+                            ]
                         } else {
-                            ('.', Color32::DARK_GRAY)
+                            data
                         };
 
-                        let pos = painter.clip_rect().min + vec2(i as f32 * 12.0, 0.0);
-                        painter.text(
-                            pos,
-                            egui::Align2::LEFT_TOP,
-                            c.to_string(),
-                            egui::FontId::monospace(12.0),
-                            color,
-                        );
+                        let color =
+                            Rgba::from_rgb(floats[0].abs(), floats[1].abs(), floats[2].abs());
+
+                        let (response, painter) =
+                            ui.allocate_painter(vec2(16.0, 16.0), Sense::hover());
+
+                        painter.rect_filled(response.rect, 0.0, color);
                     }
                 }
-            });
+            }
+
+            if show_ascii && matches!(row, DataRow::Raw) {
+                ui.add_space(16.0);
+                let (_response, painter) =
+                    ui.allocate_painter(vec2(16.0 * bytes.len() as f32, 16.0), Sense::hover());
+
+                ui.style_mut().spacing.item_spacing.x = 4.0;
+                for (i, &b) in bytes.iter().enumerate() {
+                    let (c, color) = if b.is_ascii_graphic() {
+                        (b as char, Color32::from_rgb(90, 120, 255))
+                    } else {
+                        ('.', Color32::DARK_GRAY)
+                    };
+
+                    let pos = painter.clip_rect().min + vec2(i as f32 * 12.0, 0.0);
+                    painter.text(
+                        pos,
+                        egui::Align2::LEFT_TOP,
+                        c.to_string(),
+                        egui::FontId::monospace(12.0),
+                        color,
+                    );
+                }
+            }
+        })
+        .response
+        .rect
+    }
+
+    fn scroll_if_target(&self, ui: &mut Ui, range: std::ops::Range<usize>, rect: egui::Rect) {
+        if let Some(target) = self.scroll_to_offset.get() {
+            if range.contains(&(target as usize)) {
+                ui.scroll_to_rect(rect, Some(egui::Align::Center));
+                self.scroll_to_offset.set(None);
+            }
         }
+    }
 
-        open_tag
+    /// Draws an overview of the tag data, marking where references, arrays and strings are
+    fn minimap_ui(&self, ui: &mut Ui, scan: &ExtendedScanResult) {
+        let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
+        let rect = response.rect;
+        let data_len = self.data.len().max(1) as f32;
+        let offset_to_y = |offset: u64| rect.top() + (offset as f32 / data_len) * rect.height();
+
+        painter.rect_filled(rect, 0.0, Color32::from_gray(20));
+
+        for array in &self.array_ranges {
+            let color = if array.label.is_some() {
+                // Raw string data
+                Color32::from_rgb(60, 160, 60)
+            } else {
+                Color32::from_rgb(50, 80, 160)
+            };
+
+            let array_rect = egui::Rect::from_x_y_ranges(
+                rect.x_range(),
+                offset_to_y(array.start)
+                    ..=offset_to_y(array.end).max(offset_to_y(array.start) + 1.0),
+            );
+            painter.rect_filled(array_rect.shrink2(vec2(4.0, 0.0)), 0.0, color);
+        }
+
+        for hash in &scan.file_hashes {
+            if hash.offset == u64::MAX {
+                continue;
+            }
+
+            let color = hash
+                .entry
+                .as_ref()
+                .map(|e| TagType::from_type_subtype(e.file_type, e.file_subtype).display_color())
+                .unwrap_or(Color32::GRAY);
+
+            let y = offset_to_y(hash.offset);
+            painter.hline(rect.x_range(), y, Stroke::new(1.0, color));
+        }
+
+        if let Some(pos) = response.interact_pointer_pos() {
+            if response.clicked() || response.dragged() {
+                let offset = ((pos.y - rect.top()) / rect.height()).clamp(0.0, 1.0) * data_len;
+                self.scroll_to(offset as u64);
+            }
+        }
+
+        response.on_hover_text("Click to jump to offset");
     }
 }
 
@@ -266,22 +494,14 @@ enum DataViewMode {
     U32,
 }
 
+/// How a row is displayed, the data itself is read from `TagHexView::data`
 #[derive(Clone, Copy)]
 enum DataRow {
-    Raw([u8; 16]),
+    Raw,
     Float([f32; 4]),
     // U32([u32; 4]),
 }
 
-impl DataRow {
-    fn as_raw(&self) -> Option<&[u8; 16]> {
-        match self {
-            DataRow::Raw(data) => Some(data),
-            _ => None,
-        }
-    }
-}
-
 impl From<[u8; 16]> for DataRow {
     fn from(data: [u8; 16]) -> Self {
         let from_xe_bytes = if package_manager().version.endian() == Endian::Big {
@@ -307,7 +527,7 @@ impl From<[u8; 16]> for DataRow {
         if all_valid_floats {
             DataRow::Float(floats)
         } else {
-            DataRow::Raw(data)
+            DataRow::Raw
         }
     }
 }
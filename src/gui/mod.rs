@@ -2,23 +2,33 @@
 mod audio;
 #[cfg(feature = "audio")]
 mod audio_list;
+mod background_task;
+mod class_instances;
 mod common;
+mod curves;
 mod external_file;
+mod hash_resolver;
+mod hash_search;
 mod hexview;
 mod named_tags;
+mod orphans;
+mod package_file;
 mod packages;
 mod raw_strings;
+mod session;
+mod statistics;
 mod strings;
 mod style;
 mod tag;
+mod texture_compare;
 mod texturelist;
+mod textview;
 
 use std::cell::RefCell;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::mpsc::Receiver;
 use std::sync::Arc;
-use std::time::Instant;
 
 use destiny_pkg::TagHash;
 use eframe::egui::{PointerButton, TextEdit, Widget};
@@ -30,33 +40,51 @@ use eframe::{
 };
 use egui_notify::Toasts;
 use lazy_static::lazy_static;
-use log::info;
+use log::{error, info, warn};
 use notify::Watcher;
 use parking_lot::Mutex;
 use poll_promise::Promise;
 use rustc_hash::FxHashSet;
+use serde::{Deserialize, Serialize};
 use strings::StringViewVariant;
 
+use self::class_instances::ClassInstancesView;
+use self::hash_resolver::HashResolverView;
+use self::hash_search::PartialHashSearchView;
 use self::named_tags::NamedTagView;
+use self::orphans::OrphansView;
+use self::package_file::PackageFileView;
 use self::packages::PackagesView;
 use self::raw_strings::RawStringsView;
+use self::statistics::StatisticsView;
 use self::strings::StringsView;
 use self::tag::TagView;
 use self::texturelist::TexturesView;
+
+pub use self::tag::{traversal_text, TraversalDirection};
 use crate::classes;
 use crate::gui::external_file::ExternalFileScanView;
-use crate::gui::tag::TagHistory;
-use crate::scanner::{fnv1, ScannerContext};
-use crate::text::RawStringHashCache;
+use crate::gui::tag::{tag_missing_reason, TagHistory};
+use crate::known_tags;
+use crate::scanner::Scanner;
+use crate::settings::{self, settings_mut, Theme};
+use crate::tagtypes::TagType;
+use crate::text::{self, RawStringHashCache};
 use crate::texture::TextureCache;
+use crate::util::{
+    parse_split_tag, parse_tag_box_input, parse_tag_hash, PackageLookup, ParsedTag, TagBoxInput,
+};
 use crate::{
     package_manager::package_manager,
     scanner,
-    scanner::{load_tag_cache, scanner_progress, ScanStatus, TagCache},
-    text::{create_stringmap, StringCache},
+    scanner::{
+        load_tag_cache, package_dir_timestamp, package_set_fingerprint, rebuild_tag_cache,
+        scanner_progress, ScanResult, ScanStatus, TagCache,
+    },
+    text::{create_stringmap, StringCache, StringSourceMap},
 };
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum Panel {
     Tag,
     NamedTags,
@@ -67,7 +95,31 @@ pub enum Panel {
     Strings,
     RawStrings,
     RawStringHashes,
-    ExternalFile,
+    Orphans,
+    HashResolver,
+    Statistics,
+    /// Index into `QuickTagApp::external_file_views`
+    ExternalFile(usize),
+    ClassInstances,
+    PackageFile,
+    PartialHashSearch,
+}
+
+impl Panel {
+    /// Panels that show nothing useful until the tag cache has loaded
+    fn requires_cache(&self) -> bool {
+        matches!(
+            self,
+            Panel::Strings
+                | Panel::RawStrings
+                | Panel::RawStringHashes
+                | Panel::Orphans
+                | Panel::HashResolver
+                | Panel::Statistics
+                | Panel::ClassInstances
+                | Panel::PartialHashSearch
+        )
+    }
 }
 
 lazy_static! {
@@ -75,11 +127,21 @@ lazy_static! {
 }
 
 pub struct QuickTagApp {
-    scanner_context: ScannerContext,
-    cache_load: Option<Promise<TagCache>>,
+    scanner: Scanner,
+    cache_load: Option<Promise<anyhow::Result<TagCache>>>,
     cache: Arc<TagCache>,
+    /// Package directory timestamp and package set fingerprint at the time the cache was loaded
+    current_package_state: (u64, u64),
+    /// Tag to open once the cache has finished loading
+    open_tag_on_load: Option<TagHash>,
+    /// Directory to dump the string caches to once they've been created
+    dump_strings_on_load: Option<PathBuf>,
+    /// Open tag and its scan result from before the cache was regenerated, to show what changed
+    scan_before_regen: Option<(TagHash, ScanResult)>,
     tag_history: Rc<RefCell<TagHistory>>,
     strings: Arc<StringCache>,
+    /// String container tags each localized string was read from
+    string_sources: Arc<StringSourceMap>,
     raw_strings: Arc<RawStringHashCache>,
 
     texture_cache: TextureCache,
@@ -92,7 +154,10 @@ pub struct QuickTagApp {
     open_panel: Panel,
 
     tag_view: Option<TagView>,
-    external_file_view: Option<ExternalFileScanView>,
+    external_file_views: Vec<ExternalFileScanView>,
+    class_instances_view: Option<ClassInstancesView>,
+    package_file_view: Option<PackageFileView>,
+    partial_hash_search_view: Option<PartialHashSearchView>,
 
     named_tags_view: NamedTagView,
     packages_view: PackagesView,
@@ -102,6 +167,9 @@ pub struct QuickTagApp {
     strings_view: StringsView,
     raw_strings_view: RawStringsView,
     raw_string_hashes_view: StringsView,
+    orphans_view: OrphansView,
+    statistics_view: StatisticsView,
+    hash_resolver_view: HashResolverView,
 
     schemafile_watcher: notify::RecommendedWatcher,
     schemafile_update_rx: Receiver<Result<notify::Event, notify::Error>>,
@@ -111,7 +179,11 @@ pub struct QuickTagApp {
 
 impl QuickTagApp {
     /// Called once before the first frame.
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(
+        cc: &eframe::CreationContext<'_>,
+        open_tag_on_load: Option<TagHash>,
+        dump_strings_on_load: Option<PathBuf>,
+    ) -> Self {
         let mut fonts = egui::FontDefinitions::default();
         fonts.font_data.insert(
             "Destiny_Keys".into(),
@@ -126,7 +198,15 @@ impl QuickTagApp {
 
         cc.egui_ctx.set_fonts(fonts);
 
-        let strings = Arc::new(create_stringmap().unwrap());
+        settings::load_settings(cc.storage);
+
+        let (strings, string_sources) = create_stringmap(
+            &package_manager(),
+            &settings::settings().string_languages.clone(),
+        )
+        .unwrap();
+        let strings = Arc::new(strings);
+        let string_sources = Arc::new(string_sources);
         let texture_cache = TextureCache::new(cc.wgpu_render_state.clone().unwrap());
 
         let (tx, rx) = std::sync::mpsc::channel();
@@ -139,17 +219,40 @@ impl QuickTagApp {
             .unwrap();
 
         classes::load_schemafile();
+        known_tags::load_known_tags();
+
+        if let Some(warning) = scanner::check_version_endianness(&package_manager()) {
+            warn!("{warning}");
+            TOASTS.lock().warning(warning).duration(None);
+        }
 
         QuickTagApp {
-            scanner_context: scanner::create_scanner_context(&package_manager())
-                .expect("Failed to create scanner context"),
+            scanner: scanner::scanner_from_settings().expect("Failed to create scanner context"),
             cache_load: Some(Promise::spawn_thread("load_cache", move || {
-                load_tag_cache()
+                // Without a cache there's nothing to fall back to
+                Ok(load_tag_cache().unwrap_or_else(|e| {
+                    error!("Failed to load tag cache: {e:?}");
+                    native_dialog::MessageDialog::new()
+                        .set_type(native_dialog::MessageType::Error)
+                        .set_title("Failed to load tag cache")
+                        .set_text(&e.to_string())
+                        .show_alert()
+                        .ok();
+
+                    std::process::exit(21);
+                }))
             })),
             tag_history: Rc::new(RefCell::new(TagHistory::default())),
             cache: Default::default(),
+            current_package_state: (0, 0),
+            open_tag_on_load,
+            dump_strings_on_load,
+            scan_before_regen: None,
             tag_view: None,
-            external_file_view: None,
+            external_file_views: vec![],
+            class_instances_view: None,
+            package_file_view: None,
+            partial_hash_search_view: None,
             tag_input: String::new(),
             tag_split: false,
             tag_split_input: (String::new(), String::new()),
@@ -164,17 +267,23 @@ impl QuickTagApp {
             audio_view: audio_list::AudioView::new(),
             strings_view: StringsView::new(
                 strings.clone(),
+                string_sources.clone(),
                 Default::default(),
                 StringViewVariant::LocalizedStrings,
             ),
             raw_strings_view: RawStringsView::new(Default::default()),
             raw_string_hashes_view: StringsView::new(
+                Arc::new(Default::default()),
                 Arc::new(Default::default()),
                 Default::default(),
                 StringViewVariant::RawWordlist,
             ),
+            orphans_view: OrphansView::new(Default::default()),
+            statistics_view: StatisticsView::new(Default::default()),
+            hash_resolver_view: HashResolverView::new(strings.clone(), Default::default()),
 
             strings,
+            string_sources,
             raw_strings: Default::default(),
 
             schemafile_watcher,
@@ -186,106 +295,81 @@ impl QuickTagApp {
 }
 
 impl eframe::App for QuickTagApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        settings::save_settings(storage);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        if self.schemafile_update_rx.try_recv().is_ok() {
-            classes::load_schemafile();
-            info!("Reloaded schema file");
+        classes::reset_schemafile_refreshed();
+        if self.schemafile_update_rx.try_iter().count() > 0 {
+            self.reload_schema();
         }
 
-        ctx.set_style(style::style());
-        let mut is_loading_cache = false;
-        if let Some(cache_promise) = self.cache_load.as_ref() {
-            if cache_promise.poll().is_pending() {
-                {
-                    let painter = ctx.layer_painter(egui::LayerId::background());
-                    painter.rect_filled(
-                        egui::Rect::EVERYTHING,
-                        Rounding::default(),
-                        Color32::from_black_alpha(127),
-                    );
-                }
-                egui::Window::new("Loading cache")
-                    .collapsible(false)
-                    .resizable(false)
-                    .title_bar(false)
-                    .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
-                    .show(ctx, |ui| {
-                        let progress = if let ScanStatus::Scanning {
-                            current_package,
-                            total_packages,
-                        } = scanner_progress()
-                        {
-                            current_package as f32 / total_packages as f32
-                        } else {
-                            0.9999
-                        };
-
-                        ui.add(
-                            egui::ProgressBar::new(progress)
-                                .animate(true)
-                                .text(scanner_progress().to_string()),
-                        );
-                    });
-
-                // 
-
-                is_loading_cache = true;
+        ctx.set_style(style::style(settings::settings().theme));
+        let is_loading_cache = self
+            .cache_load
+            .as_ref()
+            .is_some_and(|p| p.poll().is_pending());
+        // Reading an existing cache is fast, so the UI stays usable for anything that doesn't need the cache.
+        // Generating one blocks the UI
+        let is_generating_cache = is_loading_cache
+            && !matches!(
+                scanner_progress(),
+                ScanStatus::None | ScanStatus::LoadingCache
+            );
+        if is_generating_cache {
+            {
+                let painter = ctx.layer_painter(egui::LayerId::background());
+                painter.rect_filled(
+                    egui::Rect::EVERYTHING,
+                    Rounding::default(),
+                    Color32::from_black_alpha(127),
+                );
             }
+            egui::Window::new("Loading cache")
+                .collapsible(false)
+                .resizable(false)
+                .title_bar(false)
+                .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+                .show(ctx, |ui| {
+                    let progress = if let ScanStatus::Scanning {
+                        current_package,
+                        total_packages,
+                    } = scanner_progress()
+                    {
+                        current_package as f32 / total_packages as f32
+                    } else {
+                        0.9999
+                    };
+
+                    ui.add(
+                        egui::ProgressBar::new(progress)
+                            .animate(true)
+                            .text(scanner_progress().to_string()),
+                    );
+                });
         }
 
-        if self
-            .cache_load
-            .as_ref()
-            .map(|v| v.poll().is_ready())
-            .unwrap_or_default()
-        {
-            let c = self.cache_load.take().unwrap();
-            let cache = c.try_take().unwrap_or_default();
+        let is_running_task = !is_generating_cache && background_task::show_tasks(ctx);
+
+        if let Some(cache) = self.take_loaded_cache() {
             self.cache = Arc::new(cache);
+            self.current_package_state = (
+                package_dir_timestamp(&package_manager().package_dir),
+                package_set_fingerprint(&package_manager()),
+            );
 
             self.strings_view = StringsView::new(
                 self.strings.clone(),
+                self.string_sources.clone(),
                 self.cache.clone(),
                 StringViewVariant::LocalizedStrings,
             );
             self.raw_strings_view = RawStringsView::new(self.cache.clone());
+            self.orphans_view = OrphansView::new(self.cache.clone());
+            self.statistics_view = StatisticsView::new(self.cache.clone());
 
-            let mut new_rsh_cache = RawStringHashCache::default();
-            for s in self
-                .cache
-                .hashes
-                .iter()
-                .flat_map(|(_, sc)| sc.raw_strings.iter().cloned())
-            {
-                let h = fnv1(s.as_bytes());
-                let entry = new_rsh_cache.entry(h).or_default();
-                if entry.iter().any(|(s2, _)| s2 == &s) {
-                    continue;
-                }
-
-                entry.push((s, false));
-            }
-
-            #[cfg(feature = "wordlist")]
-            {
-                const WORDLIST: &str = include_str!("../../wordlist.txt");
-                let load_start = Instant::now();
-                for s in WORDLIST.lines() {
-                    let s = s.to_string();
-                    let h = fnv1(s.as_bytes());
-                    let entry = new_rsh_cache.entry(h).or_default();
-                    if entry.iter().any(|(s2, _)| s2 == &s) {
-                        continue;
-                    }
-
-                    entry.push((s, true));
-                }
-                info!(
-                    "Loading {} strings from embedded wordlist in {}ms",
-                    WORDLIST.lines().count(),
-                    load_start.elapsed().as_millis()
-                );
-            }
+            let new_rsh_cache = text::create_raw_string_hash_cache(&self.cache);
 
             let mut filtered_wordlist_hashes: StringCache = Default::default();
             let found_hashes: FxHashSet<u32> = self
@@ -315,25 +399,58 @@ impl eframe::App for QuickTagApp {
 
             self.raw_string_hashes_view = StringsView::new(
                 Arc::new(filtered_wordlist_hashes),
+                Arc::new(Default::default()),
                 self.cache.clone(),
                 StringViewVariant::RawWordlist,
             );
 
-            // // Dump all raw strings to a csv file
-            // if let Ok(mut f) = std::fs::File::create("raw_strings.csv") {
-            //     writeln!(f, "hash|string|is_wordlist").unwrap();
-            //     for (hash, strings) in new_rsh_cache.iter() {
-            //         for (string, is_wordlist) in strings {
-            //             writeln!(f, "{:08X}|{}|{}", hash, string, is_wordlist).unwrap();
-            //         }
-            //     }
-            // }
-
             self.raw_strings = Arc::new(new_rsh_cache);
+            self.hash_resolver_view =
+                HashResolverView::new(self.strings.clone(), self.raw_strings.clone());
+
+            if let Some(dir) = self.dump_strings_on_load.take() {
+                self.dump_strings(&dir);
+            }
+
+            if let Some((tag, old_scan)) = self.scan_before_regen.take() {
+                self.open_tag(tag, false);
+                if let Some(tag_view) = &mut self.tag_view {
+                    tag_view.set_scan_before_regen(&old_scan);
+                }
+            }
+
+            if let Some(tag) = self.open_tag_on_load.take() {
+                self.tag_input = tag.to_string();
+                self.open_tag(tag, true);
+            }
+        }
+
+        if !is_generating_cache && !is_running_task && !ctx.wants_keyboard_input() {
+            let pasted = ctx.input(|i| {
+                i.events.iter().find_map(|e| match e {
+                    egui::Event::Paste(s) => Some(s.clone()),
+                    _ => None,
+                })
+            });
+
+            if let Some(pasted) = pasted {
+                if !self.open_tag_from_text(&pasted) {
+                    TOASTS
+                        .lock()
+                        .info("Clipboard does not contain a valid tag hash");
+                }
+            }
+        }
+
+        if !is_generating_cache && !is_running_task {
+            let dropped_files = ctx.input(|i| i.raw.dropped_files.clone());
+            for file in dropped_files {
+                self.handle_dropped_file(file);
+            }
         }
 
         egui::CentralPanel::default().show(ctx, |ui| {
-            ui.add_enabled_ui(!is_loading_cache, |ui| {
+            ui.add_enabled_ui(!is_generating_cache && !is_running_task, |ui| {
                 egui::menu::bar(ui, |ui| {
                     ui.menu_button("File", |ui| {
                         if ui.button("Scan file").clicked() {
@@ -341,28 +458,207 @@ impl eframe::App for QuickTagApp {
                                 .add_filter("All files", &["*"])
                                 .show_open_single_file()
                             {
-                                let filename = selected_file
-                                    .file_name()
-                                    .unwrap()
-                                    .to_string_lossy()
-                                    .to_string();
-                                let data = std::fs::read(&selected_file).unwrap();
-                                self.external_file_view = Some(ExternalFileScanView::new(
-                                    filename,
-                                    &self.scanner_context,
-                                    &data,
-                                ));
-
-                                self.open_panel = Panel::ExternalFile;
+                                self.open_external_file(&selected_file);
+                            }
+
+                            ui.close_menu();
+                        }
+
+                        if ui
+                            .button("Open package")
+                            .on_hover_text("Browse the entries of a single package file")
+                            .clicked()
+                        {
+                            if let Ok(Some(selected_file)) = native_dialog::FileDialog::new()
+                                .add_filter("Package files", &["pkg"])
+                                .show_open_single_file()
+                            {
+                                match PackageFileView::open(&selected_file) {
+                                    Ok(view) => {
+                                        self.package_file_view = Some(view);
+                                        self.open_panel = Panel::PackageFile;
+                                    }
+                                    Err(e) => {
+                                        error!("Failed to open package {selected_file:?}: {e:?}");
+                                        TOASTS.lock().error(format!("Failed to open package: {e}"));
+                                    }
+                                }
+                            }
+
+                            ui.close_menu();
+                        }
+
+                        if ui
+                            .button("Reload schema")
+                            .on_hover_text("Reloads schema.txt and refreshes the open tag")
+                            .clicked()
+                        {
+                            self.reload_schema();
+                            ui.close_menu();
+                        }
+
+                        if ui
+                            .button("Regenerate cache")
+                            .on_hover_text("Rescans all packages. The open tag is reopened afterwards with its changed references highlighted")
+                            .clicked()
+                        {
+                            self.regenerate_cache();
+                            ui.close_menu();
+                        }
+
+                        if ui
+                            .button("Reload known tags")
+                            .on_hover_text(format!(
+                                "Reloads tag labels from {}",
+                                known_tags::known_tags_path().display()
+                            ))
+                            .clicked()
+                        {
+                            known_tags::load_known_tags();
+                            ui.close_menu();
+                        }
+
+                        if ui
+                            .button("Export raw string hashes")
+                            .on_hover_text("Writes raw_strings.csv and raw_strings.json, including whether each string came from the wordlist")
+                            .clicked()
+                        {
+                            match text::export_raw_string_hashes(&self.raw_strings) {
+                                Ok(()) => {
+                                    TOASTS.lock().success("Raw string hashes exported");
+                                }
+                                Err(e) => {
+                                    error!("Failed to export raw string hashes: {e:?}");
+                                }
+                            }
+
+                            ui.close_menu();
+                        }
+
+                        if ui
+                            .button("Dump all strings…")
+                            .on_hover_text("Writes the localized strings and raw string hashes (including the wordlist) as CSV and JSON to a directory")
+                            .clicked()
+                        {
+                            if let Ok(Some(dir)) =
+                                native_dialog::FileDialog::new().show_open_single_dir()
+                            {
+                                self.dump_strings(&dir);
+                            }
+
+                            ui.close_menu();
+                        }
+
+                        ui.separator();
+
+                        if ui
+                            .button("Save session")
+                            .on_hover_text("Saves the open tags, files, panel and known tag labels to a JSON file that can be shared")
+                            .clicked()
+                        {
+                            if let Ok(Some(path)) = native_dialog::FileDialog::new()
+                                .add_filter("Session files", &["json"])
+                                .show_save_single_file()
+                            {
+                                self.save_session(&path);
+                            }
+
+                            ui.close_menu();
+                        }
+
+                        if ui.button("Load session").clicked() {
+                            if let Ok(Some(path)) = native_dialog::FileDialog::new()
+                                .add_filter("Session files", &["json"])
+                                .show_open_single_file()
+                            {
+                                self.load_session(&path);
                             }
 
                             ui.close_menu();
                         }
                     });
 
-                    // ui.with_layout(egui::Layout::right_to_left(egui::Align::Max), |ui| {
-                    //     egui::global_dark_light_mode_switch(ui);
-                    // });
+                    ui.menu_button("Settings", |ui| {
+                        ui.checkbox(
+                            &mut settings_mut().traverse_skipped_classes,
+                            "Traverse skipped classes by default",
+                        )
+                        .on_hover_text(
+                            "Follow classes marked with @skip_traversal (eg. localized strings) when traversing and searching",
+                        );
+                        let mut texture_settings_changed = ui
+                            .checkbox(
+                                &mut settings_mut().premultiply_alpha,
+                                "Premultiply texture alpha",
+                            )
+                            .changed();
+                        texture_settings_changed |= ui
+                            .checkbox(
+                                &mut settings_mut().rgba8_as_srgb,
+                                "Interpret rgba8 textures as sRGB",
+                            )
+                            .on_hover_text("Non-sRGB rgba8 textures appear to be sRGB in practice. Disable this if they look too dark")
+                            .changed();
+                        if texture_settings_changed {
+                            self.texture_cache.clear();
+                            if let Some(tag_view) = &mut self.tag_view {
+                                tag_view.apply_texture_settings();
+                            }
+                        }
+                        ui.checkbox(
+                            &mut settings_mut().open_tags_in_hex_view,
+                            "Open tags in hex view",
+                        );
+                        ui.checkbox(
+                            &mut settings_mut().hex_scroll_to_first_reference,
+                            "Scroll hex view to first reference",
+                        )
+                        .on_hover_text("Applies to newly opened tags");
+                        ui.horizontal(|ui| {
+                            ui.label("Max tag size to scan:");
+                            let mut max_size_mib = settings::settings().scan_max_tag_size / (1024 * 1024);
+                            if ui
+                                .add(
+                                    egui::DragValue::new(&mut max_size_mib)
+                                        .range(0..=4095)
+                                        .suffix(" MiB"),
+                                )
+                                .on_hover_text("Larger tags are only recorded with their size, without their references. 0 scans everything.\nThe cache is rebuilt with the new limit on the next start, or when regenerating it")
+                                .changed()
+                            {
+                                settings_mut().scan_max_tag_size = max_size_mib * 1024 * 1024;
+                            }
+                        });
+
+                        ui.separator();
+                        ui.menu_button("Tag type colors", tag_type_colors_ui);
+                        ui.menu_button("String languages", string_languages_ui);
+                        ui.horizontal(|ui| {
+                            ui.label("Theme:");
+                            let mut settings = settings_mut();
+                            ui.radio_value(&mut settings.theme, Theme::Dark, "Dark");
+                            ui.radio_value(&mut settings.theme, Theme::Light, "Light");
+                        });
+                    });
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if is_loading_cache {
+                            ui.spinner();
+                            ui.weak("Loading cache");
+                        } else {
+                            self.cache_status_ui(ui);
+                        }
+
+                        let loading_textures = self.texture_cache.loading_texture_count();
+                        if loading_textures > 0 {
+                            ui.separator();
+                            ui.spinner();
+                            ui.weak(format!(
+                                "Loading {loading_textures} texture{}",
+                                if loading_textures == 1 { "" } else { "s" }
+                            ));
+                        }
+                    });
                 });
                 ui.separator();
 
@@ -386,7 +682,7 @@ impl eframe::App for QuickTagApp {
                             && ui.input(|i| i.key_pressed(egui::Key::Enter));
                     } else {
                         submitted |= TextEdit::singleline(&mut self.tag_input)
-                            .hint_text("32/64-bit hex tag")
+                            .hint_text("32/64-bit hex tag, partial hash or class name")
                             .desired_width(128. + 8.)
                             .ui(ui)
                             .lost_focus()
@@ -394,40 +690,64 @@ impl eframe::App for QuickTagApp {
                     }
 
                     if ui.button("Open").clicked() || submitted {
-                        let tag_input_trimmed = self.tag_input.trim();
-                        let tag = if self.tag_split {
-                            let pkg_id = self.tag_split_input.0.trim();
-                            let entry_index = self.tag_split_input.1.trim();
-
-                            if pkg_id.is_empty() || entry_index.is_empty() {
-                                TagHash::NONE
-                            } else {
-                                let pkg_id: u16 =
-                                    u16::from_str_radix(pkg_id, 16).unwrap_or_default();
-                                let entry_index = str::parse(entry_index).unwrap_or_default();
-                                TagHash::new(pkg_id, entry_index)
-                            }
-                        } else if tag_input_trimmed.len() >= 16 {
-                            let hash =
-                                u64::from_str_radix(tag_input_trimmed, 16).unwrap_or_default();
-                            if let Some(t) = package_manager().hash64_table.get(&u64::from_be(hash))
+                        let input = if self.tag_split {
+                            TagBoxInput::Tag(ParsedTag::Tag32(parse_split_tag(
+                                &self.tag_split_input.0,
+                                &self.tag_split_input.1,
+                            )))
+                        } else {
+                            parse_tag_box_input(&self.tag_input, &PackageLookup)
+                        };
+
+                        let tag = match input {
+                            TagBoxInput::Class(_) | TagBoxInput::PartialHash(_)
+                                if is_loading_cache =>
                             {
-                                t.hash32
-                            } else {
-                                TagHash::NONE
+                                TOASTS
+                                    .lock()
+                                    .info("Searching is available once the cache has loaded");
+                                None
+                            }
+                            TagBoxInput::Class(class) => {
+                                self.class_instances_view =
+                                    Some(ClassInstancesView::new(class, self.cache.clone()));
+                                self.open_panel = Panel::ClassInstances;
+                                None
+                            }
+                            TagBoxInput::PartialHash(query) => {
+                                // A single match is opened by the view once the search is done
+                                self.partial_hash_search_view =
+                                    Some(PartialHashSearchView::new(query, self.cache.clone()));
+                                self.open_panel = Panel::PartialHashSearch;
+                                None
+                            }
+                            TagBoxInput::Tag(parsed) => {
+                                match parsed {
+                                    ParsedTag::Ambiguous { tag, flipped } => {
+                                        TOASTS.lock().info(format!(
+                                            "Both {tag} and its byte-flipped form {flipped} exist, opening {tag}"
+                                        ));
+                                    }
+                                    ParsedTag::Flipped(tag) => {
+                                        TOASTS
+                                            .lock()
+                                            .info(format!("Opening byte-flipped hash as {tag}"));
+                                    }
+                                    ParsedTag::Tag64 { hash64, tag: None } => {
+                                        TOASTS
+                                            .lock()
+                                            .error(format!("64-bit tag {hash64} was not found"));
+                                    }
+                                    _ => {}
+                                }
+
+                                Some(parsed.tag().unwrap_or(TagHash::NONE))
                             }
-                        } else if tag_input_trimmed.len() > 8
-                            && tag_input_trimmed.chars().all(char::is_numeric)
-                        {
-                            let hash = tag_input_trimmed.parse().unwrap_or_default();
-                            TagHash(hash)
-                        } else {
-                            let hash =
-                                u32::from_str_radix(tag_input_trimmed, 16).unwrap_or_default();
-                            TagHash(u32::from_be(hash))
                         };
 
-                        self.open_tag(tag, true);
+                        if let Some(tag) = tag {
+                            self.open_tag(tag, true);
+                        }
                     }
 
                     ui.checkbox(&mut self.tag_split, "Split pkg/entry");
@@ -447,42 +767,70 @@ impl eframe::App for QuickTagApp {
                         Panel::RawStringHashes,
                         "Wordlist Hashes",
                     );
-                    if let Some(external_file_view) = &self.external_file_view {
+                    ui.selectable_value(&mut self.open_panel, Panel::Orphans, "Orphans");
+                    ui.selectable_value(
+                        &mut self.open_panel,
+                        Panel::HashResolver,
+                        "Hash Resolver",
+                    );
+                    ui.selectable_value(&mut self.open_panel, Panel::Statistics, "Statistics");
+                    let mut close_file = None;
+                    for (i, external_file_view) in self.external_file_views.iter().enumerate() {
                         ui.selectable_value(
                             &mut self.open_panel,
-                            Panel::ExternalFile,
+                            Panel::ExternalFile(i),
                             format!("File {}", external_file_view.filename),
                         );
+                        if ui.small_button("❌").on_hover_text("Close file").clicked() {
+                            close_file = Some(i);
+                        }
                     }
-                });
-
-                ui.separator();
 
-                let action = match self.open_panel {
-                    Panel::Tag => {
-                        if let Some(tagview) = &mut self.tag_view {
-                            tagview.view(ctx, ui)
-                        } else {
-                            ui.label("No tag loaded");
-                            None
+                    if let Some(i) = close_file {
+                        self.external_file_views.remove(i);
+                        match self.open_panel {
+                            Panel::ExternalFile(current) if current == i => {
+                                self.open_panel = Panel::Tag;
+                            }
+                            Panel::ExternalFile(current) if current > i => {
+                                self.open_panel = Panel::ExternalFile(current - 1);
+                            }
+                            _ => {}
                         }
                     }
-                    Panel::NamedTags => self.named_tags_view.view(ctx, ui),
-                    Panel::Packages => self.packages_view.view(ctx, ui),
-                    Panel::Textures => self.textures_view.view(ctx, ui),
-                    #[cfg(feature = "audio")]
-                    Panel::Audio => self.audio_view.view(ctx, ui),
-                    Panel::Strings => self.strings_view.view(ctx, ui),
-                    Panel::RawStrings => self.raw_strings_view.view(ctx, ui),
-                    Panel::RawStringHashes => self.raw_string_hashes_view.view(ctx, ui),
-                    Panel::ExternalFile => {
-                        if let Some(external_file_view) = &mut self.external_file_view {
-                            external_file_view.view(ctx, ui, &self.texture_cache)
-                        } else {
-                            self.open_panel = Panel::Tag;
-                            None
-                        }
+                    if let Some(class_instances_view) = &self.class_instances_view {
+                        ui.selectable_value(
+                            &mut self.open_panel,
+                            Panel::ClassInstances,
+                            format!("Class {}", class_instances_view.class.name),
+                        );
                     }
+                    if let Some(package_file_view) = &self.package_file_view {
+                        ui.selectable_value(
+                            &mut self.open_panel,
+                            Panel::PackageFile,
+                            format!("Package {}", package_file_view.filename),
+                        );
+                    }
+                    if let Some(partial_hash_search_view) = &self.partial_hash_search_view {
+                        ui.selectable_value(
+                            &mut self.open_panel,
+                            Panel::PartialHashSearch,
+                            format!("Search '{}'", partial_hash_search_view.query),
+                        );
+                    }
+                });
+
+                ui.separator();
+
+                let action = if is_loading_cache && self.open_panel.requires_cache() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Loading cache…");
+                    });
+                    None
+                } else {
+                    self.panel_ui(ctx, ui)
                 };
 
                 if self.open_panel == Panel::Tag && action.is_none() {
@@ -504,6 +852,7 @@ impl eframe::App for QuickTagApp {
                 if let Some(action) = action {
                     match action {
                         ViewAction::OpenTag(t) => self.open_tag(t, true),
+                        ViewAction::RescanPackage(pkg_id) => self.rescan_package(pkg_id),
                     }
                 }
             });
@@ -519,9 +868,276 @@ impl eframe::App for QuickTagApp {
 }
 
 impl QuickTagApp {
+    /// Shows when the cache was generated and warns if the packages have changed since
+    fn cache_status_ui(&self, ui: &mut egui::Ui) {
+        let format_timestamp = |timestamp: u64| {
+            chrono::DateTime::from_timestamp(timestamp as i64, 0)
+                .map(|d| d.format("%Y-%m-%d %H:%M UTC").to_string())
+                .unwrap_or_else(|| "unknown".to_string())
+        };
+
+        let (current_timestamp, current_fingerprint) = self.current_package_state;
+        let timestamp_changed = self.cache.timestamp < current_timestamp;
+        let fingerprint_changed = self.cache.package_fingerprint != current_fingerprint;
+
+        let mut details = format!(
+            "Cache generated from packages modified at {}\nPackage set fingerprint: {:016X}",
+            format_timestamp(self.cache.timestamp),
+            self.cache.package_fingerprint
+        );
+        if timestamp_changed {
+            details += &format!(
+                "\n\nThe package directory was modified at {}, after the cache was generated",
+                format_timestamp(current_timestamp)
+            );
+        }
+        if fingerprint_changed {
+            details += &format!(
+                "\n\nThe current package set has a different fingerprint ({current_fingerprint:016X}), packages were added, removed or patched since the cache was generated"
+            );
+        }
+
+        let label = format!(
+            "Cache: {} ({:08X})",
+            format_timestamp(self.cache.timestamp),
+            self.cache.package_fingerprint as u32
+        );
+        if timestamp_changed || fingerprint_changed {
+            ui.label(
+                egui::RichText::new(format!("⚠ {label} - may be stale")).color(Color32::YELLOW),
+            )
+            .on_hover_text(details);
+        } else {
+            ui.weak(label).on_hover_text(details);
+        }
+    }
+
+    /// Takes the new cache once loading has finished. Errors are shown as a toast, keeping the current cache
+    fn take_loaded_cache(&mut self) -> Option<TagCache> {
+        if !self
+            .cache_load
+            .as_ref()
+            .is_some_and(|p| p.poll().is_ready())
+        {
+            return None;
+        }
+
+        match self.cache_load.take()?.try_take() {
+            Ok(Ok(cache)) => Some(cache),
+            Ok(Err(e)) => {
+                error!("{e:?}");
+                TOASTS.lock().error(format!("{e:#}")).duration(None);
+                self.scan_before_regen = None;
+                None
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Keeps the scan result of the open tag, so its changes can be highlighted once the cache has been regenerated
+    fn remember_scan_before_regen(&mut self) {
+        self.scan_before_regen = self.tag_view.as_ref().and_then(|tv| {
+            let tag = tv.tag();
+            Some((tag, self.cache.hashes.get(&tag)?.clone()))
+        });
+    }
+
+    fn regenerate_cache(&mut self) {
+        if self.cache_load.is_some() {
+            TOASTS
+                .lock()
+                .warning("The cache is still loading, try again once it has finished");
+            return;
+        }
+
+        self.remember_scan_before_regen();
+        self.cache_load = Some(Promise::spawn_thread("rebuild_cache", rebuild_tag_cache));
+    }
+
+    fn rescan_package(&mut self, pkg_id: u16) {
+        if self.cache_load.is_some() {
+            TOASTS
+                .lock()
+                .warning("The cache is still loading, try again once it has finished");
+            return;
+        }
+
+        self.remember_scan_before_regen();
+        let cache = self.cache.clone();
+        self.cache_load = Some(Promise::spawn_thread("rescan_package", move || {
+            scanner::rescan_package(&cache, pkg_id)
+        }));
+    }
+
+    fn reload_schema(&mut self) {
+        classes::load_schemafile();
+        info!("Reloaded schema file");
+
+        if let Some(tag_view) = &mut self.tag_view {
+            tag_view.reload();
+        }
+    }
+
+    fn panel_ui(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) -> Option<ViewAction> {
+        match self.open_panel {
+            Panel::Tag => {
+                if let Some(tagview) = &mut self.tag_view {
+                    tagview.view(ctx, ui)
+                } else {
+                    ui.label("No tag loaded");
+                    None
+                }
+            }
+            Panel::NamedTags => self.named_tags_view.view(ctx, ui),
+            Panel::Packages => self.packages_view.view(ctx, ui),
+            Panel::Textures => self.textures_view.view(ctx, ui),
+            #[cfg(feature = "audio")]
+            Panel::Audio => self.audio_view.view(ctx, ui),
+            Panel::Strings => self.strings_view.view(ctx, ui),
+            Panel::RawStrings => self.raw_strings_view.view(ctx, ui),
+            Panel::RawStringHashes => self.raw_string_hashes_view.view(ctx, ui),
+            Panel::Orphans => self.orphans_view.view(ctx, ui, &self.texture_cache),
+            Panel::HashResolver => self.hash_resolver_view.view(ctx, ui),
+            Panel::Statistics => {
+                self.statistics_view.view(ctx, ui);
+                None
+            }
+            Panel::ExternalFile(i) => {
+                if let Some(external_file_view) = self.external_file_views.get_mut(i) {
+                    external_file_view.view(ctx, ui, &self.texture_cache)
+                } else {
+                    self.open_panel = Panel::Tag;
+                    None
+                }
+            }
+            Panel::ClassInstances => {
+                if let Some(class_instances_view) = &mut self.class_instances_view {
+                    class_instances_view.view(ctx, ui, &self.texture_cache)
+                } else {
+                    self.open_panel = Panel::Tag;
+                    None
+                }
+            }
+            Panel::PackageFile => {
+                if let Some(package_file_view) = &mut self.package_file_view {
+                    package_file_view.view(ctx, ui)
+                } else {
+                    self.open_panel = Panel::Tag;
+                    None
+                }
+            }
+            Panel::PartialHashSearch => {
+                if let Some(partial_hash_search_view) = &mut self.partial_hash_search_view {
+                    partial_hash_search_view.view(ctx, ui, &self.texture_cache)
+                } else {
+                    self.open_panel = Panel::Tag;
+                    None
+                }
+            }
+        }
+    }
+
+    /// Opens the tag hash in `text`, as pasted or dropped onto the window. Returns false if it isn't a tag hash
+    fn open_tag_from_text(&mut self, text: &str) -> bool {
+        let Some(tag) = parse_tag_hash(text) else {
+            return false;
+        };
+
+        self.tag_input = text.trim().to_string();
+        self.open_tag(tag, true);
+        true
+    }
+
+    /// Dropped text is opened as a tag hash, dropped packages are browsed and any other file is scanned.
+    /// winit doesn't forward text dragged from other applications on desktop, in which case pasting is the fallback
+    fn handle_dropped_file(&mut self, file: egui::DroppedFile) {
+        // Text drops show up as in-memory files without a path on backends that support them
+        if let Some(bytes) = &file.bytes {
+            let text = String::from_utf8_lossy(bytes);
+            if !self.open_tag_from_text(&text) && !self.open_tag_from_text(&file.name) {
+                TOASTS.lock().info(
+                    "Dropped text is not a valid tag hash, try copying and pasting it instead",
+                );
+            }
+            return;
+        }
+
+        let Some(path) = file.path else {
+            return;
+        };
+
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("pkg"))
+        {
+            match PackageFileView::open(&path) {
+                Ok(view) => {
+                    self.package_file_view = Some(view);
+                    self.open_panel = Panel::PackageFile;
+                }
+                Err(e) => {
+                    error!("Failed to open package {path:?}: {e:?}");
+                    TOASTS.lock().error(format!("Failed to open package: {e}"));
+                }
+            }
+            return;
+        }
+
+        // Small text files (eg. a snippet dragged out of an editor) are treated as dropped text
+        let is_small = std::fs::metadata(&path).is_ok_and(|m| m.len() <= 64);
+        if is_small {
+            if let Ok(text) = std::fs::read_to_string(&path) {
+                if self.open_tag_from_text(&text) {
+                    return;
+                }
+            }
+        }
+
+        self.open_external_file(&path);
+    }
+
+    fn open_external_file(&mut self, path: &Path) {
+        match ExternalFileScanView::open(path, self.scanner.context()) {
+            Ok(view) => {
+                self.external_file_views.push(view);
+                self.open_panel = Panel::ExternalFile(self.external_file_views.len() - 1);
+            }
+            Err(e) => {
+                error!("Failed to read file {path:?}: {e}");
+                TOASTS.lock().error(format!("Failed to read file: {e}"));
+            }
+        }
+    }
+
+    fn dump_strings(&self, dir: &Path) {
+        match text::dump_strings(dir, &self.strings, &self.raw_strings) {
+            Ok(()) => {
+                TOASTS
+                    .lock()
+                    .success(format!("Strings dumped to {}", dir.display()));
+            }
+            Err(e) => {
+                error!("Failed to dump strings to {}: {e:?}", dir.display());
+                TOASTS.lock().error(format!("Failed to dump strings: {e}"));
+            }
+        }
+    }
+
     fn open_tag(&mut self, tag: TagHash, push_history: bool) {
+        // While the cache is loading, the tag is opened with just its own scan result, so it has no incoming references.
+        // It's reopened with the full cache (and added to the history) once loading is done
+        let is_loading_cache = self.cache_load.is_some();
+        let cache = if is_loading_cache {
+            self.open_tag_on_load = Some(tag);
+            let mut cache = TagCache::default();
+            cache.hashes.insert(tag, self.scanner.scan_tag(tag));
+            Arc::new(cache)
+        } else {
+            self.cache.clone()
+        };
+
         let new_view = TagView::create(
-            self.cache.clone(),
+            cache,
             self.tag_history.clone(),
             self.strings.clone(),
             self.raw_strings.clone(),
@@ -532,18 +1148,16 @@ impl QuickTagApp {
         if new_view.is_some() {
             self.tag_view = new_view;
             self.open_panel = Panel::Tag;
-        } else if package_manager().get_entry(tag).is_some() {
+        } else if let Some(reason) = tag_missing_reason(tag) {
+            TOASTS.lock().error(reason);
+        } else {
             TOASTS.lock().warning(format!(
                 "Could not find tag '{}' ({tag}) in cache\nThis usually means it has no references",
                 self.tag_input
             ));
-        } else {
-            TOASTS
-                .lock()
-                .error(format!("Could not find tag '{}' ({tag})", self.tag_input));
         }
 
-        if push_history {
+        if push_history && !is_loading_cache {
             self.tag_history.borrow_mut().push(tag);
         }
     }
@@ -551,8 +1165,64 @@ impl QuickTagApp {
 
 pub enum ViewAction {
     OpenTag(TagHash),
+    /// Rescan a single package and update the cache
+    RescanPackage(u16),
 }
 
 pub trait View {
     fn view(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) -> Option<ViewAction>;
 }
+
+/// Lets the user pick the languages loaded into the string cache. Destiny 1 only has English strings
+fn string_languages_ui(ui: &mut egui::Ui) {
+    ui.label(egui::RichText::new("Applies on the next start").weak());
+    for code in text::LANGUAGE_CODES {
+        let mut selected = settings::settings()
+            .string_languages
+            .iter()
+            .any(|l| l == code);
+        if ui.checkbox(&mut selected, code).changed() {
+            let mut settings = settings_mut();
+            if selected {
+                settings.string_languages.push(code.to_string());
+            } else if settings.string_languages.len() > 1 {
+                settings.string_languages.retain(|l| l != code);
+            }
+        }
+    }
+}
+
+/// Lets the user override the color of each tag type
+fn tag_type_colors_ui(ui: &mut egui::Ui) {
+    egui::Grid::new("tag_type_colors")
+        .striped(true)
+        .show(ui, |ui| {
+            for t in TagType::all_colorable() {
+                let key = t.color_key();
+                let label = match t {
+                    TagType::Unknown { .. } => "Unknown".to_string(),
+                    t => t.to_string(),
+                };
+                ui.label(egui::RichText::new(label).color(t.display_color()));
+
+                let [r, g, b, _] = t.display_color().to_array();
+                let mut rgb = [r, g, b];
+                if ui.color_edit_button_srgb(&mut rgb).changed() {
+                    settings_mut().tag_type_colors.insert(key.to_string(), rgb);
+                }
+
+                let is_custom = settings::settings().tag_type_colors.contains_key(key);
+                if ui
+                    .add_enabled(is_custom, egui::Button::new("Reset"))
+                    .clicked()
+                {
+                    settings_mut().tag_type_colors.remove(key);
+                }
+                ui.end_row();
+            }
+        });
+
+    if ui.button("Reset all").clicked() {
+        settings_mut().tag_type_colors.clear();
+    }
+}
@@ -1,6 +1,7 @@
 use destiny_pkg::{package::UEntryHeader, PackageNamedTagEntry};
 use eframe::egui::{self, RichText};
 
+use crate::package_manager::get_hash64;
 use crate::{package_manager::package_manager, tagtypes::TagType};
 
 use super::{common::ResponseExt, tag::format_tag_entry, View, ViewAction};
@@ -43,7 +44,8 @@ impl View for NamedTagView {
     ) -> Option<super::ViewAction> {
         ui.horizontal(|ui| {
             ui.label("Search:");
-            ui.text_edit_singleline(&mut self.named_tag_filter);
+            ui.text_edit_singleline(&mut self.named_tag_filter)
+                .on_hover_text("Matches names, 32-bit and 64-bit hashes");
         });
 
         egui::ScrollArea::vertical()
@@ -54,10 +56,13 @@ impl View for NamedTagView {
                 } else {
                     for i in 0..self.named_tags.tags.len() {
                         let (entry, nt) = self.named_tags.tags[i].clone();
-                        if !nt
-                            .name
-                            .to_lowercase()
-                            .contains(&self.named_tag_filter.to_lowercase())
+                        let tag64 = get_hash64(nt.hash);
+                        let filter = self.named_tag_filter.to_lowercase();
+                        if !nt.name.to_lowercase().contains(&filter)
+                            && !nt.hash.to_string().to_lowercase().contains(&filter)
+                            && !tag64
+                                .map(|t| t.to_string().to_lowercase().contains(&filter))
+                                .unwrap_or_default()
                         {
                             continue;
                         }
@@ -65,7 +70,10 @@ impl View for NamedTagView {
                         let tagtype =
                             TagType::from_type_subtype(entry.file_type, entry.file_subtype);
 
-                        let fancy_tag = format_tag_entry(nt.hash, Some(&entry));
+                        let mut fancy_tag = format_tag_entry(nt.hash, Some(&entry));
+                        if let Some(tag64) = tag64 {
+                            fancy_tag += &format!(" (64-bit: {tag64})");
+                        }
 
                         let tag_label =
                             egui::RichText::new(fancy_tag).color(tagtype.display_color());
@@ -0,0 +1,223 @@
+use std::sync::Arc;
+
+use destiny_pkg::{package::UEntryHeader, TagHash};
+use eframe::egui::{self, RichText};
+use itertools::Itertools;
+use rustc_hash::FxHashMap;
+
+use crate::gui::common::TagSelection;
+use crate::gui::tag::{format_tag_entry, tag_search_results_ui};
+use crate::package_manager::package_manager;
+use crate::scanner::TagCache;
+use crate::tagtypes::TagType;
+use crate::texture::TextureCache;
+use crate::util::format_file_size;
+
+use super::ViewAction;
+
+struct OrphanGroup {
+    tag_type: TagType,
+    tags: Vec<(TagHash, UEntryHeader)>,
+    /// Combined data size of all tags in the group
+    total_size: u64,
+}
+
+/// Lists tags that are not referenced by anything and don't reference anything themselves
+pub struct OrphansView {
+    cache: Arc<TagCache>,
+    /// None until the report has been generated
+    groups: Option<Vec<OrphanGroup>>,
+    selected_group: usize,
+
+    filter: String,
+    /// Sort tags by their data size (largest first) instead of by hash
+    sort_by_size: bool,
+    filtered: Vec<(TagHash, UEntryHeader)>,
+    selection: TagSelection,
+}
+
+impl OrphansView {
+    pub fn new(cache: Arc<TagCache>) -> Self {
+        Self {
+            cache,
+            groups: None,
+            selected_group: usize::MAX,
+            filter: String::new(),
+            sort_by_size: false,
+            filtered: vec![],
+            selection: TagSelection::default(),
+        }
+    }
+
+    fn generate(&mut self) {
+        let mut groups: FxHashMap<(u8, u8), Vec<(TagHash, UEntryHeader)>> = Default::default();
+        for (pkg_id, entries) in package_manager().package_entry_index.iter() {
+            for (i, entry) in entries.iter().enumerate() {
+                let tag = TagHash::new(*pkg_id, i as u16);
+                let is_orphan = match self.cache.hashes.get(&tag) {
+                    Some(scan) => {
+                        scan.references.is_empty()
+                            && scan.file_hashes.is_empty()
+                            && scan.file_hashes64.is_empty()
+                    }
+                    None => true,
+                };
+
+                if is_orphan {
+                    groups
+                        .entry((entry.file_type, entry.file_subtype))
+                        .or_default()
+                        .push((tag, entry.clone()));
+                }
+            }
+        }
+
+        self.groups = Some(
+            groups
+                .into_iter()
+                .map(|((file_type, file_subtype), mut tags)| {
+                    tags.sort_by_key(|(tag, _)| tag.0);
+                    OrphanGroup {
+                        tag_type: TagType::from_type_subtype(file_type, file_subtype),
+                        total_size: tags.iter().map(|(tag, _)| self.tag_size(*tag)).sum(),
+                        tags,
+                    }
+                })
+                .sorted_by_key(|g| std::cmp::Reverse(g.tags.len()))
+                .collect(),
+        );
+        self.selected_group = usize::MAX;
+        self.filtered.clear();
+        self.selection.clear();
+    }
+
+    /// Data size recorded in the cache. Orphans that aren't in the cache at all fall back to the entry size
+    fn tag_size(&self, tag: TagHash) -> u64 {
+        self.cache
+            .data_size(tag)
+            .or_else(|| package_manager().get_entry(tag).map(|e| e.file_size))
+            .unwrap_or_default() as u64
+    }
+
+    fn update_filter(&mut self) {
+        self.selection.clear();
+        let Some(group) = self
+            .groups
+            .as_ref()
+            .and_then(|g| g.get(self.selected_group))
+        else {
+            self.filtered.clear();
+            return;
+        };
+
+        let filter = self.filter.to_lowercase();
+        let mut filtered: Vec<(TagHash, UEntryHeader)> = group
+            .tags
+            .iter()
+            .filter(|(tag, entry)| {
+                filter.is_empty()
+                    || format_tag_entry(*tag, Some(entry))
+                        .to_lowercase()
+                        .contains(&filter)
+            })
+            .cloned()
+            .collect();
+
+        if self.sort_by_size {
+            filtered.sort_by_cached_key(|(tag, _)| std::cmp::Reverse(self.tag_size(*tag)));
+        }
+        self.filtered = filtered;
+    }
+
+    pub fn view(
+        &mut self,
+        _ctx: &egui::Context,
+        ui: &mut egui::Ui,
+        texture_cache: &TextureCache,
+    ) -> Option<ViewAction> {
+        egui::SidePanel::left("orphans_left_panel")
+            .resizable(true)
+            .min_width(256.0)
+            .show_inside(ui, |ui| {
+                if ui
+                    .button(if self.groups.is_some() {
+                        "Regenerate report"
+                    } else {
+                        "Generate report"
+                    })
+                    .on_hover_text(
+                        "Finds all tags that are neither referenced by nor reference any other tag",
+                    )
+                    .clicked()
+                {
+                    self.generate();
+                }
+
+                let Some(groups) = &self.groups else {
+                    return;
+                };
+
+                ui.label(format!(
+                    "{} orphaned tags",
+                    groups.iter().map(|g| g.tags.len()).sum::<usize>()
+                ));
+                ui.separator();
+
+                let mut changed = false;
+                egui::ScrollArea::vertical()
+                    .max_width(f32::INFINITY)
+                    .show(ui, |ui| {
+                        for (i, group) in groups.iter().enumerate() {
+                            changed |= ui
+                                .selectable_value(
+                                    &mut self.selected_group,
+                                    i,
+                                    RichText::new(format!(
+                                        "{} ({}, {})",
+                                        group.tag_type,
+                                        group.tags.len(),
+                                        format_file_size(group.total_size as usize)
+                                    ))
+                                    .color(group.tag_type.display_color()),
+                                )
+                                .changed();
+                        }
+                    });
+
+                if changed {
+                    self.update_filter();
+                }
+            });
+
+        egui::CentralPanel::default()
+            .show_inside(ui, |ui| {
+                if self.selected_group == usize::MAX {
+                    ui.label(RichText::new("No type selected").italics());
+                    return None;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label("Search:");
+                    if ui.text_edit_singleline(&mut self.filter).changed() {
+                        self.update_filter();
+                    }
+                    if ui
+                        .checkbox(&mut self.sort_by_size, "Sort by size")
+                        .changed()
+                    {
+                        self.update_filter();
+                    }
+                });
+
+                tag_search_results_ui(
+                    ui,
+                    &self.filtered,
+                    &mut self.selection,
+                    &self.cache,
+                    texture_cache,
+                )
+                .map(ViewAction::OpenTag)
+            })
+            .inner
+    }
+}
@@ -0,0 +1,154 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use destiny_pkg::package::{Package, UEntryHeader};
+use eframe::egui::{self, RichText};
+use eframe::epaint::Color32;
+use egui_extras::{Column, TableBuilder};
+
+use crate::gui::hexview::TagHexView;
+use crate::gui::tag::ExtendedScanResult;
+use crate::package_manager::package_manager;
+use crate::tagtypes::TagType;
+use crate::util::format_file_size;
+
+use super::ViewAction;
+
+/// Browses the entries of a single package file opened from disk, without going through the package manager or tag cache
+pub struct PackageFileView {
+    pub filename: String,
+    pub path: PathBuf,
+    pkg: Arc<dyn Package>,
+    entries: Vec<UEntryHeader>,
+
+    selected_entry: Option<usize>,
+    /// Hex view of the selected entry, or the error that occurred while reading it
+    entry_view: Option<Result<(TagHexView, ExtendedScanResult), String>>,
+}
+
+impl PackageFileView {
+    /// Opens a package using the version of the currently loaded game
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let pkg = package_manager().version.open(&path.to_string_lossy())?;
+        let entries = pkg.entries().to_vec();
+
+        Ok(Self {
+            filename: path
+                .file_name()
+                .map(|f| f.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            path: path.to_path_buf(),
+            pkg,
+            entries,
+            selected_entry: None,
+            entry_view: None,
+        })
+    }
+
+    fn select_entry(&mut self, index: usize) {
+        self.selected_entry = Some(index);
+        self.entry_view = Some(
+            self.pkg
+                .read_entry(index)
+                .map(|data| {
+                    let scan = ExtendedScanResult::from_raw_data(
+                        &data,
+                        package_manager().version.endian(),
+                    );
+                    (TagHexView::new(data), scan)
+                })
+                .map_err(|e| e.to_string()),
+        );
+    }
+
+    pub fn view(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) -> Option<ViewAction> {
+        let mut select = None;
+        egui::SidePanel::left("package_file_left_panel")
+            .resizable(true)
+            .min_width(384.0)
+            .show_inside(ui, |ui| {
+                ui.label(format!(
+                    "{} (pkg {:04X}, {} entries)",
+                    self.filename,
+                    self.pkg.pkg_id(),
+                    self.entries.len()
+                ));
+                ui.separator();
+
+                TableBuilder::new(ui)
+                    .striped(true)
+                    .column(Column::auto().at_least(48.0))
+                    .column(Column::auto().at_least(160.0))
+                    .column(Column::auto().at_least(72.0))
+                    .column(Column::remainder())
+                    .header(20.0, |mut header| {
+                        header.col(|ui| {
+                            ui.strong("Index");
+                        });
+                        header.col(|ui| {
+                            ui.strong("Type");
+                        });
+                        header.col(|ui| {
+                            ui.strong("Reference");
+                        });
+                        header.col(|ui| {
+                            ui.strong("Size");
+                        });
+                    })
+                    .body(|body| {
+                        body.rows(18.0, self.entries.len(), |mut row| {
+                            let i = row.index();
+                            let entry = &self.entries[i];
+                            let tagtype =
+                                TagType::from_type_subtype(entry.file_type, entry.file_subtype);
+                            row.col(|ui| {
+                                if ui
+                                    .selectable_label(
+                                        self.selected_entry == Some(i),
+                                        format!("{i}"),
+                                    )
+                                    .clicked()
+                                {
+                                    select = Some(i);
+                                }
+                            });
+                            row.col(|ui| {
+                                ui.label(
+                                    RichText::new(format!(
+                                        "{tagtype} ({}+{})",
+                                        entry.file_type, entry.file_subtype
+                                    ))
+                                    .color(tagtype.display_color()),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.monospace(format!("{:08X}", entry.reference));
+                            });
+                            row.col(|ui| {
+                                ui.label(format_file_size(entry.file_size as usize));
+                            });
+                        });
+                    });
+            });
+
+        if let Some(i) = select {
+            self.select_entry(i);
+        }
+
+        egui::CentralPanel::default()
+            .show_inside(ui, |ui| match &mut self.entry_view {
+                None => {
+                    ui.label(RichText::new("No entry selected").italics());
+                    None
+                }
+                Some(Err(e)) => {
+                    ui.label(
+                        RichText::new(format!("Failed to read entry: {e}")).color(Color32::RED),
+                    );
+                    None
+                }
+                Some(Ok((hexview, scan))) => hexview.show(ui, scan).map(ViewAction::OpenTag),
+            })
+            .inner
+    }
+}
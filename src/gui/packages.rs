@@ -1,6 +1,7 @@
 use destiny_pkg::package::UEntryHeader;
 use destiny_pkg::{manager::PackagePath, TagHash};
 use eframe::egui::{self, RichText};
+use rustc_hash::FxHashMap;
 
 use super::{
     common::{dump_wwise_info, ResponseExt},
@@ -20,6 +21,8 @@ pub struct PackagesView {
     package_entry_filter: String,
     texture_cache: TextureCache,
     sorted_package_paths: Vec<(u16, PackagePath)>,
+    /// File size on disk and entry count of each package
+    package_stats: FxHashMap<u16, (u64, usize)>,
     show_only_hash64: bool,
     sort_by_size: bool,
 }
@@ -34,6 +37,21 @@ impl PackagesView {
 
         sorted_package_paths.sort_by_cached_key(|(_, path)| format!("{}_{}", path.name, path.id));
 
+        let package_stats = sorted_package_paths
+            .iter()
+            .map(|(id, path)| {
+                let size = std::fs::metadata(&path.path)
+                    .map(|m| m.len())
+                    .unwrap_or_default();
+                let entry_count = package_manager()
+                    .package_entry_index
+                    .get(id)
+                    .map(|e| e.len())
+                    .unwrap_or_default();
+                (*id, (size, entry_count))
+            })
+            .collect();
+
         Self {
             selected_package: u16::MAX,
             package_entry_search_cache: vec![],
@@ -41,6 +59,7 @@ impl PackagesView {
             package_entry_filter: String::new(),
             texture_cache,
             sorted_package_paths,
+            package_stats,
             show_only_hash64: false,
             sort_by_size: false,
         }
@@ -93,12 +112,24 @@ impl View for PackagesView {
                                 ""
                             };
 
+                            let stats = self
+                                .package_stats
+                                .get(id)
+                                .map(|(size, entry_count)| {
+                                    format!(
+                                        " ({}, {entry_count} entries)",
+                                        format_file_size(*size as usize)
+                                    )
+                                })
+                                .unwrap_or_default();
+
                             if ui
                                 .selectable_value(
                                     &mut self.selected_package,
                                     *id,
-                                    format!("{id:04x}: {redacted}{package_name}"),
+                                    format!("{id:04x}: {redacted}{package_name}{stats}"),
                                 )
+                                .on_hover_text(format!("Patch {}\n{}", path.patch, path.path))
                                 .changed()
                             {
                                 self.package_entry_search_cache = vec![];
@@ -132,16 +163,40 @@ impl View for PackagesView {
 
                     None
                 } else {
+                    if let Some(path) = package_manager().package_paths.get(&self.selected_package)
+                    {
+                        ui.horizontal(|ui| {
+                            ui.strong(&path.filename);
+                            ui.label(format!("Patch {}", path.patch));
+                            if let Some((size, entry_count)) =
+                                self.package_stats.get(&self.selected_package)
+                            {
+                                ui.label(format_file_size(*size as usize));
+                                ui.label(format!("{entry_count} entries"));
+                            }
+                        });
+                        ui.label(RichText::new(&path.path).weak());
+                        ui.separator();
+                    }
+
                     ui.horizontal(|ui| {
                         ui.label("Search:");
                         ui.text_edit_singleline(&mut self.package_entry_filter);
                     });
 
+                    let mut rescan = false;
                     ui.horizontal(|ui| {
                         if ui.button("Export audio info").clicked() {
                             dump_wwise_info(self.selected_package);
                         }
 
+                        rescan = ui
+                            .button("Rescan package")
+                            .on_hover_text(
+                                "Scans only this package again and updates the tag cache",
+                            )
+                            .clicked();
+
                         ui.checkbox(&mut self.show_only_hash64, "★ Only show hash64");
                         if ui
                             .checkbox(&mut self.sort_by_size, "Sort by size descending")
@@ -150,6 +205,11 @@ impl View for PackagesView {
                             self.sort_entries();
                         }
                     });
+
+                    if rescan {
+                        return Some(ViewAction::RescanPackage(self.selected_package));
+                    }
+
                     egui::ScrollArea::vertical()
                         .max_width(f32::INFINITY)
                         .show(ui, |ui| {
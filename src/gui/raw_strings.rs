@@ -8,6 +8,7 @@ use rustc_hash::FxHashMap;
 use crate::{
     package_manager::package_manager,
     scanner::{fnv1, TagCache},
+    settings::{settings, settings_mut},
     tagtypes::TagType,
 };
 
@@ -39,21 +40,38 @@ impl RawStringsView {
             };
         }
 
-        let strings = strings.into_iter().collect_vec();
-
-        Self {
-            strings_vec_filtered: strings
-                .iter()
-                .enumerate()
-                .map(|(i, (k, (v, h)))| (i, k.clone(), v.clone(), *h))
-                .collect(),
-            strings: strings
-                .into_iter()
-                .map(|(v0, (v1, v2))| (v0, v1, v2))
-                .collect(),
+        let strings = strings
+            .into_iter()
+            .map(|(v0, (v1, v2))| (v0, v1, v2))
+            .collect();
+
+        let mut view = Self {
+            strings,
+            strings_vec_filtered: vec![],
             string_filter: String::new(),
             selected_stringset: usize::MAX,
-        }
+        };
+        view.update_filter();
+
+        view
+    }
+
+    fn update_filter(&mut self) {
+        let min_length = settings().raw_string_min_length;
+        let hide_unprintable = settings().raw_string_hide_unprintable;
+        let string_filter = self.string_filter.to_lowercase();
+
+        self.strings_vec_filtered = self
+            .strings
+            .iter()
+            .enumerate()
+            .filter(|(_, (s, _, _))| {
+                s.chars().count() >= min_length
+                    && (!hide_unprintable || is_mostly_printable(s))
+                    && (string_filter.is_empty() || s.to_lowercase().contains(&string_filter))
+            })
+            .map(|(i, (k, v, h))| (i, k.clone(), v.clone(), *h))
+            .collect();
     }
 }
 
@@ -67,24 +85,25 @@ impl View for RawStringsView {
             ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Truncate);
             ui.horizontal(|ui| {
                 ui.label("Search:");
-                if ui.text_edit_singleline(&mut self.string_filter).changed() {
-                    self.strings_vec_filtered = if !self.string_filter.is_empty() {
-                        self.strings
-                            .iter()
-                            .enumerate()
-                            .filter(|(_, (s, _, _))| {
-                                s.to_lowercase()
-                                    .contains(&self.string_filter.to_lowercase())
-                            })
-                            .map(|(i, (k, v, h))| (i, k.clone(), v.clone(), *h))
-                            .collect()
-                    } else {
-                        self.strings
-                            .iter()
-                            .enumerate()
-                            .map(|(i, (k, v, h))| (i, k.clone(), v.clone(), *h))
-                            .collect_vec()
-                    };
+                let mut update_filter = ui.text_edit_singleline(&mut self.string_filter).changed();
+
+                ui.label("Min length:");
+                update_filter |= ui
+                    .add(
+                        egui::DragValue::new(&mut settings_mut().raw_string_min_length)
+                            .range(1..=64),
+                    )
+                    .changed();
+                update_filter |= ui
+                    .checkbox(
+                        &mut settings_mut().raw_string_hide_unprintable,
+                        "Hide unprintable",
+                    )
+                    .on_hover_text("Hides strings that consist mostly of non-printable characters")
+                    .changed();
+
+                if update_filter {
+                    self.update_filter();
                 }
             });
 
@@ -180,6 +199,17 @@ impl View for RawStringsView {
     }
 }
 
+/// Returns true if at least 3/4 of the characters in the string are printable
+fn is_mostly_printable(s: &str) -> bool {
+    let total = s.chars().count();
+    let printable = s
+        .chars()
+        .filter(|c| !c.is_control() || c.is_whitespace())
+        .count();
+
+    printable * 4 >= total * 3
+}
+
 fn truncate_string_stripped(s: &str, max_length: usize) -> String {
     let s = s.replace('\n', "\\n");
 
@@ -0,0 +1,134 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use destiny_pkg::TagHash;
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use crate::known_tags;
+use crate::package_manager::package_manager;
+
+use super::package_file::PackageFileView;
+use super::{Panel, QuickTagApp, TOASTS};
+
+/// Working state that can be saved to a file and handed to someone else looking at the same game version
+#[derive(Serialize, Deserialize)]
+struct Session {
+    /// Cache key of the packages the session was saved with, sessions for other versions are refused
+    cache_key: String,
+    open_panel: Panel,
+    open_tag: Option<u32>,
+    tag_history: Vec<u32>,
+    tag_history_current: usize,
+    external_files: Vec<PathBuf>,
+    package_file: Option<PathBuf>,
+    known_tags: Vec<(u32, String)>,
+}
+
+impl QuickTagApp {
+    pub(super) fn save_session(&self, path: &Path) {
+        let history = self.tag_history.borrow();
+        let mut known_tags: Vec<(u32, String)> = known_tags::known_tags()
+            .iter()
+            .map(|(tag, label)| (tag.0, label.clone()))
+            .collect();
+        known_tags.sort_by_key(|(tag, _)| *tag);
+
+        let session = Session {
+            cache_key: package_manager().cache_key(),
+            open_panel: self.open_panel,
+            open_tag: self.tag_view.as_ref().map(|v| v.tag().0),
+            tag_history: history.tags.iter().map(|(tag, _, _)| tag.0).collect(),
+            tag_history_current: history.current,
+            external_files: self
+                .external_file_views
+                .iter()
+                .map(|v| v.path.clone())
+                .collect(),
+            package_file: self.package_file_view.as_ref().map(|v| v.path.clone()),
+            known_tags,
+        };
+
+        let result = File::create(path)
+            .context("Failed to create session file")
+            .and_then(|f| Ok(serde_json::to_writer_pretty(f, &session)?));
+
+        match result {
+            Ok(()) => {
+                TOASTS.lock().success("Session saved");
+            }
+            Err(e) => {
+                error!("Failed to save session to {path:?}: {e:?}");
+                TOASTS.lock().error(format!("Failed to save session: {e}"));
+            }
+        }
+    }
+
+    pub(super) fn load_session(&mut self, path: &Path) {
+        let session: Session = match File::open(path)
+            .context("Failed to open session file")
+            .and_then(|f| Ok(serde_json::from_reader(f)?))
+        {
+            Ok(s) => s,
+            Err(e) => {
+                error!("Failed to load session from {path:?}: {e:?}");
+                TOASTS.lock().error(format!("Failed to load session: {e}"));
+                return;
+            }
+        };
+
+        let cache_key = package_manager().cache_key();
+        if session.cache_key != cache_key {
+            TOASTS.lock().error(format!(
+                "Session was saved for '{}', but the loaded packages are '{cache_key}'",
+                session.cache_key
+            ));
+            return;
+        }
+
+        known_tags::set_known_tags(
+            session
+                .known_tags
+                .into_iter()
+                .map(|(tag, label)| (TagHash(tag), label))
+                .collect(),
+        );
+
+        self.external_file_views.clear();
+        for path in &session.external_files {
+            self.open_external_file(path);
+        }
+
+        self.package_file_view = None;
+        if let Some(path) = &session.package_file {
+            match PackageFileView::open(path) {
+                Ok(view) => self.package_file_view = Some(view),
+                Err(e) => {
+                    error!("Failed to open package {path:?}: {e:?}");
+                    TOASTS.lock().error(format!("Failed to open package: {e}"));
+                }
+            }
+        }
+
+        {
+            let mut history = self.tag_history.borrow_mut();
+            history.tags.clear();
+            for tag in &session.tag_history {
+                history.push(TagHash(*tag));
+            }
+            history.current = session
+                .tag_history_current
+                .min(history.tags.len().saturating_sub(1));
+        }
+
+        self.tag_view = None;
+        if let Some(tag) = session.open_tag {
+            self.open_tag(TagHash(tag), false);
+        }
+
+        // Views that failed to open fall back to the tag panel on the next frame
+        self.open_panel = session.open_panel;
+        TOASTS.lock().success("Session loaded");
+    }
+}
@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use destiny_pkg::TagHash;
+use eframe::egui::{self, RichText};
+use itertools::Itertools;
+use rustc_hash::FxHashMap;
+
+use crate::package_manager::package_manager;
+use crate::scanner::TagCache;
+use crate::tagtypes::TagType;
+use crate::util::format_file_size;
+
+#[derive(Default)]
+struct TypeStats {
+    count: usize,
+    /// Combined entry size
+    total_size: u64,
+    /// Not in the tag cache at all. Tags of types that aren't scanned for references are still indexed
+    not_indexed: usize,
+    failed: usize,
+}
+
+struct TypeRow {
+    tag_type: TagType,
+    stats: TypeStats,
+}
+
+#[derive(Default)]
+struct DatasetStats {
+    types: Vec<TypeRow>,
+    total: TypeStats,
+    /// Tags that were only recorded with their size, as they exceeded the max tag size
+    skipped_too_large: usize,
+}
+
+/// Shows how many tags of each type the loaded packages contain, and how many of them are in the cache and could be read
+pub struct StatisticsView {
+    cache: Arc<TagCache>,
+    /// None until the report has been generated
+    stats: Option<DatasetStats>,
+}
+
+impl StatisticsView {
+    pub fn new(cache: Arc<TagCache>) -> Self {
+        Self { cache, stats: None }
+    }
+
+    fn generate(&mut self) {
+        let mut stats = DatasetStats::default();
+        let mut types: FxHashMap<(u8, u8), TypeStats> = Default::default();
+        for (pkg_id, entries) in package_manager().package_entry_index.iter() {
+            for (i, entry) in entries.iter().enumerate() {
+                let tag = TagHash::new(*pkg_id, i as u16);
+                let type_stats = types
+                    .entry((entry.file_type, entry.file_subtype))
+                    .or_default();
+
+                for s in [&mut *type_stats, &mut stats.total] {
+                    s.count += 1;
+                    s.total_size += entry.file_size as u64;
+                }
+
+                match self.cache.hashes.get(&tag) {
+                    Some(scan) => {
+                        if !scan.successful {
+                            type_stats.failed += 1;
+                            stats.total.failed += 1;
+                        }
+                        if scan.skipped_too_large {
+                            stats.skipped_too_large += 1;
+                        }
+                    }
+                    None => {
+                        type_stats.not_indexed += 1;
+                        stats.total.not_indexed += 1;
+                    }
+                }
+            }
+        }
+
+        stats.types = types
+            .into_iter()
+            .map(|((file_type, file_subtype), stats)| TypeRow {
+                tag_type: TagType::from_type_subtype(file_type, file_subtype),
+                stats,
+            })
+            .sorted_by_key(|r| std::cmp::Reverse(r.stats.count))
+            .collect();
+
+        self.stats = Some(stats);
+    }
+
+    pub fn view(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) {
+        if ui
+            .button(if self.stats.is_some() {
+                "Regenerate report"
+            } else {
+                "Generate report"
+            })
+            .on_hover_text(
+                "Counts the tags of each type in all packages, and how many are in the tag cache",
+            )
+            .clicked()
+        {
+            self.generate();
+        }
+
+        let Some(stats) = &self.stats else {
+            return;
+        };
+
+        let indexed = stats.total.count - stats.total.not_indexed;
+        ui.label(format!(
+            "{} tags ({}) in {} packages",
+            stats.total.count,
+            format_file_size(stats.total.total_size as usize),
+            package_manager().package_paths.len()
+        ));
+        ui.label(format!(
+            "{indexed} indexed, {} not in the cache",
+            stats.total.not_indexed
+        ));
+        ui.label(format!(
+            "{} read successfully, {} failed to read",
+            indexed - stats.total.failed,
+            stats.total.failed
+        ));
+        if stats.total.failed > 0 {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                format!(
+                    "⚠ {:.1}% of indexed tags failed to read",
+                    stats.total.failed as f32 / indexed.max(1) as f32 * 100.0
+                ),
+            );
+        }
+        if stats.skipped_too_large > 0 {
+            ui.label(format!(
+                "{} tags were too large to scan for references",
+                stats.skipped_too_large
+            ));
+        }
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                egui::Grid::new("dataset_statistics")
+                    .striped(true)
+                    .num_columns(5)
+                    .show(ui, |ui| {
+                        ui.strong("Type");
+                        ui.strong("Count");
+                        ui.strong("Size");
+                        ui.strong("Not indexed");
+                        ui.strong("Failed");
+                        ui.end_row();
+
+                        for row in &stats.types {
+                            ui.label(
+                                RichText::new(row.tag_type.to_string())
+                                    .color(row.tag_type.display_color()),
+                            );
+                            ui.label(row.stats.count.to_string());
+                            ui.label(format_file_size(row.stats.total_size as usize));
+                            ui.label(row.stats.not_indexed.to_string());
+                            if row.stats.failed > 0 {
+                                ui.colored_label(
+                                    egui::Color32::YELLOW,
+                                    row.stats.failed.to_string(),
+                                );
+                            } else {
+                                ui.label("0");
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+    }
+}
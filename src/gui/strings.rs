@@ -15,14 +15,20 @@ use crate::{
     package_manager::package_manager,
     scanner::TagCache,
     tagtypes::TagType,
-    text::{decode_text, StringCache, StringCacheVec, StringContainer, StringData, StringPart},
+    text::{
+        decode_text, export_string_sources, StringCache, StringCacheVec, StringContainer,
+        StringData, StringPart, StringSourceMap,
+    },
+    util::GameVersionExt,
 };
 
-use super::{common::ResponseExt, tag::format_tag_entry, View, ViewAction};
+use super::{common::ResponseExt, tag::format_tag_entry, View, ViewAction, TOASTS};
 
 pub struct StringsView {
     cache: Arc<TagCache>,
     strings: Arc<StringCache>,
+    /// String containers each string was read from. Only available for localized strings
+    sources: Arc<StringSourceMap>,
     strings_vec_filtered: StringCacheVec,
 
     selected_string: u32,
@@ -44,6 +50,7 @@ pub enum StringViewVariant {
 impl StringsView {
     pub fn new(
         strings: Arc<StringCache>,
+        sources: Arc<StringSourceMap>,
         cache: Arc<TagCache>,
         variant: StringViewVariant,
     ) -> Self {
@@ -59,6 +66,7 @@ impl StringsView {
         Self {
             cache,
             strings,
+            sources,
             strings_vec_filtered,
             selected_string: u32::MAX,
             string_filter: String::new(),
@@ -82,10 +90,27 @@ impl View for StringsView {
             .resizable(true)
             .min_width(384.0)
             .show_inside(ui, |ui| {
-                if self.variant == StringViewVariant::LocalizedStrings
-                    && ui.button("Dump all languages").clicked()
-                {
-                    dump_all_languages().unwrap();
+                if self.variant == StringViewVariant::LocalizedStrings {
+                    ui.horizontal(|ui| {
+                        if ui.button("Dump all languages").clicked() {
+                            dump_all_languages().unwrap();
+                        }
+
+                        if ui
+                            .button("Export string sources")
+                            .on_hover_text("Writes the string container of every localized string to string_sources.csv")
+                            .clicked()
+                        {
+                            match export_string_sources(&self.strings, &self.sources) {
+                                Ok(()) => {
+                                    TOASTS.lock().success("String sources exported");
+                                }
+                                Err(e) => {
+                                    log::error!("Failed to export string sources: {e:?}");
+                                }
+                            }
+                        }
+                    });
                 }
 
                 ui.separator();
@@ -237,6 +262,34 @@ impl View for StringsView {
                         if self.selected_string == u32::MAX {
                             ui.label(RichText::new("No string selected").italics());
                         } else {
+                            if let Some(containers) = self.sources.get(&self.selected_string) {
+                                ui.heading("String containers");
+                                for tag in containers {
+                                    let entry = package_manager().get_entry(*tag);
+                                    let color = entry
+                                        .as_ref()
+                                        .map(|e| {
+                                            TagType::from_type_subtype(e.file_type, e.file_subtype)
+                                                .display_color()
+                                        })
+                                        .unwrap_or(egui::Color32::GRAY);
+                                    if ui
+                                        .add(egui::SelectableLabel::new(
+                                            false,
+                                            RichText::new(format_tag_entry(*tag, entry.as_ref()))
+                                                .color(color),
+                                        ))
+                                        .tag_context(*tag)
+                                        .clicked()
+                                    {
+                                        return Some(ViewAction::OpenTag(*tag));
+                                    }
+                                }
+
+                                ui.separator();
+                                ui.heading("Referenced by");
+                            }
+
                             for (tag, label, tag_type) in &self.string_selected_entries {
                                 if ui
                                     .add(egui::SelectableLabel::new(
@@ -269,10 +322,7 @@ fn truncate_string_stripped(s: &str, max_length: usize) -> String {
 }
 
 fn dump_all_languages() -> anyhow::Result<()> {
-    let prebl = matches!(
-        package_manager().version,
-        GameVersion::Destiny2Beta | GameVersion::Destiny2Forsaken | GameVersion::Destiny2Shadowkeep
-    );
+    let prebl = package_manager().version.is_prebl();
     let bl = package_manager().version == GameVersion::Destiny2BeyondLight;
 
     std::fs::create_dir("strings").ok();
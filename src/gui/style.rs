@@ -2,13 +2,31 @@
 
 use eframe::egui;
 use eframe::egui::Margin;
+
+use crate::settings::Theme;
 use egui::{
     epaint::Shadow,
     style::{Interaction, Selection, Spacing, WidgetVisuals, Widgets},
     Color32, Rounding, Stroke, Style, Vec2, Visuals,
 };
 
-pub fn style() -> Style {
+pub fn style(theme: Theme) -> Style {
+    let mut style = dark_style();
+    if theme == Theme::Light {
+        style.visuals = Visuals {
+            window_rounding: style.visuals.window_rounding,
+            menu_rounding: style.visuals.menu_rounding,
+            collapsing_header_frame: false,
+            indent_has_left_vline: true,
+            slider_trailing_fill: false,
+            ..Visuals::light()
+        };
+    }
+
+    style
+}
+
+fn dark_style() -> Style {
     Style {
         // override the text styles here:
         // override_text_style: Option<TextStyle>
@@ -3,26 +3,35 @@ use std::fs::File;
 use std::io::Write as _;
 use std::path::PathBuf;
 use std::{
-    collections::HashSet,
     fmt::Display,
     io::{Cursor, Seek, SeekFrom},
+    ops::Range,
     path::Path,
     sync::Arc,
     time::{Duration, Instant},
 };
 
 use super::{
+    background_task::{spawn_task, TaskProgress},
     common::{
-        open_audio_file_in_default_application, open_tag_in_default_application, tag_context,
-        ResponseExt,
+        list_keyboard_input, open_audio_file_in_default_application,
+        open_tag_in_default_application, tag_context, ResponseExt, TagSelection,
     },
-    View, ViewAction,
+    View, ViewAction, TOASTS,
 };
-use crate::classes::get_class_by_id;
+use crate::cache_diff::{diff_scan_results, TagDiff};
+use crate::classes::{self, get_class_by_id};
+use crate::gui::curves::{is_plausible_float, TagCurveView};
 use crate::gui::hexview::TagHexView;
+use crate::gui::texture_compare::TextureComparison;
+use crate::gui::textview::TagTextView;
+use crate::known_tags::get_known_tag_label;
 use crate::package_manager::get_hash64;
 use crate::scanner::ScannedHash;
-use crate::util::ui_image_rotated;
+use crate::settings::settings;
+use crate::util::{
+    format_file_size, parse_tag_hash, u32_from_endian, u64_from_endian, ui_image_rotated,
+};
 use crate::{
     package_manager::package_manager,
     scanner::{ScanResult, TagCache},
@@ -30,8 +39,9 @@ use crate::{
     text::StringCache,
 };
 use crate::{
-    scanner::read_raw_string_blob, text::RawStringHashCache, texture::Texture,
-    texture::TextureCache,
+    scanner::{find_blocked_ranges, find_raw_strings},
+    text::RawStringHashCache,
+    texture::{Texture, TextureCache, TextureSizeMismatch},
 };
 use anyhow::Context;
 use binrw::{binread, BinReaderExt, Endian};
@@ -47,11 +57,14 @@ use eframe::{
 };
 use itertools::Itertools;
 use log::error;
+use parking_lot::Mutex;
 use poll_promise::Promise;
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::fmt::Write;
 use std::hash::{Hash, Hasher};
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, Sender};
 
 #[derive(Copy, Clone, PartialEq)]
 enum TagViewMode {
@@ -59,6 +72,8 @@ enum TagViewMode {
     Hex,
     HexReferenced,
     Float,
+    Text,
+    Curves,
     Search,
 }
 
@@ -72,9 +87,25 @@ pub struct TagView {
     raw_string_hashes: Vec<(u64, u32)>,
     raw_strings: Vec<(u64, String, Vec<u64>)>,
     arrays: Vec<(u64, TagArray)>,
+    /// Other tags with the same content hash
+    identical_tags: Vec<TagHash>,
+    /// Ranges of `@block_tags` arrays that the scanner skips over
+    blocked_ranges: Vec<Range<u64>>,
 
     /// Used if this tag is a texture header
     texture: anyhow::Result<(Texture, TextureId)>,
+    /// Overrides the global premultiply alpha setting for this view
+    texture_premultiply_alpha: bool,
+    /// Layer 0 of the texture, decoded to RGBA8
+    texture_decoded: Option<anyhow::Result<(Texture, TextureId)>>,
+    texture_show_decoded: bool,
+    /// Comparison against an external reference image
+    texture_comparison: Option<anyhow::Result<TextureComparison>>,
+    texture_zoom: f32,
+    /// Offset of the texture center from the center of the preview area, in points
+    texture_pan: egui::Vec2,
+    /// Materials and shaders that (indirectly) reference this texture, found on request
+    referencing_materials: Option<Vec<(TagHash, UEntryHeader)>>,
 
     tag: TagHash,
     tag64: Option<TagHash64>,
@@ -83,11 +114,24 @@ pub struct TagView {
     tag_data: Vec<u8>,
 
     scan: ExtendedScanResult,
-    tag_traversal: Option<Promise<(TraversedTag, String)>>,
+    tag_traversal: Option<Promise<(TraversedTag, String, Vec<PrunedReference>)>>,
+    /// Output of the traversal that is currently running, streamed in as it's generated
+    traversal_partial: String,
+    traversal_rx: Option<Receiver<String>>,
+    traversal_cancel: Arc<AtomicBool>,
     traversal_depth_limit: usize,
     traversal_show_strings: bool,
+    /// Follow classes marked with `@skip_traversal`
+    traversal_follow_skipped: bool,
     traversal_interactive: bool,
     hide_already_traversed: bool,
+    traversal_search: String,
+    /// Index of the selected match
+    traversal_search_current: usize,
+    /// Expand and scroll to the selected match on the next frame
+    traversal_search_jump: bool,
+    /// Matches of `traversal_search` in the finished traversal, recomputed when the query or traversal changes
+    traversal_search_results: Option<TraversalSearchResults>,
     start_time: Instant,
 
     search_tagtype: TagType,
@@ -96,11 +140,33 @@ pub struct TagView {
     search_depth_limit: usize,
     search_package_name_filter: String,
     search_results: Vec<(TagHash, UEntryHeader)>,
+    /// Chain of references from this tag to each search result, including both ends
+    search_result_paths: FxHashMap<TagHash, Vec<TagHash>>,
+    /// References the last search didn't follow because of `@skip_traversal`
+    search_pruned: Vec<PrunedReference>,
+    search_show_paths: bool,
+    path_target_input: String,
+    path_direction: ReferenceDirection,
+    path_depth_limit: usize,
+    /// Depth limit and shortest reference paths of the last path search, filled in by a background task
+    path_results: Arc<Mutex<Option<(usize, Vec<Vec<TagHash>>)>>>,
+    /// Keyboard cursor in the reference lists
+    reference_cursor: Option<usize>,
+    search_selection: TagSelection,
 
     render_state: RenderState,
     texture_cache: TextureCache,
     hexview: TagHexView,
-    hexview_referenced: Option<TagHexView>,
+    /// Hex view and references of the data buffer referenced by constant buffers.
+    /// Offsets in here are relative to the start of that buffer, not the header
+    hexview_referenced: Option<(TagHexView, ExtendedScanResult)>,
+    /// Only available if the tag data looks like text
+    textview: Option<TagTextView>,
+    /// Detected when the curves tab is first opened, as it needs to look at every float in the tag
+    curves: Option<TagCurveView>,
+    /// Changes to this tag's scan result since the cache was regenerated
+    scan_diff: Option<TagDiff>,
+    highlight_scan_diff: bool,
     mode: TagViewMode,
 }
 
@@ -127,7 +193,6 @@ impl TagView {
     ) -> Option<TagView> {
         let tag_data = package_manager().read_tag(tag).ok()?;
         let mut array_offsets = vec![];
-        let mut raw_string_offsets = vec![];
         let mut string_hashes = vec![];
         let mut raw_string_hashes = vec![];
 
@@ -169,10 +234,6 @@ impl TagView {
                 array_offsets.push(offset + 4);
             }
 
-            if matches!(value, 0x80800065 | 0x808000CB) {
-                raw_string_offsets.push(offset);
-            }
-
             if string_cache.contains_key(&value) {
                 string_hashes.push((offset, value));
             }
@@ -182,15 +243,16 @@ impl TagView {
             }
         }
 
-        let raw_strings = raw_string_offsets
-            .into_iter()
-            .flat_map(|o| read_raw_string_blob(&tag_data, o))
-            .collect_vec();
-
-        let raw_strings = raw_strings
-            .into_iter()
-            .map(|(o, s)| (o, s, find_potential_relpointers(&data_chunks_u64, o)))
-            .collect_vec();
+        let blocked_ranges = find_blocked_ranges(endian, package_manager().version, &tag_data);
+        let raw_strings = find_raw_strings(
+            endian,
+            package_manager().version,
+            &tag_data,
+            &blocked_ranges,
+        )
+        .into_iter()
+        .map(|(o, s)| (o, s, find_potential_relpointers(&data_chunks_u64, o)))
+        .collect_vec();
 
         let mut arrays: Vec<(u64, TagArray)> = if matches!(
             package_manager().version,
@@ -250,17 +312,11 @@ impl TagView {
         let tag_entry = package_manager().get_entry(tag)?;
         let tag_type = TagType::from_type_subtype(tag_entry.file_type, tag_entry.file_subtype);
         let scan = ExtendedScanResult::from_scanresult(cache.hashes.get(&tag).cloned()?);
+        let identical_tags = cache.identical_tags(tag);
 
+        let texture_premultiply_alpha = settings().premultiply_alpha;
         let texture = if tag_type.is_texture() && tag_type.is_header() {
-            Texture::load(&render_state, tag, true).map(|t| {
-                let egui_handle = render_state.renderer.write().register_native_texture(
-                    &render_state.device,
-                    &t.view,
-                    wgpu::FilterMode::Linear,
-                );
-
-                (t, egui_handle)
-            })
+            Self::load_texture(&render_state, tag, texture_premultiply_alpha)
         } else {
             Err(anyhow::anyhow!("Tag is not a texture header"))
         };
@@ -269,17 +325,43 @@ impl TagView {
             package_manager()
                 .read_tag(tag_entry.reference)
                 .ok()
-                .map(TagHexView::new)
+                .map(|data| {
+                    let scan = ExtendedScanResult::from_raw_data(&data, endian);
+                    (TagHexView::new(data), scan)
+                })
         } else {
             None
         };
 
+        let hexview = TagHexView::new(tag_data.clone());
+        if settings().hex_scroll_to_first_reference {
+            if let Some(offset) = scan
+                .file_hashes
+                .iter()
+                .map(|h| h.offset)
+                .filter(|&o| o != u64::MAX)
+                .min()
+            {
+                hexview.scroll_to(offset);
+            }
+        }
+
         Some(Self {
-            hexview: TagHexView::new(tag_data.clone()),
+            textview: TagTextView::detect(&tag_data),
+            curves: None,
+            scan_diff: None,
+            highlight_scan_diff: true,
+            hexview,
             hexview_referenced,
-            mode: TagViewMode::Traversal,
+            mode: if settings().open_tags_in_hex_view {
+                TagViewMode::Hex
+            } else {
+                TagViewMode::Traversal
+            },
 
             arrays,
+            identical_tags,
+            blocked_ranges,
             string_hashes,
             raw_string_hashes,
             tag,
@@ -289,15 +371,30 @@ impl TagView {
             tag_data,
 
             texture,
+            texture_premultiply_alpha,
+            texture_decoded: None,
+            texture_show_decoded: false,
+            texture_comparison: None,
+            texture_zoom: 1.0,
+            texture_pan: egui::Vec2::ZERO,
+            referencing_materials: None,
 
             scan,
             cache,
             tag_history,
             traversal_depth_limit: 16,
             tag_traversal: None,
+            traversal_partial: String::new(),
+            traversal_rx: None,
+            traversal_cancel: Arc::new(AtomicBool::new(false)),
             traversal_show_strings: false,
+            traversal_follow_skipped: settings().traverse_skipped_classes,
             traversal_interactive: false,
             hide_already_traversed: true,
+            traversal_search: String::new(),
+            traversal_search_current: 0,
+            traversal_search_jump: false,
+            traversal_search_results: None,
 
             search_tagtype: TagType::Tag,
             search_reference: u32::MAX,
@@ -305,6 +402,15 @@ impl TagView {
             search_depth_limit: 32,
             search_package_name_filter: String::new(),
             search_results: vec![],
+            search_result_paths: Default::default(),
+            search_pruned: vec![],
+            search_show_paths: false,
+            path_target_input: String::new(),
+            path_direction: ReferenceDirection::Outgoing,
+            path_depth_limit: 8,
+            path_results: Default::default(),
+            reference_cursor: None,
+            search_selection: TagSelection::default(),
 
             string_cache,
             raw_string_hash_cache,
@@ -315,6 +421,78 @@ impl TagView {
         })
     }
 
+    /// Switches to the hex view and scrolls to the given offset
+    fn jump_to_offset(&mut self, offset: u64, in_referenced_data: bool) {
+        match &mut self.hexview_referenced {
+            Some((hexview, _)) if in_referenced_data => {
+                hexview.jump_to(offset);
+                self.mode = TagViewMode::HexReferenced;
+            }
+            _ => {
+                self.hexview.jump_to(offset);
+                self.mode = TagViewMode::Hex;
+            }
+        }
+    }
+
+    fn load_texture(
+        render_state: &RenderState,
+        tag: TagHash,
+        premultiply_alpha: bool,
+    ) -> anyhow::Result<(Texture, TextureId)> {
+        Texture::load(render_state, tag, premultiply_alpha).map(|t| {
+            let egui_handle = render_state.renderer.write().register_native_texture(
+                &render_state.device,
+                &t.view,
+                wgpu::FilterMode::Linear,
+            );
+
+            (t, egui_handle)
+        })
+    }
+
+    const TEXTURE_ZOOM_MIN: f32 = 0.1;
+    const TEXTURE_ZOOM_MAX: f32 = 64.0;
+
+    fn reload_texture(&mut self) {
+        if let Ok((_, egui_tex)) = self.texture {
+            self.render_state.renderer.write().free_texture(&egui_tex);
+        }
+        if let Some(Ok((_, egui_tex))) = self.texture_decoded.take() {
+            self.render_state.renderer.write().free_texture(&egui_tex);
+        }
+
+        self.texture =
+            Self::load_texture(&self.render_state, self.tag, self.texture_premultiply_alpha);
+        if self.texture_show_decoded {
+            self.decode_texture();
+        }
+    }
+
+    /// Reloads the texture of this tag after the global texture settings have changed
+    pub fn apply_texture_settings(&mut self) {
+        if self.tag_type.is_texture() && self.tag_type.is_header() {
+            self.texture_premultiply_alpha = settings().premultiply_alpha;
+            self.reload_texture();
+        }
+    }
+
+    fn decode_texture(&mut self) {
+        let Ok((tex, _)) = &self.texture else {
+            return;
+        };
+
+        self.texture_decoded = Some(tex.to_decoded(&self.render_state, 0).map(|t| {
+            let egui_handle = self.render_state.renderer.write().register_native_texture(
+                &self.render_state.device,
+                &t.view,
+                wgpu::FilterMode::Linear,
+            );
+
+            (t, egui_handle)
+        }));
+    }
+
     /// Replaces this view with another tag
     pub fn open_tag(&mut self, tag: TagHash, push_history: bool) {
         if push_history {
@@ -331,23 +509,130 @@ impl TagView {
         ) {
             tv.traversal_depth_limit = self.traversal_depth_limit;
             tv.traversal_show_strings = self.traversal_show_strings;
+            tv.traversal_follow_skipped = self.traversal_follow_skipped;
             tv.traversal_interactive = self.traversal_interactive;
             tv.mode = self.mode;
             tv.search_tagtype = self.search_tagtype;
             tv.search_reference = self.search_reference;
             tv.search_depth_limit = self.search_depth_limit;
+            tv.search_show_paths = self.search_show_paths;
 
             *self = tv;
+        } else if let Some(reason) = tag_missing_reason(tag) {
+            error!("Could not open new tag view for {tag}: {reason}");
+            TOASTS.lock().error(reason);
         } else {
             error!("Could not open new tag view for {tag} (tag not found in cache)");
         }
     }
 
+    pub fn tag(&self) -> TagHash {
+        self.tag
+    }
+
+    /// Compares the current scan result against the one from before the cache was regenerated
+    pub fn set_scan_before_regen(&mut self, old_scan: &ScanResult) {
+        if let Some(new_scan) = self.cache.hashes.get(&self.tag) {
+            self.scan_diff = Some(diff_scan_results(self.tag, old_scan, new_scan));
+        }
+    }
+
+    /// Whether an outgoing reference was added by the cache regeneration
+    fn is_new_reference(&self, hash: &ExtendedTagHash) -> bool {
+        self.highlight_scan_diff
+            && self
+                .scan_diff
+                .as_ref()
+                .is_some_and(|d| d.added_references.contains(&hash.to_string()))
+    }
+
+    /// Re-creates this view for the current tag, eg. after the schema file has changed
+    pub fn reload(&mut self) {
+        self.open_tag(self.tag, false);
+    }
+
+    fn start_traversal(&mut self, direction: TraversalDirection) {
+        let tag = self.tag;
+        let cache = self.cache.clone();
+        let strings = self.string_cache.clone();
+        let raw_strings = self.raw_string_hash_cache.clone();
+        let depth_limit = self.traversal_depth_limit;
+        let show_strings = self.traversal_show_strings;
+        let follow_skipped = self.traversal_follow_skipped;
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.traversal_partial.clear();
+        self.traversal_search_results = None;
+        self.traversal_rx = Some(rx);
+        self.traversal_cancel = cancel.clone();
+
+        self.tag_traversal = Some(Promise::spawn_thread("traverse tags", move || {
+            traverse_tags(
+                tag,
+                depth_limit,
+                cache,
+                strings,
+                raw_strings,
+                show_strings,
+                follow_skipped,
+                direction,
+                TraversalOutput::new(Some(tx), Some(cancel)),
+            )
+        }));
+    }
+
+    fn spawn_bundle_export(&self, include_non_structure: bool) {
+        let tag = self.tag;
+        let depth_limit = self.traversal_depth_limit;
+        let follow_skipped = self.traversal_follow_skipped;
+        let cache = self.cache.clone();
+        let strings = self.string_cache.clone();
+        let raw_strings = self.raw_string_hash_cache.clone();
+        let directory = PathBuf::from("dump").join(format!("bundle_{tag}"));
+
+        spawn_task(format!("Exporting bundle for {tag}"), move |progress| {
+            let count = export_bundle(
+                tag,
+                depth_limit,
+                follow_skipped,
+                include_non_structure,
+                cache,
+                strings,
+                raw_strings,
+                &directory,
+                progress,
+            )?;
+            TOASTS
+                .lock()
+                .success(format!("Exported {count} tags to {}", directory.display()));
+            Ok(())
+        });
+    }
+
+    /// Shows the localized strings of a traversed tag next to its label, if string finding is enabled
+    fn traversed_strings_ui(&self, ui: &mut egui::Ui, tag: TagHash) {
+        if !self.traversal_show_strings {
+            return;
+        }
+
+        let strings = localized_strings_in_tag(&self.cache, &self.string_cache, tag);
+        if strings.is_empty() {
+            return;
+        }
+
+        let preview = strings.iter().map(|s| format!("{s:?}")).join(", ");
+        ui.add(egui::Label::new(RichText::new(preview).weak()).truncate())
+            .on_hover_text(strings.join("\n"));
+    }
+
+    /// `node_index` is the index of this node in [`TraversalSearchResults::nodes`]
     pub fn traverse_interactive_ui(
         &self,
         ui: &mut egui::Ui,
         traversed: &TraversedTag,
         depth: usize,
+        node_index: &mut usize,
     ) -> Option<TagHash> {
         let mut open_new_tag = None;
         let mut is_texture = false;
@@ -356,31 +641,48 @@ impl TagView {
             return None;
         }
 
-        let tag_label = if let Some(entry) = &traversed.entry {
+        let search_node = self
+            .traversal_search_results
+            .as_ref()
+            .and_then(|r| r.nodes.get(*node_index))
+            .copied();
+        *node_index += 1;
+
+        let label_text = self.traversed_tag_label(traversed);
+        let mut is_current_match = false;
+        let mut tag_label = if let Some(entry) = &traversed.entry {
             let tagtype = TagType::from_type_subtype(entry.file_type, entry.file_subtype);
             is_texture = tagtype.is_texture() && tagtype.is_header();
 
-            let fancy_tag = format_tag_entry(traversed.tag, Some(entry));
-            let reason = traversed
-                .reason
-                .as_ref()
-                .map(|r| format!(" ({r})"))
-                .unwrap_or_default();
-
-            egui::RichText::new(format!("{fancy_tag}{reason}")).color(tagtype.display_color())
+            egui::RichText::new(&label_text).color(tagtype.display_color())
         } else {
-            egui::RichText::new(format!("{} (pkg entry not found)", traversed.tag))
-                .color(Color32::LIGHT_RED)
+            egui::RichText::new(&label_text).color(Color32::LIGHT_RED)
         };
 
+        if let Some(node) = search_node.filter(|n| n.is_match) {
+            is_current_match = node.matches_before == self.traversal_search_current;
+            tag_label = tag_label.background_color(search_match_color(is_current_match));
+        }
+        let scroll_to_match = is_current_match && self.traversal_search_jump;
+
         ui.style_mut().spacing.indent = 16.0;
         if traversed.subtags.is_empty() {
             ui.horizontal(|ui| {
-                if ui
-                    .add_enabled(depth > 0, egui::SelectableLabel::new(false, tag_label))
+                let mut response =
+                    ui.add_enabled(depth > 0, egui::SelectableLabel::new(false, tag_label));
+                if scroll_to_match {
+                    response.scroll_to_me(Some(egui::Align::Center));
+                }
+
+                if !traversed.first_seen.is_empty() {
+                    response = response.on_hover_ui(|ui| first_seen_ui(ui, &traversed.first_seen));
+                }
+
+                let clicked = response
                     .tag_context_with_texture(traversed.tag, &self.texture_cache, is_texture)
-                    .clicked()
-                {
+                    .clicked();
+                self.traversed_strings_ui(ui, traversed.tag);
+                if clicked {
                     if ui.input(|i| i.modifiers.ctrl)
                         && traversed
                             .entry
@@ -395,107 +697,226 @@ impl TagView {
                 }
             });
         } else {
-            CollapsingState::load_with_default_open(
+            let children_start = *node_index;
+            let mut state = CollapsingState::load_with_default_open(
                 ui.ctx(),
                 egui::Id::new(format!(
                     "traversed_tag{}_collapse_depth{depth}",
                     traversed.tag
                 )),
                 true,
-            )
-            .show_header(ui, |ui| {
-                ui.horizontal(|ui| {
-                    let response =
-                        ui.add_enabled(depth > 0, egui::SelectableLabel::new(false, tag_label));
-
-                    if response
-                        .tag_context_with_texture(traversed.tag, &self.texture_cache, is_texture)
-                        .clicked()
-                    {
-                        open_new_tag = Some(traversed.tag);
-                    }
-                });
-            })
-            .body_unindented(|ui| {
-                ui.style_mut().spacing.indent = 16.0 * 2.;
-                ui.indent(format!("traversed_tag{}_indent", traversed.tag), |ui| {
-                    for t in &traversed.subtags {
-                        if let Some(new_tag) = self.traverse_interactive_ui(ui, t, depth + 1) {
-                            open_new_tag = Some(new_tag);
+            );
+
+            // Make sure the selected match isn't hidden inside of a collapsed node
+            if let Some(node) = search_node {
+                if self.traversal_search_jump
+                    && node
+                        .descendant_match_range()
+                        .contains(&self.traversal_search_current)
+                {
+                    state.set_open(true);
+                }
+            }
+
+            state
+                .show_header(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        let response =
+                            ui.add_enabled(depth > 0, egui::SelectableLabel::new(false, tag_label));
+                        if scroll_to_match {
+                            response.scroll_to_me(Some(egui::Align::Center));
                         }
-                    }
+
+                        if response
+                            .tag_context_with_texture(
+                                traversed.tag,
+                                &self.texture_cache,
+                                is_texture,
+                            )
+                            .clicked()
+                        {
+                            open_new_tag = Some(traversed.tag);
+                        }
+                        self.traversed_strings_ui(ui, traversed.tag);
+                    });
+                })
+                .body_unindented(|ui| {
+                    ui.style_mut().spacing.indent = 16.0 * 2.;
+                    ui.indent(format!("traversed_tag{}_indent", traversed.tag), |ui| {
+                        for t in &traversed.subtags {
+                            if let Some(new_tag) =
+                                self.traverse_interactive_ui(ui, t, depth + 1, node_index)
+                            {
+                                open_new_tag = Some(new_tag);
+                            }
+                        }
+                    });
                 });
-            });
+
+            // Collapsed children aren't drawn, so skip over their nodes
+            if let Some(node) = search_node {
+                *node_index = children_start + node.descendants;
+            }
         }
 
         open_new_tag
     }
 
+    fn traversed_tag_label(&self, traversed: &TraversedTag) -> String {
+        if let Some(entry) = &traversed.entry {
+            let reason = traversed
+                .reason
+                .as_ref()
+                .map(|r| format!(" ({r})"))
+                .unwrap_or_default();
+
+            format!(
+                "{}{}{reason}",
+                read_failed_marker(&self.cache, traversed.tag),
+                format_tag_entry(traversed.tag, Some(entry))
+            )
+        } else {
+            format!("{} (pkg entry not found)", traversed.tag)
+        }
+    }
+
+    /// Searches the finished traversal for `traversal_search`.
+    /// Returns `None` if the query and display options haven't changed since [`Self::traversal_search_results`] was computed
+    fn search_traversal(
+        &self,
+        trav_interactive: &TraversedTag,
+        trav_static: &str,
+    ) -> Option<TraversalSearchResults> {
+        let query = self.traversal_search.to_lowercase();
+        if self.traversal_search_results.as_ref().is_some_and(|r| {
+            r.query == query
+                && r.interactive == self.traversal_interactive
+                && r.hide_already_traversed == self.hide_already_traversed
+        }) {
+            return None;
+        }
+
+        let mut results = TraversalSearchResults {
+            query,
+            interactive: self.traversal_interactive,
+            hide_already_traversed: self.hide_already_traversed,
+            static_lines: vec![],
+            matching_lines: vec![],
+            nodes: vec![],
+            match_count: 0,
+        };
+
+        let mut offset = 0;
+        for line in trav_static.split_inclusive('\n') {
+            let len = line.trim_end_matches(&['\r', '\n'][..]).len();
+            results.static_lines.push(offset..offset + len);
+            offset += line.len();
+        }
+
+        if !results.query.is_empty() {
+            if results.interactive {
+                self.collect_traversal_search_nodes(
+                    std::slice::from_ref(trav_interactive),
+                    &mut results,
+                );
+            } else {
+                results.matching_lines = results
+                    .static_lines
+                    .iter()
+                    .positions(|l| {
+                        trav_static[l.clone()]
+                            .to_lowercase()
+                            .contains(&results.query)
+                    })
+                    .collect();
+                results.match_count = results.matching_lines.len();
+            }
+        }
+
+        Some(results)
+    }
+
+    /// Adds the visible nodes of the given trees to `results.nodes`, in the order they're drawn
+    fn collect_traversal_search_nodes(
+        &self,
+        tags: &[TraversedTag],
+        results: &mut TraversalSearchResults,
+    ) {
+        for t in tags {
+            if self.hide_already_traversed && t.reason.is_some() {
+                continue;
+            }
+
+            let index = results.nodes.len();
+            let is_match = self
+                .traversed_tag_label(t)
+                .to_lowercase()
+                .contains(&results.query);
+            results.nodes.push(TraversalSearchNode {
+                matches_before: results.match_count,
+                is_match,
+                descendants: 0,
+                descendant_matches: 0,
+            });
+            if is_match {
+                results.match_count += 1;
+            }
+
+            let matches_before_children = results.match_count;
+            self.collect_traversal_search_nodes(&t.subtags, results);
+            results.nodes[index].descendants = results.nodes.len() - index - 1;
+            results.nodes[index].descendant_matches = results.match_count - matches_before_children;
+        }
+    }
+
     pub fn traverse_ui(&mut self, ui: &mut egui::Ui) -> Option<TagHash> {
         let mut open_new_tag = None;
         if !self.scan.successful {
             ui.heading(RichText::new("⚠ Tag data failed to read").color(Color32::YELLOW));
+            if let Some(error) = &self.scan.read_error {
+                ui.colored_label(Color32::YELLOW, error);
+            }
+        }
+
+        if self.cache.skipped_too_large(self.tag) {
+            ui.colored_label(
+                Color32::YELLOW,
+                format!(
+                    "⚠ Tag was not scanned for references, as it is larger than the max tag size ({})",
+                    format_file_size(self.cache.max_tag_size as usize)
+                ),
+            );
         }
 
         if self.tag_type.is_tag() {
             ui.horizontal_wrapped(|ui| {
+                let traversal_ready = self
+                    .tag_traversal
+                    .as_ref()
+                    .map(|v| v.poll().is_ready())
+                    .unwrap_or(true);
+
                 if ui
-                    .add_enabled(
-                        self.tag_traversal
-                            .as_ref()
-                            .map(|v| v.poll().is_ready())
-                            .unwrap_or(true),
-                        egui::Button::new("Traverse children"),
-                    )
+                    .add_enabled(traversal_ready, egui::Button::new("Traverse children"))
                     .clicked()
                 {
-                    let tag = self.tag;
-                    let cache = self.cache.clone();
-                    let string_cache = self.raw_string_hash_cache.clone();
-                    let depth_limit = self.traversal_depth_limit;
-                    let show_strings = self.traversal_show_strings;
-                    self.tag_traversal = Some(Promise::spawn_thread("traverse tags", move || {
-                        traverse_tags(
-                            tag,
-                            depth_limit,
-                            cache,
-                            string_cache,
-                            show_strings,
-                            TraversalDirection::Down,
-                        )
-                    }));
+                    self.start_traversal(TraversalDirection::Down);
                 }
 
                 if ui
-                    .add_enabled(
-                        self.tag_traversal
-                            .as_ref()
-                            .map(|v| v.poll().is_ready())
-                            .unwrap_or(true),
-                        egui::Button::new("Traverse ancestors"),
-                    )
+                    .add_enabled(traversal_ready, egui::Button::new("Traverse ancestors"))
                     .clicked()
                 {
-                    let tag = self.tag;
-                    let cache = self.cache.clone();
-                    let string_cache = self.raw_string_hash_cache.clone();
-                    let depth_limit = self.traversal_depth_limit;
-                    let show_strings = self.traversal_show_strings;
-                    self.tag_traversal = Some(Promise::spawn_thread("traverse tags", move || {
-                        traverse_tags(
-                            tag,
-                            depth_limit,
-                            cache,
-                            string_cache,
-                            show_strings,
-                            TraversalDirection::Up,
-                        )
-                    }));
+                    self.start_traversal(TraversalDirection::Up);
+                }
+
+                if !traversal_ready && ui.button("Cancel").clicked() {
+                    self.traversal_cancel.store(true, Ordering::Relaxed);
                 }
 
                 if ui.button("Copy traversal").clicked() {
                     if let Some(traversal) = self.tag_traversal.as_ref() {
-                        if let Some((_, result)) = traversal.ready() {
+                        if let Some((_, result, _)) = traversal.ready() {
                             ui.output_mut(|o| o.copied_text = result.clone());
                         }
                     }
@@ -504,15 +925,17 @@ impl TagView {
                 ui.add(egui::DragValue::new(&mut self.traversal_depth_limit).range(1..=256));
                 ui.label("Max depth");
 
-                ui.checkbox(
-                    &mut self.traversal_show_strings,
-                    "Find strings (currently only shows raw strings)",
-                );
+                ui.checkbox(&mut self.traversal_show_strings, "Find strings")
+                    .on_hover_text(
+                        "Show the localized strings, raw strings and raw string hashes found in each tag",
+                    );
+                ui.checkbox(&mut self.traversal_follow_skipped, "Follow skipped classes")
+                    .on_hover_text("Traverse into classes marked with @skip_traversal");
                 ui.checkbox(&mut self.traversal_interactive, "Interactive");
                 ui.checkbox(&mut self.hide_already_traversed, "Hide already traversed");
 
                 if let Some(traversal) = self.tag_traversal.as_ref() {
-                    if let Some((trav_interactive, _)) = traversal.ready() {
+                    if let Some((trav_interactive, _, _)) = traversal.ready() {
                         let ctrl = ui.input(|i| i.modifiers.ctrl);
                         if ui
                             .button(format!(
@@ -533,32 +956,198 @@ impl TagView {
                                 error!("Failed to dump tag data: {e:?}");
                             }
                         }
+
+                        if ui
+                            .button(format!(
+                                "Export bundle{}",
+                                if ctrl { "+non-structure tag data" } else { "" }
+                            ))
+                            .on_hover_text("Exports this tag and all of its children to a directory, with a manifest.json describing the references between them. Shared children are only exported once")
+                            .clicked()
+                        {
+                            self.spawn_bundle_export(ctrl);
+                        }
+
+                        if ui
+                            .button("Save traversal as HTML")
+                            .on_hover_text(
+                                "Saves the traversal tree as a standalone, collapsible HTML page",
+                            )
+                            .clicked()
+                        {
+                            if let Ok(Some(path)) = native_dialog::FileDialog::new()
+                                .set_filename(&format!("traversal_{}.html", trav_interactive.tag))
+                                .add_filter("HTML", &["html"])
+                                .show_save_single_file()
+                            {
+                                match std::fs::write(&path, traversal_to_html(trav_interactive)) {
+                                    Ok(()) => {
+                                        TOASTS.lock().success("Traversal saved");
+                                    }
+                                    Err(e) => error!("Failed to save traversal: {e}"),
+                                }
+                            }
+                        }
                     }
                 }
             });
 
             if let Some(traversal) = self.tag_traversal.as_ref() {
-                if let Some((trav_interactive, trav_static)) = traversal.ready() {
-                    egui::ScrollArea::both()
-                        .auto_shrink([false; 2])
-                        .show(ui, |ui| {
-                            if self.traversal_interactive {
+                if let Some((trav_interactive, trav_static, pruned)) = traversal.ready() {
+                    if let Some(results) = self.search_traversal(trav_interactive, trav_static) {
+                        self.traversal_search_results = Some(results);
+                    }
+                    let search = self.traversal_search_results.as_ref().unwrap();
+                    let has_query = !search.query.is_empty();
+                    let match_count = search.match_count;
+
+                    ui.horizontal(|ui| {
+                        ui.label("Find:");
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut self.traversal_search)
+                                .hint_text("Tag hash, class name or string (Ctrl+F)")
+                                .desired_width(320.0),
+                        );
+                        if ui.input(|i| i.modifiers.command && i.key_pressed(egui::Key::F)) {
+                            response.request_focus();
+                        }
+
+                        if response.changed() {
+                            self.traversal_search_current = 0;
+                            self.traversal_search_jump = true;
+                        }
+
+                        let submitted =
+                            response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        if submitted {
+                            response.request_focus();
+                        }
+
+                        if ui
+                            .add_enabled(match_count > 0, egui::Button::new("⏶"))
+                            .on_hover_text("Previous match")
+                            .clicked()
+                        {
+                            self.traversal_search_current =
+                                (self.traversal_search_current + match_count - 1) % match_count;
+                            self.traversal_search_jump = true;
+                        }
+
+                        if ui
+                            .add_enabled(match_count > 0, egui::Button::new("⏷"))
+                            .on_hover_text("Next match (Enter)")
+                            .clicked()
+                            || (submitted && match_count > 0)
+                        {
+                            self.traversal_search_current =
+                                (self.traversal_search_current + 1) % match_count;
+                            self.traversal_search_jump = true;
+                        }
+
+                        if has_query {
+                            if match_count == 0 {
+                                ui.label(RichText::new("No matches").italics());
+                            } else {
+                                self.traversal_search_current =
+                                    self.traversal_search_current.min(match_count - 1);
+                                ui.label(format!(
+                                    "{}/{match_count}",
+                                    self.traversal_search_current + 1
+                                ));
+                            }
+                        }
+                    });
+
+                    open_new_tag =
+                        open_new_tag.or(pruned_references_ui(ui, "traversal_pruned", pruned));
+
+                    if self.traversal_interactive {
+                        egui::ScrollArea::both()
+                            .auto_shrink([false; 2])
+                            .show(ui, |ui| {
                                 open_new_tag = open_new_tag.or(self.traverse_interactive_ui(
                                     ui,
                                     trav_interactive,
                                     0,
+                                    &mut 0,
                                 ));
-                            } else {
-                                ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
-                                ui.label(RichText::new(trav_static).monospace());
+                            });
+                    } else {
+                        let search = self.traversal_search_results.as_ref().unwrap();
+                        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+                        let mut scroll_area = egui::ScrollArea::both().auto_shrink([false; 2]);
+                        if self.traversal_search_jump {
+                            if let Some(&line) =
+                                search.matching_lines.get(self.traversal_search_current)
+                            {
+                                let row_stride = row_height + ui.spacing().item_spacing.y;
+                                scroll_area = scroll_area.vertical_scroll_offset(
+                                    (line as f32 * row_stride - ui.available_height() / 2.0)
+                                        .max(0.0),
+                                );
+                            }
+                        }
+
+                        let current_line = search.matching_lines.get(self.traversal_search_current);
+                        let line_count = search.static_lines.len();
+                        scroll_area.show_rows(ui, row_height, line_count, |ui, range| {
+                            ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+                            for i in range {
+                                let line = &trav_static[search.static_lines[i].clone()];
+                                let mut text = RichText::new(line).monospace();
+                                if search.matching_lines.binary_search(&i).is_ok() {
+                                    text = text.background_color(search_match_color(
+                                        current_line == Some(&i),
+                                    ));
+                                }
+                                ui.label(text);
                             }
                         });
+                    }
+
+                    self.traversal_search_jump = false;
                 } else {
-                    ui.spinner();
-                    ui.label("Traversing tags");
+                    if let Some(rx) = self.traversal_rx.as_ref() {
+                        self.traversal_partial.extend(rx.try_iter());
+                    }
+
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Traversing tags");
+                    });
+
+                    egui::ScrollArea::both()
+                        .auto_shrink([false; 2])
+                        .stick_to_bottom(true)
+                        .show(ui, |ui| {
+                            ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+                            ui.label(RichText::new(&self.traversal_partial).monospace());
+                        });
+
+                    ui.ctx().request_repaint();
                 }
             }
         } else if self.tag_type.is_texture() && self.tag_type.is_header() {
+            if ui
+                .checkbox(&mut self.texture_premultiply_alpha, "Premultiply alpha")
+                .changed()
+            {
+                self.reload_texture();
+            }
+
+            let is_compressed =
+                matches!(&self.texture, Ok((tex, _)) if tex.desc.format.is_compressed());
+            if is_compressed
+                && ui
+                    .checkbox(&mut self.texture_show_decoded, "Show decoded RGBA")
+                    .on_hover_text("Decodes the first layer to RGBA8 instead of sampling the block compressed texture directly")
+                    .changed()
+                && self.texture_show_decoded
+                && self.texture_decoded.is_none()
+            {
+                self.decode_texture();
+            }
+
             match &self.texture {
                 Ok((tex, egui_texture)) => {
                     let min_dimension = ui.available_size().min_elem();
@@ -573,27 +1162,194 @@ impl TagView {
                             min_dimension,
                         )
                     } * 0.8;
-                    let (response, painter) = ui.allocate_painter(size, Sense::hover());
-                    ui_image_rotated(
-                        &painter,
-                        *egui_texture,
-                        response.rect,
-                        // Rotate the image if it's a cubemap
-                        if tex.desc.array_size == 6 { 90. } else { 0. },
-                        tex.desc.array_size == 6,
-                    );
+                    let (response, painter) = ui.allocate_painter(size, Sense::click_and_drag());
 
-                    ui.label(tex.desc.info());
+                    if response.double_clicked() {
+                        self.texture_zoom = 1.0;
+                        self.texture_pan = egui::Vec2::ZERO;
+                    } else if response.dragged() {
+                        self.texture_pan += response.drag_delta();
+                    }
 
-                    if let Some(ref comment) = tex.comment {
-                        ui.collapsing("Texture Header", |ui| {
-                            ui.weak(comment);
-                        });
+                    if let Some(pointer) = response.hover_pos() {
+                        let scroll = ui.input(|i| i.smooth_scroll_delta.y);
+                        if scroll != 0.0 {
+                            let new_zoom = (self.texture_zoom * (scroll * 0.005).exp())
+                                .clamp(Self::TEXTURE_ZOOM_MIN, Self::TEXTURE_ZOOM_MAX);
+
+                            // Keep the point under the cursor in place
+                            let cursor = pointer - response.rect.center();
+                            self.texture_pan = cursor
+                                - (cursor - self.texture_pan) * (new_zoom / self.texture_zoom);
+                            self.texture_zoom = new_zoom;
+                        }
                     }
+
+                    let image_rect = egui::Rect::from_center_size(
+                        response.rect.center() + self.texture_pan,
+                        response.rect.size() * self.texture_zoom,
+                    );
+                    let comparison_texture = self
+                        .texture_comparison
+                        .as_ref()
+                        .and_then(|c| c.as_ref().ok())
+                        .and_then(|c| c.displayed_texture());
+                    let replacement_texture = match &self.texture_decoded {
+                        _ if comparison_texture.is_some() => comparison_texture,
+                        Some(Ok((_, decoded))) if is_compressed && self.texture_show_decoded => {
+                            Some(*decoded)
+                        }
+                        _ => None,
+                    };
+
+                    if let Some(replacement) = replacement_texture {
+                        ui_image_rotated(&painter, replacement, image_rect, 0., false);
+                    } else {
+                        ui_image_rotated(
+                            &painter,
+                            *egui_texture,
+                            image_rect,
+                            // Rotate the image if it's a cubemap
+                            if tex.desc.array_size == 6 { 90. } else { 0. },
+                            tex.desc.array_size == 6,
+                        );
+                    }
+
+                    if let Some(Err(e)) = &self.texture_decoded {
+                        if self.texture_show_decoded {
+                            ui.colored_label(
+                                Color32::RED,
+                                format!("⚠ Failed to decode texture: {e}"),
+                            );
+                        }
+                    }
+
+                    ui.horizontal(|ui| {
+                        ui.label(format!("Zoom: {:.0}%", self.texture_zoom * 100.0));
+                        ui.weak("(scroll to zoom, drag to pan, double-click to reset)");
+                    });
+                    ui.label(tex.desc.info());
+                    if !tex.partial.is_complete() {
+                        ui.colored_label(
+                            Color32::YELLOW,
+                            "⚠ Texture data is incomplete, missing data is shown as black",
+                        );
+                        for e in &tex.partial.read_errors {
+                            ui.colored_label(Color32::YELLOW, e);
+                        }
+                        if let Some(mismatch) = &tex.partial.size_mismatch {
+                            texture_size_mismatch_ui(ui, mismatch);
+                        }
+                    }
+
+                    if let Some(ref comment) = tex.comment {
+                        ui.collapsing("Texture Header", |ui| {
+                            let fields = parse_debug_fields(comment);
+                            if ui.button("📋 Copy").clicked() {
+                                let text = fields
+                                    .iter()
+                                    .map(|(depth, key, value)| {
+                                        let indent = "  ".repeat(*depth);
+                                        if key.is_empty() {
+                                            format!("{indent}{value}")
+                                        } else {
+                                            format!("{indent}{key}: {value}")
+                                        }
+                                    })
+                                    .join("\n");
+                                ui.output_mut(|o| o.copied_text = text);
+                            }
+
+                            egui::Grid::new("texture_header_fields")
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for (depth, key, value) in &fields {
+                                        ui.horizontal(|ui| {
+                                            ui.add_space(*depth as f32 * 12.0);
+                                            ui.strong(key);
+                                        });
+                                        ui.monospace(value);
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                    }
+
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button("Find referencing materials")
+                            .on_hover_text("Lists the techniques and shaders that reference this texture, either directly or through intermediate tags")
+                            .clicked()
+                        {
+                            self.referencing_materials =
+                                Some(find_referencing_materials(&self.cache, self.tag));
+                        }
+
+                        if let Some(materials) = &self.referencing_materials {
+                            ui.label(format!("{} found", materials.len()));
+                        }
+                    });
+
+                    if let Some(materials) = &self.referencing_materials {
+                        if materials.is_empty() {
+                            ui.label(RichText::new("No referencing materials found").italics());
+                        }
+
+                        for (tag, entry) in materials {
+                            if ui
+                                .selectable_label(false, format_tag_entry(*tag, Some(entry)))
+                                .tag_context(*tag)
+                                .clicked()
+                            {
+                                open_new_tag = Some(*tag);
+                            }
+                        }
+                    }
+
+                    ui.collapsing("Compare with reference image", |ui| {
+                        ui.horizontal(|ui| {
+                            if ui.button("Load reference image…").clicked() {
+                                if let Ok(Some(path)) = native_dialog::FileDialog::new()
+                                    .add_filter("Images", &["png", "exr"])
+                                    .show_open_single_file()
+                                {
+                                    if let Some(Ok(old)) = self.texture_comparison.take() {
+                                        old.free(&self.render_state);
+                                    }
+                                    self.texture_comparison = Some(TextureComparison::new(
+                                        &self.render_state,
+                                        tex,
+                                        &path,
+                                    ));
+                                }
+                            }
+
+                            if self.texture_comparison.is_some() && ui.button("Clear").clicked() {
+                                if let Some(Ok(old)) = self.texture_comparison.take() {
+                                    old.free(&self.render_state);
+                                }
+                            }
+                        });
+
+                        match &mut self.texture_comparison {
+                            Some(Ok(comparison)) => comparison.ui(ui),
+                            Some(Err(e)) => {
+                                ui.colored_label(
+                                    Color32::RED,
+                                    format!("⚠ Failed to compare texture: {e}"),
+                                );
+                            }
+                            None => {}
+                        }
+                    });
                 }
                 Err(e) => {
-                    ui.colored_label(Color32::RED, "⚠ Failed to load texture");
-                    ui.colored_label(Color32::RED, strip_ansi_codes(&format!("{e:?}")));
+                    if let Some(reason) = Texture::unsupported_reason() {
+                        ui.colored_label(Color32::YELLOW, format!("⚠ {reason}"));
+                    } else {
+                        ui.colored_label(Color32::RED, "⚠ Failed to load texture");
+                        ui.colored_label(Color32::RED, strip_ansi_codes(&format!("{e:?}")));
+                    }
                 }
             }
         } else {
@@ -610,8 +1366,8 @@ impl TagView {
         }
 
         let float_count = self.tag_data.len() / 4;
-        let data_f32: Vec<f32> = match bytemuck::try_cast_slice(&self.tag_data[..float_count * 4]) {
-            Ok(data) => data.to_vec(),
+        let data_f32: &[f32] = match bytemuck::try_cast_slice(&self.tag_data[..float_count * 4]) {
+            Ok(data) => data,
             Err(e) => {
                 ui.label("Failed to convert data to floats");
                 ui.label(format!("{e:?}"));
@@ -619,29 +1375,42 @@ impl TagView {
             }
         };
 
-        for (i, row) in data_f32.chunks(4).enumerate() {
-            // Check if all values are reasonable enough to be floats. Any very low/high values (with exponents) are likely not floats.
-
-            let mut all_valid = row
-                .iter()
-                .all(|&v| (v.is_normal() && v.abs() < 1e7 && v.abs() > 1e-10) || v == 0.0);
-            if row.iter().all(|&v| v == 0.0) {
-                all_valid = false;
-            }
-
-            if all_valid {
-                ui.horizontal(|ui| {
-                    ui.strong(format!("{:08X}:", i * 16));
-                    for &value in row {
-                        ui.label(format!("{:.4}", value));
+        let row_count = data_f32.len().div_ceil(4);
+        let row_height = ui.text_style_height(&egui::TextStyle::Body);
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show_rows(ui, row_height, row_count, |ui, row_range| {
+                for i in row_range {
+                    let row = &data_f32[i * 4..(i * 4 + 4).min(data_f32.len())];
+
+                    // Check if all values are reasonable enough to be floats
+                    let mut all_valid = row.iter().all(|&v| is_plausible_float(v));
+                    if row.iter().all(|&v| v == 0.0) {
+                        all_valid = false;
                     }
-                });
-            }
-        }
+
+                    ui.horizontal(|ui| {
+                        if all_valid {
+                            ui.strong(format!("{:08X}:", i * 16));
+                            for &value in row {
+                                ui.label(format!("{:.4}", value));
+                            }
+                        } else {
+                            // Rows have to keep a fixed height, so invalid rows are only dimmed
+                            ui.weak(format!("{:08X}:", i * 16));
+                        }
+                    });
+                }
+            });
     }
 
     #[must_use]
     pub fn search_ui(&mut self, ui: &mut egui::Ui) -> Option<TagHash> {
+        let open_path = egui::CollapsingHeader::new("Find path to tag")
+            .show(ui, |ui| self.reference_path_ui(ui))
+            .body_returned
+            .flatten();
+
         ui.label(RichText::new("Perform a search for a specific tag type").italics());
 
         ui.horizontal(|ui| {
@@ -674,15 +1443,33 @@ impl TagView {
             ui.label("Package name filter");
         });
 
-        if ui.button("Search").clicked() {
-            self.search_results = perform_tagsearch(
+        let mut search_clicked = false;
+        ui.horizontal(|ui| {
+            search_clicked = ui.button("Search").clicked();
+            ui.checkbox(&mut self.search_show_paths, "Show reference paths")
+                .on_hover_text("Shows how each result is reached from this tag");
+        });
+
+        if search_clicked {
+            self.search_selection.clear();
+            let (results, pruned) = perform_tagsearch(
                 &self.cache,
                 self.tag,
                 self.search_tagtype,
                 self.search_reference,
                 self.search_depth_limit,
                 self.search_min_depth,
+                self.traversal_follow_skipped,
             );
+            self.search_pruned = pruned;
+            self.search_result_paths = results
+                .iter()
+                .map(|(tag, _, path)| (*tag, path.clone()))
+                .collect();
+            self.search_results = results
+                .into_iter()
+                .map(|(tag, entry, _)| (tag, entry))
+                .collect();
 
             if !self.search_package_name_filter.is_empty() {
                 self.search_results.retain(|(tag, _)| {
@@ -699,55 +1486,414 @@ impl TagView {
             }
         }
 
+        let open_pruned = pruned_references_ui(ui, "search_pruned", &self.search_pruned);
+
         ui.separator();
 
-        let mut result = None;
-        egui::ScrollArea::vertical().show_rows(ui, 22.0, self.search_results.len(), |ui, range| {
-            for (tag, entry) in &self.search_results[range] {
-                let tagtype = TagType::from_type_subtype(entry.file_type, entry.file_subtype);
+        let open_result = if self.search_show_paths {
+            self.search_paths_ui(ui)
+        } else {
+            tag_search_results_ui(
+                ui,
+                &self.search_results,
+                &mut self.search_selection,
+                &self.cache,
+                &self.texture_cache,
+            )
+        };
 
-                let fancy_tag = format_tag_entry(*tag, Some(entry));
+        open_path.or(open_pruned).or(open_result)
+    }
 
-                let tag_label = egui::RichText::new(fancy_tag).color(tagtype.display_color());
+    fn spawn_path_search(&self, target: TagHash) {
+        let tag = self.tag;
+        let cache = self.cache.clone();
+        let direction = self.path_direction;
+        let depth_limit = self.path_depth_limit;
+        let results = self.path_results.clone();
+
+        spawn_task(format!("Finding paths to {target}"), move |progress| {
+            let paths =
+                find_reference_paths(&cache, tag, target, direction, depth_limit, progress)?;
+            *results.lock() = Some((depth_limit, paths));
+            Ok(())
+        });
+    }
 
-                let response = ui.selectable_label(false, tag_label);
-                if response
-                    .tag_context_with_texture(
-                        *tag,
-                        &self.texture_cache,
-                        tagtype.is_texture() && tagtype.is_header(),
-                    )
-                    .clicked()
-                {
-                    result = Some(*tag)
+    /// Searches for the shortest chains of references connecting this tag to another one
+    #[must_use]
+    fn reference_path_ui(&mut self, ui: &mut egui::Ui) -> Option<TagHash> {
+        let mut search_clicked = false;
+        ui.horizontal(|ui| {
+            let response = ui.text_edit_singleline(&mut self.path_target_input);
+            ui.label("Target tag");
+            search_clicked = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+
+            egui::ComboBox::from_id_source("path_direction")
+                .selected_text(self.path_direction.to_string())
+                .show_ui(ui, |ui| {
+                    for d in ReferenceDirection::ALL {
+                        ui.selectable_value(&mut self.path_direction, d, d.to_string());
+                    }
+                });
+
+            ui.add(egui::DragValue::new(&mut self.path_depth_limit).range(1..=64));
+            ui.label("Max depth");
+
+            search_clicked |= ui.button("Find path").clicked();
+        });
+
+        if search_clicked {
+            match parse_tag_hash(self.path_target_input.trim()) {
+                Some(target) => self.spawn_path_search(target),
+                None => {
+                    TOASTS.lock().error("Invalid target tag");
                 }
             }
-        });
+        }
+
+        let path_results = self.path_results.lock();
+        let (depth_limit, paths) = path_results.as_ref()?;
+        if paths.is_empty() {
+            ui.label(
+                RichText::new(format!("No path found within {depth_limit} references")).italics(),
+            );
+            return None;
+        }
+
+        ui.label(format!(
+            "{} shortest path(s) of {} references{}",
+            paths.len(),
+            paths[0].len() - 1,
+            if paths.len() >= MAX_REFERENCE_PATHS {
+                " (limited)"
+            } else {
+                ""
+            }
+        ));
+
+        let mut result = None;
+        egui::ScrollArea::vertical()
+            .id_source("reference_paths")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                for path in paths {
+                    ui.horizontal_wrapped(|ui| {
+                        for (i, t) in path.iter().enumerate() {
+                            if i != 0 {
+                                ui.weak("→");
+                            }
+
+                            let entry = package_manager().get_entry(*t);
+                            let color = entry
+                                .as_ref()
+                                .map(|e| {
+                                    TagType::from_type_subtype(e.file_type, e.file_subtype)
+                                        .display_color()
+                                })
+                                .unwrap_or(Color32::GRAY);
+                            if ui
+                                .link(RichText::new(t.to_string()).color(color))
+                                .on_hover_text(format_tag_entry(*t, entry.as_ref()))
+                                .clicked()
+                            {
+                                result = Some(*t);
+                            }
+                        }
+
+                        if self.reveal_path_button(
+                            ui,
+                            path,
+                            "Opens the target and adds every tag along the path to the history",
+                        ) {
+                            result = path.last().copied();
+                        }
+                    });
+                }
+            });
+
+        result
+    }
+
+    /// Shows a "Reveal path" button for a chain of references starting at this tag.
+    /// When clicked, every tag in between is added to the history, so the caller only has to open the end of the path
+    fn reveal_path_button(&self, ui: &mut egui::Ui, path: &[TagHash], hover_text: &str) -> bool {
+        if path.len() <= 2
+            || !ui
+                .small_button("Reveal path")
+                .on_hover_text(hover_text)
+                .clicked()
+        {
+            return false;
+        }
+
+        let mut history = self.tag_history.borrow_mut();
+        for t in &path[1..path.len() - 1] {
+            history.push(*t);
+        }
+        true
+    }
+
+    /// Lists the search results along with the chain of references leading to them
+    #[must_use]
+    fn search_paths_ui(&mut self, ui: &mut egui::Ui) -> Option<TagHash> {
+        let mut result = None;
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                for (tag, entry) in &self.search_results {
+                    let tagtype = TagType::from_type_subtype(entry.file_type, entry.file_subtype);
+                    let path = self
+                        .search_result_paths
+                        .get(tag)
+                        .map(Vec::as_slice)
+                        .unwrap_or_default();
+
+                    ui.horizontal(|ui| {
+                        if ui
+                            .selectable_label(
+                                false,
+                                RichText::new(format_tag_entry(*tag, Some(entry)))
+                                    .color(tagtype.display_color()),
+                            )
+                            .tag_context_with_texture(
+                                *tag,
+                                &self.texture_cache,
+                                tagtype.is_texture() && tagtype.is_header(),
+                            )
+                            .clicked()
+                        {
+                            result = Some(*tag);
+                        }
+
+                        if self.reveal_path_button(
+                            ui,
+                            path,
+                            "Opens the result and adds every tag along the path to the history",
+                        ) {
+                            result = Some(*tag);
+                        }
+                    });
+
+                    ui.horizontal_wrapped(|ui| {
+                        ui.add_space(16.0);
+                        for (i, t) in path.iter().enumerate() {
+                            if i != 0 {
+                                ui.weak("→");
+                            }
+
+                            if ui
+                                .link(RichText::new(t.to_string()).weak())
+                                .on_hover_text(
+                                    package_manager()
+                                        .get_entry(*t)
+                                        .map(|e| format_tag_entry(*t, Some(&e)))
+                                        .unwrap_or_else(|| t.to_string()),
+                                )
+                                .clicked()
+                            {
+                                result = Some(*t);
+                            }
+                        }
+                    });
+                }
+            });
 
         result
     }
 
+    /// Writes the analysis of this tag to `path`. CSV files only contain the raw strings
+    fn export_analysis(&self, path: &Path) -> anyhow::Result<()> {
+        let is_csv = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("csv"));
+
+        if is_csv {
+            let mut f = std::io::BufWriter::new(File::create(path)?);
+            writeln!(f, "offset|string|relpointers")?;
+            for (offset, string, relpointers) in &self.raw_strings {
+                writeln!(
+                    f,
+                    "0x{offset:X}|{string}|{}",
+                    relpointers.iter().map(|o| format!("0x{o:X}")).join(",")
+                )?;
+            }
+        } else {
+            let analysis = TagAnalysisExport {
+                tag: self.tag.to_string(),
+                tag64: self.tag64.map(|t| t.to_string()),
+                tag_type: self.tag_type.to_string(),
+                references: self
+                    .scan
+                    .file_hashes
+                    .iter()
+                    .map(|h| AnalysisReference {
+                        offset: h.offset,
+                        tag: h.hash.hash32().to_string(),
+                    })
+                    .collect(),
+                string_hashes: self
+                    .string_hashes
+                    .iter()
+                    .map(|(offset, hash)| AnalysisStringHash {
+                        offset: *offset,
+                        hash: format!("{hash:08X}"),
+                        strings: self.string_cache.get(hash).cloned().unwrap_or_default(),
+                    })
+                    .collect(),
+                raw_strings: self
+                    .raw_strings
+                    .iter()
+                    .map(|(offset, string, relpointers)| AnalysisRawString {
+                        offset: *offset,
+                        string: string.clone(),
+                        relpointers: relpointers.clone(),
+                    })
+                    .collect(),
+                arrays: self
+                    .arrays
+                    .iter()
+                    .map(|(offset, array)| AnalysisArray {
+                        offset: *offset,
+                        class: format!("{:08X}", array.tagtype),
+                        class_name: get_class_by_id(array.tagtype).map(|c| c.name.to_string()),
+                        count: array.count,
+                        references: array.references.clone(),
+                    })
+                    .collect(),
+            };
+
+            serde_json::to_writer_pretty(File::create(path)?, &analysis)?;
+        }
+
+        Ok(())
+    }
+
+    /// Summarizes everything this view knows about the tag as Markdown, for pasting into issues and docs
+    fn analysis_markdown(&self) -> String {
+        let mut md = String::new();
+        writeln!(
+            md,
+            "## {}",
+            format_tag_entry(self.tag, Some(&self.tag_entry))
+        )
+        .ok();
+        writeln!(md).ok();
+        if let Some(tag64) = self.tag64 {
+            writeln!(md, "- 64-bit hash: `{tag64}`").ok();
+        }
+        writeln!(
+            md,
+            "- Type: {} ({}+{})",
+            self.tag_type, self.tag_entry.file_type, self.tag_entry.file_subtype
+        )
+        .ok();
+        writeln!(
+            md,
+            "- Reference: `{:08X}`{}",
+            self.tag_entry.reference,
+            get_class_by_id(self.tag_entry.reference)
+                .map(|c| format!(" ({})", c.name))
+                .unwrap_or_default()
+        )
+        .ok();
+        if let Some(p) = package_manager().package_paths.get(&self.tag.pkg_id()) {
+            writeln!(
+                md,
+                "- Package: `{}` (patch {})",
+                Path::new(&p.path)
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy(),
+                p.patch
+            )
+            .ok();
+        }
+        writeln!(md, "- Size: {}", format_file_size(self.tag_data.len())).ok();
+        if let Ok((texture, _)) = &self.texture {
+            writeln!(md, "- Texture: {}", texture.desc.info()).ok();
+        }
+
+        if !self.scan.file_hashes.is_empty() {
+            writeln!(md, "\n### Outgoing references\n").ok();
+            writeln!(md, "| Offset | Tag |").ok();
+            writeln!(md, "|---|---|").ok();
+            for h in &self.scan.file_hashes {
+                let offset = if h.offset == u64::MAX {
+                    "Header".to_string()
+                } else {
+                    format!("0x{:X}", h.offset)
+                };
+                writeln!(
+                    md,
+                    "| {offset} | {} |",
+                    format_tag_entry(h.hash.hash32(), h.entry.as_ref())
+                )
+                .ok();
+            }
+        }
+
+        if !self.scan.references.is_empty() {
+            writeln!(md, "\n### Incoming references\n").ok();
+            for r in self.scan.references.iter().unique_by(|r| r.hash) {
+                writeln!(md, "- {}", format_tag_entry(r.hash, r.entry.as_ref())).ok();
+            }
+        }
+
+        if !self.arrays.is_empty() {
+            writeln!(md, "\n### Arrays\n").ok();
+            writeln!(md, "| Offset | Class | Count |").ok();
+            writeln!(md, "|---|---|---|").ok();
+            for (offset, array) in &self.arrays {
+                let class = get_class_by_id(array.tagtype)
+                    .map(|c| format!("{:08X} ({})", array.tagtype, c.name))
+                    .unwrap_or_else(|| format!("{:08X}", array.tagtype));
+                writeln!(md, "| 0x{offset:X} | {class} | {} |", array.count).ok();
+            }
+        }
+
+        if !self.string_hashes.is_empty() {
+            writeln!(md, "\n### String hashes\n").ok();
+            writeln!(md, "| Offset | Hash | Strings |").ok();
+            writeln!(md, "|---|---|---|").ok();
+            for (offset, hash) in &self.string_hashes {
+                let strings = self
+                    .string_cache
+                    .get(hash)
+                    .map(|s| s.iter().map(|s| format!("\"{s}\"")).join(", "))
+                    .unwrap_or_default();
+                writeln!(
+                    md,
+                    "| 0x{offset:X} | `{hash:08X}` | {} |",
+                    strings.replace('|', "\\|").replace('\n', " ")
+                )
+                .ok();
+            }
+        }
+
+        if !self.raw_strings.is_empty() {
+            writeln!(md, "\n### Raw strings\n").ok();
+            writeln!(md, "| Offset | String |").ok();
+            writeln!(md, "|---|---|").ok();
+            for (offset, string, _) in &self.raw_strings {
+                writeln!(
+                    md,
+                    "| 0x{offset:X} | `{}` |",
+                    string.replace('|', "\\|").replace('\n', " ")
+                )
+                .ok();
+            }
+        }
+
+        md
+    }
+
     pub fn dump_traversed_tag_data_recursive(
         tag: &TraversedTag,
         directory: &Path,
         dump_non_structure: bool,
     ) -> anyhow::Result<()> {
-        let tag_postfix = if let Some(entry) = &tag.entry {
-            let ref_postfix = get_class_by_id(entry.reference)
-                .map(|c| format!("_{}", c.name))
-                .unwrap_or_default();
-            let tag_type =
-                TagType::from_type_subtype(entry.file_type, entry.file_subtype).to_string();
-            format!(
-                "_{:08X}{ref_postfix}_{}",
-                entry.reference,
-                tag_type.replace(" ", "").replace("/", "_")
-            )
-        } else {
-            "".to_string()
-        };
-
-        let path = directory.join(format!("{}{}.bin", tag.tag, tag_postfix));
+        let path = directory.join(tag_dump_file_name(tag.tag, tag.entry.as_ref()));
         match package_manager().read_tag(tag.tag) {
             Ok(o) => {
                 let mut file = File::create(&path).with_context(|| {
@@ -792,6 +1938,8 @@ impl View for TagView {
     ) -> Option<ViewAction> {
         let mut open_new_tag = None;
         let mut push_history = true;
+        // (offset, whether the offset is in the constant buffer's referenced data)
+        let mut jump_to_offset: Option<(u64, bool)> = None;
 
         ctx.style_mut(|s| {
             s.interaction.show_tooltips_only_when_still = false;
@@ -843,18 +1991,104 @@ impl View for TagView {
         ui.heading(format_tag_entry(self.tag, Some(&self.tag_entry)))
             .context_menu(|ui| tag_context(ui, self.tag));
 
-        ui.label(
-            RichText::new(format!(
-                "Package {}",
-                package_manager()
-                    .package_paths
-                    .get(&self.tag.pkg_id())
-                    .map(|p| Path::new(&p.path).file_name().unwrap_or_default())
-                    .unwrap_or_default()
-                    .to_string_lossy()
-            ))
-            .weak(),
-        );
+        if let Some(tag64) = self.tag64 {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(format!("★ 64-bit hash: {tag64}")).weak());
+                if ui
+                    .small_button("📋")
+                    .on_hover_text("Copy 64-bit hash")
+                    .clicked()
+                {
+                    ui.output_mut(|o| o.copied_text = tag64.to_string());
+                }
+            });
+        }
+
+        if let Some(p) = package_manager().package_paths.get(&self.tag.pkg_id()) {
+            ui.label(
+                RichText::new(format!(
+                    "Package {} (patch {})",
+                    Path::new(&p.path)
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy(),
+                    p.patch
+                ))
+                .weak(),
+            )
+            .on_hover_text(&p.path);
+        } else {
+            ui.label(RichText::new("Package not found").weak());
+        }
+
+        {
+            let incoming = self.scan.references.iter().map(|r| r.hash).unique().count();
+            let outgoing = self.scan.file_hashes.len();
+            let outgoing_unique = self
+                .scan
+                .file_hashes
+                .iter()
+                .map(|h| h.hash.hash32())
+                .unique()
+                .count();
+            let orphaned = if incoming == 0 && outgoing == 0 {
+                " (orphaned)"
+            } else {
+                ""
+            };
+
+            ui.label(
+                RichText::new(format!(
+                    "{incoming} incoming, {outgoing} outgoing references{orphaned}"
+                ))
+                .weak(),
+            )
+            .on_hover_text(format!(
+                "Referenced by {incoming} tags, references {outgoing_unique} unique tags"
+            ));
+        }
+
+        if !self.identical_tags.is_empty() {
+            ui.menu_button(
+                RichText::new(format!(
+                    "{} tags with the same content hash",
+                    self.identical_tags.len()
+                ))
+                .weak(),
+                |ui| {
+                    ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+                    egui::ScrollArea::vertical()
+                        .max_height(480.0)
+                        .show(ui, |ui| {
+                            for tag in &self.identical_tags {
+                                let entry = package_manager().get_entry(*tag);
+                                let color = entry
+                                    .as_ref()
+                                    .map(|e| {
+                                        TagType::from_type_subtype(e.file_type, e.file_subtype)
+                                            .display_color()
+                                    })
+                                    .unwrap_or(Color32::LIGHT_RED);
+                                if ui
+                                    .selectable_label(
+                                        false,
+                                        RichText::new(format_tag_entry(*tag, entry.as_ref()))
+                                            .color(color),
+                                    )
+                                    .clicked()
+                                {
+                                    open_new_tag = Some(*tag);
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                },
+            )
+            .response
+            .on_hover_text(
+                "Tags whose data has the same 64-bit hash. The data is very likely identical, but isn't compared byte for byte",
+            );
+        }
 
         ui.horizontal(|ui| {
             if ui.button("Open tag data in external application").clicked() {
@@ -878,21 +2112,96 @@ impl View for TagView {
                     .scan
                     .references
                     .iter()
-                    .map(|(hash, _entry)| format!("{}", hash))
+                    .map(|r| format!("{}", r.hash))
                     .collect::<Vec<String>>()
                     .join("\n");
 
                 ui.output_mut(|o| o.copied_text = tag_hashes_str);
             }
-        });
 
-        ui.separator();
-        egui::SidePanel::left("tv_left_panel")
-            .resizable(true)
-            .min_width(256.0)
-            .show_inside(ui, |ui| {
-                ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Truncate);
-                egui::ScrollArea::vertical().show(ui, |ui| {
+            if ui
+                .button("Export analysis…")
+                .on_hover_text("Saves the references, strings and arrays found in this tag as JSON, or the raw strings and their potential relpointers as CSV")
+                .clicked()
+            {
+                if let Ok(Some(path)) = native_dialog::FileDialog::new()
+                    .set_filename(&format!("analysis_{}.json", self.tag))
+                    .add_filter("JSON", &["json"])
+                    .add_filter("CSV (raw strings)", &["csv"])
+                    .show_save_single_file()
+                {
+                    match self.export_analysis(&path) {
+                        Ok(()) => {
+                            TOASTS.lock().success("Analysis exported");
+                        }
+                        Err(e) => error!("Failed to export analysis: {e:?}"),
+                    }
+                }
+            }
+
+            if ui
+                .button("Copy analysis as Markdown")
+                .on_hover_text("Copies a summary of this tag, its references, arrays and strings for pasting into issues and docs")
+                .clicked()
+            {
+                ui.output_mut(|o| o.copied_text = self.analysis_markdown());
+                TOASTS.lock().success("Analysis copied to clipboard");
+            }
+        });
+
+        ui.separator();
+        egui::SidePanel::left("tv_left_panel")
+            .resizable(true)
+            .min_width(256.0)
+            .show_inside(ui, |ui| {
+                ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Truncate);
+
+                // Offsets of every reference to this tag, per referencing tag
+                let mut references_collapsed =
+                    FxHashMap::<TagHash, (Option<UEntryHeader>, Vec<u64>)>::default();
+                for r in &self.scan.references {
+                    references_collapsed
+                        .entry(r.hash)
+                        .or_insert_with(|| (r.entry.clone(), vec![]))
+                        .1
+                        .push(r.offset);
+                }
+
+                // Incoming and outgoing references are navigated as a single list.
+                // Row indices come from the list positions, so collapsed sections don't shift the rows after them
+                let mut navigable_tags = vec![];
+                let mut row_index = |tag: TagHash| {
+                    if tag == self.tag {
+                        return None;
+                    }
+
+                    navigable_tags.push(tag);
+                    Some(navigable_tags.len() - 1)
+                };
+                let incoming_rows = references_collapsed
+                    .keys()
+                    .map(|t| row_index(*t))
+                    .collect_vec();
+                let outgoing_rows = self
+                    .scan
+                    .file_hashes
+                    .iter()
+                    .map(|t| row_index(t.hash.hash32()))
+                    .collect_vec();
+                let keyboard = list_keyboard_input(
+                    ui,
+                    ui.max_rect(),
+                    &mut self.reference_cursor,
+                    navigable_tags.len(),
+                );
+                if keyboard.activated {
+                    open_new_tag = self
+                        .reference_cursor
+                        .and_then(|i| navigable_tags.get(i).copied());
+                }
+                let is_cursor = |row: Option<usize>| row.is_some() && self.reference_cursor == row;
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
                     CollapsingHeader::new(
                         egui::RichText::new("Files referencing this tag").strong(),
                     )
@@ -901,20 +2210,24 @@ impl View for TagView {
                         if self.scan.references.is_empty() {
                             ui.label(RichText::new("No incoming references found").italics());
                         } else {
-                            let mut references_collapsed =
-                                FxHashMap::<TagHash, Option<UEntryHeader>>::default();
-                            for (tag, entry) in &self.scan.references {
-                                references_collapsed
-                                    .entry(*tag)
-                                    .or_insert_with(|| entry.clone());
-                            }
-
-                            for (tag, entry) in &references_collapsed {
-                                let fancy_tag = format_tag_entry(*tag, entry.as_ref());
+                            for (i, (tag, (entry, offsets))) in
+                                references_collapsed.iter().enumerate()
+                            {
+                                let fancy_tag = format!(
+                                    "{}{} @ {}{}",
+                                    read_failed_marker(&self.cache, *tag),
+                                    format_tag_entry(*tag, entry.as_ref()),
+                                    format_reference_offsets(offsets),
+                                    reference_count_badge(&self.cache, *tag)
+                                );
+                                let highlighted = is_cursor(incoming_rows[i]);
                                 let response = ui.add_enabled(
                                     *tag != self.tag,
-                                    egui::SelectableLabel::new(false, fancy_tag),
+                                    egui::SelectableLabel::new(highlighted, fancy_tag),
                                 );
+                                if highlighted && keyboard.moved {
+                                    response.scroll_to_me(None);
+                                }
 
                                 if response.tag_context(*tag).clicked() {
                                     open_new_tag = Some(*tag);
@@ -923,6 +2236,10 @@ impl View for TagView {
                         }
                     });
 
+                    if let Some(diff) = &self.scan_diff {
+                        scan_diff_ui(ui, diff, &mut self.highlight_scan_diff);
+                    }
+
                     CollapsingHeader::new(
                         egui::RichText::new("Tag references in this file").strong(),
                     )
@@ -931,10 +2248,12 @@ impl View for TagView {
                         if self.scan.file_hashes.is_empty() {
                             ui.label(RichText::new("No outgoing references found").italics());
                         } else {
-                            for tag in &self.scan.file_hashes {
+                            for (i, tag) in self.scan.file_hashes.iter().enumerate() {
                                 let mut is_texture = false;
                                 let offset_label = if tag.offset == u64::MAX {
                                     "TagHeader reference".to_string()
+                                } else if self.hexview_referenced.is_some() {
+                                    format!("header+0x{:X}", tag.offset)
                                 } else {
                                     format!("0x{:X}", tag.offset)
                                 };
@@ -948,20 +2267,43 @@ impl View for TagView {
 
                                     let fancy_tag =
                                         format_tag_entry(tag.hash.hash32(), Some(entry));
+                                    let marker = read_failed_marker(&self.cache, tag.hash.hash32());
+                                    let badge =
+                                        reference_count_badge(&self.cache, tag.hash.hash32());
 
-                                    egui::RichText::new(format!("{fancy_tag} @ {offset_label}"))
+                                    egui::RichText::new(format!("{marker}{fancy_tag}{badge}"))
                                         .color(tagtype.display_color())
                                 } else {
                                     egui::RichText::new(format!(
-                                        "{} (pkg entry not found) @ {offset_label}",
+                                        "{} (pkg entry not found)",
                                         tag.hash
                                     ))
                                     .color(Color32::LIGHT_RED)
                                 };
+                                let tag_label = if self.is_new_reference(&tag.hash) {
+                                    tag_label.background_color(NEW_REFERENCE_COLOR)
+                                } else {
+                                    tag_label
+                                };
 
-                                // TODO(cohae): Highlight/jump to tag in hex viewer
                                 if tag.hash.hash32() != self.tag {
-                                    let response = ui.selectable_label(false, tag_label);
+                                    let highlighted = is_cursor(outgoing_rows[i]);
+                                    let response = ui
+                                        .horizontal(|ui| {
+                                            let response =
+                                                ui.selectable_label(highlighted, tag_label);
+                                            if tag.offset == u64::MAX {
+                                                ui.weak(format!("@ {offset_label}"));
+                                            } else if offset_link(ui, &offset_label) {
+                                                jump_to_offset = Some((tag.offset, false));
+                                            }
+                                            response
+                                        })
+                                        .inner;
+                                    if highlighted && keyboard.moved {
+                                        response.scroll_to_me(None);
+                                    }
+
                                     if response
                                         .tag_context_with_texture(
                                             tag.hash.hash32(),
@@ -990,6 +2332,49 @@ impl View for TagView {
                             }
                         }
                     });
+
+                    if let Some((_, referenced_scan)) = &self.hexview_referenced {
+                        CollapsingHeader::new(
+                            egui::RichText::new("Tag references in referenced data").strong(),
+                        )
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            if referenced_scan.file_hashes.is_empty() {
+                                ui.label(
+                                    RichText::new("No references in referenced data").italics(),
+                                );
+                            }
+
+                            for tag in &referenced_scan.file_hashes {
+                                let hash = tag.hash.hash32();
+                                let color = tag
+                                    .entry
+                                    .as_ref()
+                                    .map(|e| {
+                                        TagType::from_type_subtype(e.file_type, e.file_subtype)
+                                            .display_color()
+                                    })
+                                    .unwrap_or(Color32::LIGHT_RED);
+                                let label =
+                                    RichText::new(format_tag_entry(hash, tag.entry.as_ref()))
+                                        .color(color);
+
+                                ui.horizontal(|ui| {
+                                    if ui
+                                        .selectable_label(false, label)
+                                        .tag_context(hash)
+                                        .clicked()
+                                    {
+                                        open_new_tag = Some(hash);
+                                    }
+
+                                    if offset_link(ui, &format!("data+0x{:X}", tag.offset)) {
+                                        jump_to_offset = Some((tag.offset, true));
+                                    }
+                                });
+                            }
+                        });
+                    }
                 });
             });
 
@@ -1020,11 +2405,19 @@ impl View for TagView {
                                                     format!("{:08X}", array.tagtype)
                                                 });
 
-                                            ui.selectable_label(
+                                            let blocked = self
+                                                .blocked_ranges
+                                                .iter()
+                                                .any(|r| r.start == *offset);
+
+                                            let response = ui.selectable_label(
                                                 false,
                                                 format!(
-                                                    "type={} count={} @ 0x{:X}",
-                                                    ref_label, array.count, offset
+                                                    "{}type={} count={} @ 0x{:X}",
+                                                    if blocked { "🚫 " } else { "" },
+                                                    ref_label,
+                                                    array.count,
+                                                    offset
                                                 ),
                                             )
                                             .on_hover_text({
@@ -1040,13 +2433,46 @@ impl View for TagView {
                                                             .join(", ")
                                                     )
                                                 }
-                                            })
-                                            .clicked();
+                                            });
+
+                                            if response.clicked() {
+                                                jump_to_offset = Some((*offset, false));
+                                            }
                                         }
                                     }
                                 });
                             });
 
+                        if !self.blocked_ranges.is_empty() {
+                            CollapsingHeader::new(
+                                egui::RichText::new("Blocked Ranges").strong(),
+                            )
+                            .default_open(false)
+                            .show(ui, |ui| {
+                                ui.group(|ui| {
+                                    for range in &self.blocked_ranges {
+                                        let response = ui.selectable_label(
+                                            false,
+                                            format!(
+                                                "0x{:X}..0x{:X} ({} bytes)",
+                                                range.start,
+                                                range.end,
+                                                range.end - range.start
+                                            ),
+                                        );
+                                        if response
+                                            .on_hover_text(
+                                                "The scanner ignores any hashes in this range (@block_tags)",
+                                            )
+                                            .clicked()
+                                        {
+                                            jump_to_offset = Some((range.start, false));
+                                        }
+                                    }
+                                });
+                            });
+                        }
+
                         CollapsingHeader::new(egui::RichText::new("String Hashes").strong())
                             .default_open(true)
                             .show(ui, |ui| {
@@ -1056,7 +2482,7 @@ impl View for TagView {
                                     } else {
                                         for (offset, hash) in &self.string_hashes {
                                             if let Some(strings) = self.string_cache.get(hash) {
-                                                if strings.len() > 1 {
+                                                let response = if strings.len() > 1 {
                                                     ui.selectable_label(
                                                         false,
                                                         format!(
@@ -1073,7 +2499,6 @@ impl View for TagView {
                                                         ),
                                                     )
                                                     .on_hover_text(strings.join("\n"))
-                                                    .clicked();
                                                 } else {
                                                     ui.selectable_label(
                                                         false,
@@ -1082,7 +2507,10 @@ impl View for TagView {
                                                             strings[0], hash, offset
                                                         ),
                                                     )
-                                                    .clicked();
+                                                };
+
+                                                if response.clicked() {
+                                                    jump_to_offset = Some((*offset, false));
                                                 }
                                             }
                                         }
@@ -1100,7 +2528,7 @@ impl View for TagView {
                                     ui.label(RichText::new("No raw strings found").italics());
                                 } else {
                                     for (offset, string, offsets) in &self.raw_strings {
-                                        ui.selectable_label(
+                                        let response = ui.selectable_label(
                                             false,
                                             format!("'{}' @ 0x{:X}", string, offset),
                                         )
@@ -1114,8 +2542,11 @@ impl View for TagView {
                                                     .map(|o| format!("0x{o:X}"))
                                                     .join(", ")
                                             )
-                                        })
-                                        .context_menu(
+                                        });
+                                        if response.clicked() {
+                                            jump_to_offset = Some((*offset, false));
+                                        }
+                                        response.context_menu(
                                             |ui| {
                                                 if ui.selectable_label(false, "Copy text").clicked()
                                                 {
@@ -1194,6 +2625,10 @@ impl View for TagView {
                                                         (response, strings[0].1)
                                                     };
 
+                                                if response.clicked() {
+                                                    jump_to_offset = Some((*offset, false));
+                                                }
+
                                                 if is_from_wordlist {
                                                     response.on_hover_text(
                                                         RichText::new(
@@ -1211,6 +2646,10 @@ impl View for TagView {
                 });
         }
 
+        if let Some((offset, in_referenced_data)) = jump_to_offset {
+            self.jump_to_offset(offset, in_referenced_data);
+        }
+
         egui::CentralPanel::default().show_inside(ui, |ui| {
             ui.horizontal_wrapped(|ui| {
                 ui.selectable_value(&mut self.mode, TagViewMode::Traversal, "Traversal");
@@ -1223,6 +2662,14 @@ impl View for TagView {
                         "Hex (referenced data)",
                     );
                 }
+                if self.textview.is_some() {
+                    ui.selectable_value(&mut self.mode, TagViewMode::Text, "Text");
+                }
+                let curves_label = match &self.curves {
+                    Some(curves) => format!("Curves ({})", curves.curve_count()),
+                    None => "Curves".to_string(),
+                };
+                ui.selectable_value(&mut self.mode, TagViewMode::Curves, curves_label);
                 ui.selectable_value(&mut self.mode, TagViewMode::Search, "Search");
             });
 
@@ -1233,11 +2680,27 @@ impl View for TagView {
                     open_new_tag = open_new_tag.or(self.traverse_ui(ui));
                 }
                 TagViewMode::Hex => {
+                    if self.hexview_referenced.is_some() {
+                        ui.label(
+                            RichText::new(format!(
+                                "Offsets are relative to the constant buffer header ({})",
+                                self.tag
+                            ))
+                            .weak(),
+                        );
+                    }
                     open_new_tag = open_new_tag.or(self.hexview.show(ui, &self.scan));
                 }
                 TagViewMode::HexReferenced => {
-                    if let Some(h) = self.hexview_referenced.as_mut() {
-                        open_new_tag = open_new_tag.or(h.show(ui, &self.scan));
+                    if let Some((h, scan)) = self.hexview_referenced.as_mut() {
+                        ui.label(
+                            RichText::new(format!(
+                                "Offsets are relative to the referenced data buffer ({})",
+                                TagHash(self.tag_entry.reference)
+                            ))
+                            .weak(),
+                        );
+                        open_new_tag = open_new_tag.or(h.show(ui, scan));
                     } else {
                         self.mode = TagViewMode::Hex;
                     }
@@ -1245,6 +2708,21 @@ impl View for TagView {
                 TagViewMode::Float => {
                     self.floatview_ui(ui);
                 }
+                TagViewMode::Text => {
+                    if let Some(textview) = self.textview.as_mut() {
+                        textview.show(ui);
+                    } else {
+                        self.mode = TagViewMode::Hex;
+                    }
+                }
+                TagViewMode::Curves => {
+                    let curves = self.curves.get_or_insert_with(|| {
+                        TagCurveView::detect(&self.tag_data, package_manager().version.endian())
+                    });
+                    if let Some(offset) = curves.show(ui) {
+                        self.jump_to_offset(offset, false);
+                    }
+                }
                 TagViewMode::Search => {
                     open_new_tag = open_new_tag.or(self.search_ui(ui));
                 }
@@ -1287,6 +2765,14 @@ impl ExtendedTagHash {
                 .unwrap_or(TagHash::NONE),
         }
     }
+
+    /// Size of the reference in the tag data, in bytes
+    pub fn size(&self) -> u64 {
+        match self {
+            ExtendedTagHash::Hash32(_) => 4,
+            ExtendedTagHash::Hash64(_) => 8,
+        }
+    }
 }
 
 impl Display for ExtendedTagHash {
@@ -1309,13 +2795,54 @@ impl Hash for ExtendedTagHash {
 
 pub struct ExtendedScanResult {
     pub successful: bool,
+    pub read_error: Option<String>,
     pub file_hashes: Vec<ScannedHashWithEntry<ExtendedTagHash>>,
 
-    /// References from other files
-    pub references: Vec<(TagHash, Option<UEntryHeader>)>,
+    /// References from other files, with the offset of the reference in that file
+    pub references: Vec<ScannedHashWithEntry<TagHash>>,
 }
 
 impl ExtendedScanResult {
+    /// Finds tag references in data that isn't in the tag cache, such as the data buffers referenced by constant buffers
+    pub fn from_raw_data(data: &[u8], endian: Endian) -> ExtendedScanResult {
+        let pm = package_manager();
+        let mut file_hashes: Vec<_> = data
+            .chunks_exact(4)
+            .enumerate()
+            .filter_map(|(i, c)| {
+                let hash = TagHash(u32_from_endian(endian, c.try_into().unwrap()));
+                if !hash.is_pkg_file() {
+                    return None;
+                }
+
+                let entry = pm.get_entry(hash)?;
+                Some(ScannedHashWithEntry {
+                    offset: i as u64 * 4,
+                    hash: ExtendedTagHash::Hash32(hash),
+                    entry: Some(entry),
+                })
+            })
+            .collect();
+
+        file_hashes.extend(data.chunks_exact(8).enumerate().filter_map(|(i, c)| {
+            let hash = u64_from_endian(endian, c.try_into().unwrap());
+            let hash32 = pm.hash64_table.get(&hash)?.hash32;
+            Some(ScannedHashWithEntry {
+                offset: i as u64 * 8,
+                hash: ExtendedTagHash::Hash64(TagHash64(hash)),
+                entry: pm.get_entry(hash32),
+            })
+        }));
+        file_hashes.sort_unstable_by_key(|v| v.offset);
+
+        ExtendedScanResult {
+            successful: true,
+            read_error: None,
+            file_hashes,
+            references: vec![],
+        }
+    }
+
     pub fn from_scanresult(s: ScanResult) -> ExtendedScanResult {
         let mut file_hashes_combined = vec![];
 
@@ -1343,12 +2870,17 @@ impl ExtendedScanResult {
 
         ExtendedScanResult {
             successful: s.successful,
+            read_error: s.read_error,
             file_hashes: file_hashes_combined,
             references: s
                 .references
                 .into_iter()
                 // TODO(cohae): Unwrap *should* be safe as long as the cache is valid but i want to be sure
-                .map(|t| (t, package_manager().get_entry(t)))
+                .map(|(t, offset)| ScannedHashWithEntry {
+                    offset,
+                    hash: t,
+                    entry: package_manager().get_entry(t),
+                })
                 .collect(),
         }
     }
@@ -1360,27 +2892,75 @@ pub struct ScannedHashWithEntry<T: Sized> {
     pub entry: Option<UEntryHeader>,
 }
 
-#[derive(Copy, Clone, PartialEq)]
-enum TraversalDirection {
+#[derive(Copy, Clone, PartialEq, Debug, clap::ValueEnum)]
+pub enum TraversalDirection {
     Up,
     Down,
 }
 
+/// Collects the textual traversal output, optionally streaming completed lines to a receiver
+struct TraversalOutput {
+    text: String,
+    /// Offset into `text` up to which lines have been sent
+    sent: usize,
+    tx: Option<Sender<String>>,
+    cancel: Option<Arc<AtomicBool>>,
+    pruned: Vec<PrunedReference>,
+}
+
+impl TraversalOutput {
+    fn new(tx: Option<Sender<String>>, cancel: Option<Arc<AtomicBool>>) -> Self {
+        Self {
+            text: String::new(),
+            sent: 0,
+            tx,
+            cancel,
+            pruned: vec![],
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .map(|c| c.load(Ordering::Relaxed))
+            .unwrap_or_default()
+    }
+}
+
+impl Write for TraversalOutput {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.text.push_str(s);
+
+        if let Some(tx) = &self.tx {
+            if s.contains('\n') {
+                let end = self.text.rfind('\n').unwrap() + 1;
+                tx.send(self.text[self.sent..end].to_string()).ok();
+                self.sent = end;
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Traverses down every tag to make a hierarchy of tags
+#[allow(clippy::too_many_arguments)]
 fn traverse_tags(
     starting_tag: TagHash,
     depth_limit: usize,
     cache: Arc<TagCache>,
+    strings: Arc<StringCache>,
     raw_strings: Arc<RawStringHashCache>,
     show_strings: bool,
+    follow_skipped: bool,
     direction: TraversalDirection,
-) -> (TraversedTag, String) {
-    let mut result = String::new();
+    mut output: TraversalOutput,
+) -> (TraversedTag, String, Vec<PrunedReference>) {
     let mut seen_tags = Default::default();
     let mut pipe_stack = vec![];
 
     let traversed = traverse_tag(
-        &mut result,
+        &mut output,
         starting_tag,
         TagHash::NONE,
         0,
@@ -1388,55 +2968,190 @@ fn traverse_tags(
         &mut pipe_stack,
         depth_limit,
         cache,
+        strings,
         raw_strings,
         show_strings,
+        follow_skipped,
         direction,
     );
 
-    (traversed, result)
+    if !output.pruned.is_empty() {
+        writeln!(
+            output,
+            "{} reference(s) to classes marked @skip_traversal were not followed",
+            output.pruned.len()
+        )
+        .ok();
+    }
+
+    (traversed, output.text, output.pruned)
+}
+
+/// Traverses a tag without a view and returns the textual tree, for the headless `--dump-traversal` mode. Strings aren't included
+pub fn traversal_text(
+    tag: TagHash,
+    depth_limit: usize,
+    direction: TraversalDirection,
+    cache: Arc<TagCache>,
+) -> String {
+    let (_, text, _) = traverse_tags(
+        tag,
+        depth_limit,
+        cache,
+        Default::default(),
+        Default::default(),
+        false,
+        settings().traverse_skipped_classes,
+        direction,
+        TraversalOutput::new(None, None),
+    );
+
+    text
+}
+
+/// Resolves the localized string hashes found in a tag, using the first string for each hash
+fn localized_strings_in_tag(cache: &TagCache, strings: &StringCache, tag: TagHash) -> Vec<String> {
+    let Some(scan) = cache.hashes.get(&tag) else {
+        return vec![];
+    };
+
+    scan.string_hashes
+        .iter()
+        .filter_map(|h| strings.get(&h.hash)?.first().cloned())
+        .unique()
+        .collect()
+}
+
+/// A reference that traversal or search didn't follow, because the target's class is marked `@skip_traversal`
+#[derive(Clone)]
+pub struct PrunedReference {
+    pub tag: TagHash,
+    /// The tag containing the reference, [`TagHash::NONE`] if the starting tag itself was skipped
+    pub parent: TagHash,
+    pub reference: u32,
+}
+
+/// Lists pruned references, returning the tag that was clicked
+#[must_use]
+fn pruned_references_ui(
+    ui: &mut egui::Ui,
+    id_salt: &str,
+    pruned: &[PrunedReference],
+) -> Option<TagHash> {
+    if pruned.is_empty() {
+        return None;
+    }
+
+    let mut open_tag = None;
+    egui::CollapsingHeader::new(
+        RichText::new(format!(
+            "⚠ {} reference(s) not followed (@skip_traversal)",
+            pruned.len()
+        ))
+        .color(Color32::YELLOW),
+    )
+    .id_source(id_salt)
+    .show(ui, |ui| {
+        ui.label(
+            RichText::new(
+                "Enable \"Follow skipped classes\" to include these, or open them directly",
+            )
+            .weak(),
+        );
+        egui::ScrollArea::vertical()
+            .max_height(160.0)
+            .show(ui, |ui| {
+                for p in pruned {
+                    ui.horizontal(|ui| {
+                        let entry = package_manager().get_entry(p.tag);
+                        if ui
+                            .selectable_label(false, format_tag_entry(p.tag, entry.as_ref()))
+                            .clicked()
+                        {
+                            open_tag = Some(p.tag);
+                        }
+
+                        let class = classes::get_class_by_id(p.reference)
+                            .map(|c| c.name.to_string())
+                            .unwrap_or_else(|| format!("{:08X}", p.reference));
+                        if p.parent == TagHash::NONE {
+                            ui.weak(class);
+                        } else {
+                            ui.weak(format!("{class}, referenced by {}", p.parent));
+                        }
+                    });
+                }
+            });
+    });
+
+    open_tag
 }
 
 pub struct TraversedTag {
     pub tag: TagHash,
+    /// Offset of the reference in the parent tag, `u64::MAX` for tag header references
+    pub offset: u64,
     pub entry: Option<UEntryHeader>,
     pub reason: Option<String>,
     pub subtags: Vec<TraversedTag>,
+    /// For already traversed tags, the chain of tags and offsets leading to where this tag first appeared
+    pub first_seen: Vec<(TagHash, u64)>,
 }
 
 #[allow(clippy::too_many_arguments)]
 fn traverse_tag(
-    out: &mut String,
+    out: &mut TraversalOutput,
     tag: TagHash,
     parent_tag: TagHash,
     offset: u64,
-    seen_tags: &mut HashSet<TagHash>,
+    seen_tags: &mut FxHashMap<TagHash, (TagHash, u64)>,
     pipe_stack: &mut Vec<char>,
     depth_limit: usize,
     cache: Arc<TagCache>,
+    strings: Arc<StringCache>,
     raw_strings_cache: Arc<RawStringHashCache>,
     show_strings: bool,
+    follow_skipped: bool,
     direction: TraversalDirection,
 ) -> TraversedTag {
     let depth = pipe_stack.len();
 
     let pm = package_manager();
 
-    seen_tags.insert(tag);
+    seen_tags.insert(tag, (parent_tag, offset));
 
     let entry = pm.get_entry(tag);
     let fancy_tag = format_tag_entry(tag, entry.as_ref());
     writeln!(out, "{fancy_tag} @ 0x{offset:X}",).ok();
 
+    if out.is_cancelled() {
+        return TraversedTag {
+            tag,
+            offset,
+            entry,
+            reason: Some("Traversal cancelled".to_string()),
+            subtags: vec![],
+            first_seen: vec![],
+        };
+    }
+
     if let Some(entry) = &entry {
-        if entry.reference == 0x808099F1 {
+        if !follow_skipped && classes::is_traversal_skipped(entry.reference) {
+            out.pruned.push(PrunedReference {
+                tag,
+                parent: parent_tag,
+                reference: entry.reference,
+            });
             return TraversedTag {
                 tag,
+                offset,
                 entry: Some(entry.clone()),
                 reason: Some(format!(
-                    "Reference 0x{:08X} is blocked from being scanned",
+                    "Reference 0x{:08X} is skipped from traversal",
                     entry.reference
                 )),
                 subtags: vec![],
+                first_seen: vec![],
             };
         }
     }
@@ -1451,18 +3166,22 @@ fn traverse_tag(
 
         return TraversedTag {
             tag,
+            offset,
             entry,
             reason: Some(format!("Depth limit reached ({depth_limit})")),
             subtags: vec![],
+            first_seen: vec![],
         };
     }
 
     let Some(scan_result) = cache.hashes.get(&tag).cloned() else {
         return TraversedTag {
             tag,
+            offset,
             entry,
             reason: Some("Tag not found in cache".to_string()),
             subtags: vec![],
+            first_seen: vec![],
         };
     };
 
@@ -1474,17 +3193,22 @@ fn traverse_tag(
             .map(|v| (v.hash.hash32(), v.offset))
             .collect_vec()
     } else {
-        let references_collapsed: FxHashSet<TagHash> =
-            scan.references.iter().map(|(t, _)| *t).collect();
-        references_collapsed.iter().map(|t| (*t, 0)).collect_vec()
+        // Offsets of the first reference to this tag in each parent
+        scan.references
+            .iter()
+            .unique_by(|r| r.hash)
+            .map(|r| (r.hash, r.offset))
+            .collect_vec()
     };
 
     if all_hashes.is_empty() {
         return TraversedTag {
             tag,
+            offset,
             entry,
             reason: None,
             subtags: vec![],
+            first_seen: vec![],
         };
     }
 
@@ -1494,20 +3218,33 @@ fn traverse_tag(
     }
 
     if show_strings {
+        let localized_strings = localized_strings_in_tag(&cache, &strings, tag);
+        if !localized_strings.is_empty() {
+            writeln!(
+                out,
+                "{line_header}├──Strings: [{}]",
+                localized_strings
+                    .into_iter()
+                    .map(|s| format!("{s:?}"))
+                    .join(", ")
+            )
+            .ok();
+        }
+
         let tag_data = package_manager().read_tag(tag).unwrap();
-        let mut raw_strings = vec![];
+        let endian = package_manager().version.endian();
+        let raw_strings = find_raw_strings(
+            endian,
+            package_manager().version,
+            &tag_data,
+            &find_blocked_ranges(endian, package_manager().version, &tag_data),
+        );
         let mut raw_string_hashes = vec![];
-        for (i, b) in tag_data.chunks_exact(4).enumerate() {
-            let v: [u8; 4] = b.try_into().unwrap();
-            let hash = u32::from_le_bytes(v);
-
+        for b in tag_data.chunks_exact(4) {
+            let hash = u32_from_endian(endian, b.try_into().unwrap());
             if let Some(v) = raw_strings_cache.get(&hash) {
                 raw_string_hashes.push(v[0].clone());
             }
-
-            if hash == 0x80800065 {
-                raw_strings.extend(read_raw_string_blob(&tag_data, i as u64 * 4));
-            }
         }
 
         if !raw_strings.is_empty() {
@@ -1549,7 +3286,7 @@ fn traverse_tag(
             pipe_stack.push('│');
         }
 
-        if seen_tags.contains(t) {
+        if seen_tags.contains_key(t) {
             let entry = pm.get_entry(*t);
             let fancy_tag = format_tag_entry(*t, entry.as_ref());
 
@@ -1568,10 +3305,12 @@ fn traverse_tag(
 
                 subtags.push(TraversedTag {
                     tag: *t,
+                    offset: *offset,
                     entry,
 
                     reason: None,
                     subtags: vec![],
+                    first_seen: vec![],
                 });
             } else if *t == parent_tag {
                 writeln!(
@@ -1582,18 +3321,27 @@ fn traverse_tag(
             } else if *t == tag {
                 // We don't care about self references
             } else {
+                let first_seen = first_seen_path(seen_tags, *t);
+                let first_parent = first_seen
+                    .iter()
+                    .rev()
+                    .nth(1)
+                    .map(|(parent, _)| format!(", first seen in {parent}"))
+                    .unwrap_or_default();
                 writeln!(
                     out,
-                    "{line_header}{branch}──{fancy_tag} @ {offset_label} (already traversed)"
+                    "{line_header}{branch}──{fancy_tag} @ {offset_label} (already traversed{first_parent})"
                 )
                 .ok();
 
                 subtags.push(TraversedTag {
                     tag: *t,
+                    offset: *offset,
                     entry,
 
                     reason: Some("Already traversed".to_string()),
                     subtags: vec![],
+                    first_seen,
                 });
             }
         } else {
@@ -1607,8 +3355,10 @@ fn traverse_tag(
                 pipe_stack,
                 depth_limit,
                 cache.clone(),
+                strings.clone(),
                 raw_strings_cache.clone(),
                 show_strings,
+                follow_skipped,
                 direction,
             );
 
@@ -1621,12 +3371,573 @@ fn traverse_tag(
 
     TraversedTag {
         tag,
+        offset,
         entry,
         reason: None,
         subtags,
+        first_seen: vec![],
+    }
+}
+
+/// Follows the parents recorded in `seen_tags` back to the root, returning the chain from the root down to `tag`
+fn first_seen_path(
+    seen_tags: &FxHashMap<TagHash, (TagHash, u64)>,
+    tag: TagHash,
+) -> Vec<(TagHash, u64)> {
+    let mut path = vec![];
+    let mut current = tag;
+    while let Some(&(parent, offset)) = seen_tags.get(&current) {
+        path.push((current, offset));
+        // Every tag is only recorded once, so this can't loop, but stay on the safe side
+        if parent == TagHash::NONE || path.len() > seen_tags.len() {
+            break;
+        }
+        current = parent;
+    }
+
+    path.reverse();
+    path
+}
+
+const NEW_REFERENCE_COLOR: Color32 = Color32::from_rgb(24, 72, 24);
+
+/// Summarizes the changes to the open tag since the cache was regenerated, listing removed references
+/// as they're no longer part of the reference list
+fn scan_diff_ui(ui: &mut egui::Ui, diff: &TagDiff, highlight: &mut bool) {
+    if diff.is_empty() {
+        ui.label(RichText::new("No changes since the cache was regenerated").weak());
+        return;
+    }
+
+    ui.horizontal(|ui| {
+        ui.label(
+            RichText::new(format!(
+                "Since cache regeneration: +{}/-{} references, +{}/-{} string hashes, +{}/-{} raw strings",
+                diff.added_references.len(),
+                diff.removed_references.len(),
+                diff.added_string_hashes.len(),
+                diff.removed_string_hashes.len(),
+                diff.added_raw_strings.len(),
+                diff.removed_raw_strings.len(),
+            ))
+            .color(Color32::YELLOW),
+        );
+        ui.checkbox(highlight, "Highlight new references")
+            .on_hover_ui(|ui| {
+                ui.label(RichText::new("New references").background_color(NEW_REFERENCE_COLOR));
+            });
+    });
+
+    if !diff.removed_references.is_empty() {
+        ui.collapsing("Removed references", |ui| {
+            for tag in &diff.removed_references {
+                ui.label(RichText::new(tag).strikethrough().color(Color32::LIGHT_RED));
+            }
+        });
     }
 }
 
+/// Formats a first seen chain as `root → tag @ 0x10 → ...`
+fn format_first_seen_path(path: &[(TagHash, u64)]) -> String {
+    path.iter()
+        .enumerate()
+        .map(|(i, (tag, offset))| {
+            if i == 0 {
+                tag.to_string()
+            } else if *offset == u64::MAX {
+                format!("{tag} @ TagHeader reference")
+            } else {
+                format!("{tag} @ 0x{offset:X}")
+            }
+        })
+        .join(" → ")
+}
+
+fn first_seen_ui(ui: &mut egui::Ui, path: &[(TagHash, u64)]) {
+    ui.strong("First seen at");
+    for (i, (tag, offset)) in path.iter().enumerate() {
+        let entry = package_manager().get_entry(*tag);
+        let offset_label = if i == 0 {
+            String::new()
+        } else if *offset == u64::MAX {
+            " @ TagHeader reference".to_string()
+        } else {
+            format!(" @ 0x{offset:X}")
+        };
+
+        ui.label(format!(
+            "{}{}{offset_label}",
+            "  ".repeat(i),
+            format_tag_entry(*tag, entry.as_ref())
+        ));
+    }
+}
+
+const TRAVERSAL_HTML_HEADER: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<style>
+body { background: #1b1b1b; color: #c8c8c8; font-family: monospace; font-size: 13px; }
+details { margin-left: 20px; }
+summary { cursor: pointer; white-space: nowrap; }
+.leaf { margin-left: 32px; white-space: nowrap; }
+.offset { color: #808080; }
+.reason { color: #c8a040; font-style: italic; }
+button { margin-right: 4px; }
+</style>
+</head>
+<body>
+<button onclick="document.querySelectorAll('details').forEach(d => d.open = true)">Expand all</button>
+<button onclick="document.querySelectorAll('details').forEach(d => d.open = false)">Collapse all</button>
+"#;
+
+/// File name for dumped tag data, including the class and type of the tag
+fn tag_dump_file_name(tag: TagHash, entry: Option<&UEntryHeader>) -> String {
+    let tag_postfix = if let Some(entry) = entry {
+        let ref_postfix = get_class_by_id(entry.reference)
+            .map(|c| format!("_{}", c.name))
+            .unwrap_or_default();
+        let tag_type = TagType::from_type_subtype(entry.file_type, entry.file_subtype).to_string();
+        format!(
+            "_{:08X}{ref_postfix}_{}",
+            entry.reference,
+            tag_type.replace(" ", "").replace("/", "_")
+        )
+    } else {
+        "".to_string()
+    };
+
+    format!("{tag}{tag_postfix}.bin")
+}
+
+#[derive(serde::Serialize)]
+struct BundleManifest {
+    root: String,
+    game_version: String,
+    tags: Vec<BundleTag>,
+}
+
+#[derive(serde::Serialize)]
+struct BundleTag {
+    tag: String,
+    tag64: Option<String>,
+    tag_type: String,
+    reference: String,
+    class_name: Option<String>,
+    size: u32,
+    /// Data file in the bundle directory, `None` if the data wasn't exported
+    file: Option<String>,
+    references: Vec<AnalysisReference>,
+}
+
+/// Exports a tag and every tag reachable from it into `directory`, along with a `manifest.json` describing the reference graph.
+/// Tags reachable through multiple paths are only exported once. Returns the number of tags in the bundle
+#[allow(clippy::too_many_arguments)]
+fn export_bundle(
+    tag: TagHash,
+    depth_limit: usize,
+    follow_skipped: bool,
+    include_non_structure: bool,
+    cache: Arc<TagCache>,
+    strings: Arc<StringCache>,
+    raw_strings: Arc<RawStringHashCache>,
+    directory: &Path,
+    progress: &TaskProgress,
+) -> anyhow::Result<usize> {
+    let (traversed, _, _) = traverse_tags(
+        tag,
+        depth_limit,
+        cache,
+        strings,
+        raw_strings,
+        false,
+        follow_skipped,
+        TraversalDirection::Down,
+        TraversalOutput::new(None, None),
+    );
+
+    // Collect the references of every tag in the tree. Already traversed tags show up as leaves, so their references come from where they were first traversed
+    let mut graph: FastIndexMap<TagHash, (Option<UEntryHeader>, Vec<(TagHash, u64)>)> =
+        Default::default();
+    let mut stack = vec![&traversed];
+    while let Some(t) = stack.pop() {
+        let (_, references) = graph
+            .entry(t.tag)
+            .or_insert_with(|| (t.entry.clone(), vec![]));
+        for subtag in &t.subtags {
+            if !references.contains(&(subtag.tag, subtag.offset)) {
+                references.push((subtag.tag, subtag.offset));
+            }
+        }
+        stack.extend(t.subtags.iter().rev());
+    }
+
+    std::fs::create_dir_all(directory)?;
+    progress.set_total(graph.len());
+
+    let mut manifest = BundleManifest {
+        root: tag.to_string(),
+        game_version: package_manager().version.name().to_string(),
+        tags: vec![],
+    };
+    for (tag, (entry, references)) in &graph {
+        progress.check_cancelled()?;
+
+        let is_structure = entry
+            .as_ref()
+            .map(|e| TagType::from_type_subtype(e.file_type, e.file_subtype).is_tag())
+            .unwrap_or(true);
+        let file = if is_structure || include_non_structure {
+            let file_name = tag_dump_file_name(*tag, entry.as_ref());
+            match package_manager().read_tag(*tag) {
+                Ok(data) => {
+                    std::fs::write(directory.join(&file_name), data)
+                        .with_context(|| format!("Failed to write tag data for {tag}"))?;
+                    Some(file_name)
+                }
+                Err(e) => {
+                    error!("Failed to read tag {tag} for bundle: {e:?}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        manifest.tags.push(BundleTag {
+            tag: tag.to_string(),
+            tag64: get_hash64(*tag).map(|h| h.to_string()),
+            tag_type: entry
+                .as_ref()
+                .map(|e| TagType::from_type_subtype(e.file_type, e.file_subtype).to_string())
+                .unwrap_or_default(),
+            reference: entry
+                .as_ref()
+                .map(|e| format!("{:08X}", e.reference))
+                .unwrap_or_default(),
+            class_name: entry
+                .as_ref()
+                .and_then(|e| get_class_by_id(e.reference))
+                .map(|c| c.name.to_string()),
+            size: entry.as_ref().map(|e| e.file_size).unwrap_or_default(),
+            file,
+            references: references
+                .iter()
+                .map(|(tag, offset)| AnalysisReference {
+                    offset: *offset,
+                    tag: tag.to_string(),
+                })
+                .collect(),
+        });
+        progress.advance();
+    }
+
+    let manifest_file = File::create(directory.join("manifest.json"))?;
+    serde_json::to_writer_pretty(std::io::BufWriter::new(manifest_file), &manifest)?;
+
+    Ok(manifest.tags.len())
+}
+
+/// Renders a traversal tree as a standalone HTML page
+pub fn traversal_to_html(root: &TraversedTag) -> String {
+    let mut out = String::from(TRAVERSAL_HTML_HEADER);
+    writeln!(
+        out,
+        "<h2>Traversal of {}</h2>",
+        html_escape(&format_tag_entry(root.tag, root.entry.as_ref()))
+    )
+    .ok();
+    write_traversed_tag_html(&mut out, root, true);
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn write_traversed_tag_html(out: &mut String, tag: &TraversedTag, is_root: bool) {
+    let color = tag
+        .entry
+        .as_ref()
+        .map(|e| TagType::from_type_subtype(e.file_type, e.file_subtype).display_color())
+        .unwrap_or(Color32::GRAY);
+
+    let offset = if is_root {
+        String::new()
+    } else if tag.offset == u64::MAX {
+        " <span class=\"offset\">@ TagHeader reference</span>".to_string()
+    } else {
+        format!(" <span class=\"offset\">@ 0x{:X}</span>", tag.offset)
+    };
+
+    let first_seen = if tag.first_seen.is_empty() {
+        String::new()
+    } else {
+        format!(
+            " title=\"First seen at {}\"",
+            html_escape(&format_first_seen_path(&tag.first_seen))
+        )
+    };
+    let reason = tag
+        .reason
+        .as_ref()
+        .map(|r| {
+            format!(
+                " <span class=\"reason\"{first_seen}>({})</span>",
+                html_escape(r)
+            )
+        })
+        .unwrap_or_default();
+
+    let label = format!(
+        "<span style=\"color: #{:02x}{:02x}{:02x}\">{}</span>{offset}{reason}",
+        color.r(),
+        color.g(),
+        color.b(),
+        html_escape(&format_tag_entry(tag.tag, tag.entry.as_ref()))
+    );
+
+    if tag.subtags.is_empty() {
+        writeln!(out, "<div class=\"leaf\">{label}</div>").ok();
+    } else {
+        writeln!(out, "<details open><summary>{label}</summary>").ok();
+        for t in &tag.subtags {
+            write_traversed_tag_html(out, t, false);
+        }
+        writeln!(out, "</details>").ok();
+    }
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// How many tags deep to look for materials through intermediate tags, such as texture assignment tags
+const MATERIAL_SEARCH_DEPTH: usize = 3;
+
+/// Material classes. Matched by name, as intermediates like `s_material_texture_assignment` also mention materials
+const MATERIAL_CLASSES: &[&str] = &["s_technique"];
+
+/// Whether a tag is a material (technique) or shader, which are the usual consumers of textures
+fn is_material_entry(entry: &UEntryHeader) -> bool {
+    let tag_type = TagType::from_type_subtype(entry.file_type, entry.file_subtype);
+    match tag_type {
+        TagType::PixelShader { .. }
+        | TagType::VertexShader { .. }
+        | TagType::GeometryShader { .. }
+        | TagType::ComputeShader { .. } => true,
+        TagType::Tag | TagType::TagGlobal => {
+            get_class_by_id(entry.reference).is_some_and(|c| MATERIAL_CLASSES.contains(&&*c.name))
+        }
+        _ => false,
+    }
+}
+
+/// Walks the incoming references of a tag to find the materials and shaders using it.
+/// The search doesn't continue past a material, as those are only referenced by meshes and other materials
+pub fn find_referencing_materials(cache: &TagCache, tag: TagHash) -> Vec<(TagHash, UEntryHeader)> {
+    let mut materials = vec![];
+    let mut visited = FxHashSet::default();
+    visited.insert(tag);
+
+    let mut current = vec![tag];
+    for _ in 0..MATERIAL_SEARCH_DEPTH {
+        let mut next = vec![];
+        for t in current {
+            let Some(scan) = cache.hashes.get(&t) else {
+                continue;
+            };
+
+            for &(r, _) in &scan.references {
+                if !visited.insert(r) {
+                    continue;
+                }
+
+                let Some(entry) = package_manager().get_entry(r) else {
+                    continue;
+                };
+
+                if is_material_entry(&entry) {
+                    materials.push((r, entry));
+                } else {
+                    next.push(r);
+                }
+            }
+        }
+
+        current = next;
+    }
+
+    materials.sort_by_key(|(t, _)| t.0);
+    materials
+}
+
+/// Upper limit on the number of equally short paths returned by [`find_reference_paths`]
+const MAX_REFERENCE_PATHS: usize = 32;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceDirection {
+    /// Follow the references a tag makes
+    Outgoing,
+    /// Follow the tags referencing a tag
+    Incoming,
+    /// Follow references regardless of their direction
+    Both,
+}
+
+impl ReferenceDirection {
+    pub const ALL: [ReferenceDirection; 3] = [
+        ReferenceDirection::Outgoing,
+        ReferenceDirection::Incoming,
+        ReferenceDirection::Both,
+    ];
+}
+
+impl Display for ReferenceDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ReferenceDirection::Outgoing => "Outgoing references",
+            ReferenceDirection::Incoming => "Incoming references",
+            ReferenceDirection::Both => "Both directions",
+        })
+    }
+}
+
+/// Tags directly connected to `tag` in the given direction. 64-bit references are resolved through the hash64 table
+fn reference_neighbours(
+    cache: &TagCache,
+    tag: TagHash,
+    direction: ReferenceDirection,
+) -> Vec<TagHash> {
+    let Some(scan) = cache.hashes.get(&tag) else {
+        return vec![];
+    };
+
+    let mut neighbours = vec![];
+    if direction != ReferenceDirection::Incoming {
+        let pm = package_manager();
+        neighbours.extend(scan.file_hashes.iter().map(|h| h.hash));
+        neighbours.extend(
+            scan.file_hashes64
+                .iter()
+                .filter_map(|h| pm.hash64_table.get(&h.hash.0).map(|e| e.hash32)),
+        );
+    }
+
+    if direction != ReferenceDirection::Outgoing {
+        neighbours.extend(scan.references.iter().map(|(t, _)| *t));
+    }
+
+    neighbours
+}
+
+/// Finds the shortest chains of references from `from` to `to`, both ends included.
+/// Returns every path of the shortest length (up to [`MAX_REFERENCE_PATHS`]), or nothing if `to` isn't reachable within `max_depth` references
+pub fn find_reference_paths(
+    cache: &TagCache,
+    from: TagHash,
+    to: TagHash,
+    direction: ReferenceDirection,
+    max_depth: usize,
+    progress: &TaskProgress,
+) -> anyhow::Result<Vec<Vec<TagHash>>> {
+    if from == to {
+        return Ok(vec![vec![from]]);
+    }
+
+    // Breadth-first search, keeping every parent a tag can be reached from at its shortest distance
+    let mut depths: FxHashMap<TagHash, usize> = FxHashMap::default();
+    let mut parents: FxHashMap<TagHash, Vec<TagHash>> = FxHashMap::default();
+    depths.insert(from, 0);
+
+    let mut current = vec![from];
+    let mut found = false;
+    progress.set_total(max_depth);
+    for depth in 1..=max_depth {
+        let mut next = vec![];
+        for t in current {
+            progress.check_cancelled()?;
+            for r in reference_neighbours(cache, t, direction) {
+                match depths.get(&r) {
+                    Some(&d) if d == depth => {
+                        let p = parents.entry(r).or_default();
+                        if !p.contains(&t) {
+                            p.push(t);
+                        }
+                    }
+                    Some(_) => {}
+                    None => {
+                        depths.insert(r, depth);
+                        parents.insert(r, vec![t]);
+                        next.push(r);
+                    }
+                }
+            }
+        }
+
+        if depths.contains_key(&to) {
+            found = true;
+            break;
+        }
+
+        if next.is_empty() {
+            break;
+        }
+        current = next;
+        progress.advance();
+    }
+
+    if !found {
+        return Ok(vec![]);
+    }
+
+    // Walk back from the target through all parents, building the paths in reverse
+    let mut paths = vec![];
+    let mut stack = vec![vec![to]];
+    while let Some(partial) = stack.pop() {
+        if paths.len() >= MAX_REFERENCE_PATHS {
+            break;
+        }
+
+        let last = *partial.last().unwrap();
+        if last == from {
+            paths.push(partial.into_iter().rev().collect_vec());
+            continue;
+        }
+
+        for p in parents.get(&last).into_iter().flatten() {
+            let mut extended = partial.clone();
+            extended.push(*p);
+            stack.push(extended);
+        }
+    }
+
+    paths.sort_by_key(|p| p.iter().map(|t| t.0).collect_vec());
+    Ok(paths)
+}
+
+/// Explains why a tag doesn't exist, telling apart packages that aren't part of this install from missing entries
+pub fn tag_missing_reason(tag: TagHash) -> Option<String> {
+    let pm = package_manager();
+    let Some(package) = pm.package_paths.get(&tag.pkg_id()) else {
+        return Some(format!(
+            "Package {:04X} for tag {tag} is not loaded\nIt may be missing from this install or dump",
+            tag.pkg_id()
+        ));
+    };
+
+    if pm.get_entry(tag).is_none() {
+        return Some(format!(
+            "Package {} is loaded, but has no entry {} (tag {tag})",
+            package.filename,
+            tag.entry_index()
+        ));
+    }
+
+    None
+}
+
 pub fn format_tag_entry(tag: TagHash, entry: Option<&UEntryHeader>) -> String {
     if let Some(entry) = entry {
         let named_tag = package_manager()
@@ -1635,13 +3946,14 @@ pub fn format_tag_entry(tag: TagHash, entry: Option<&UEntryHeader>) -> String {
             .find(|v| v.hash == tag)
             .map(|v| format!("{} ", v.name))
             .unwrap_or_default();
+        let known_tag = known_tag_label(tag);
 
         let ref_label = get_class_by_id(entry.reference)
             .map(|c| format!(" ({})", c.name))
             .unwrap_or_default();
 
         format!(
-            "{}{named_tag}{tag} {}{ref_label} ({}+{}, ref {:08X})",
+            "{}{named_tag}{known_tag}{tag} {}{ref_label} ({}+{}, ref {:08X})",
             if get_hash64(tag).is_some() {
                 "★ "
             } else {
@@ -1653,10 +3965,60 @@ pub fn format_tag_entry(tag: TagHash, entry: Option<&UEntryHeader>) -> String {
             entry.reference,
         )
     } else {
-        format!("{} (pkg entry not found)", tag)
+        format!("{}{tag} (pkg entry not found)", known_tag_label(tag))
     }
 }
 
+/// Label from the user's known tags manifest, formatted as a prefix for [`format_tag_entry`]
+fn known_tag_label(tag: TagHash) -> String {
+    get_known_tag_label(tag)
+        .map(|l| format!("[{l}] "))
+        .unwrap_or_default()
+}
+
+#[derive(serde::Serialize)]
+struct TagAnalysisExport {
+    tag: String,
+    tag64: Option<String>,
+    tag_type: String,
+    references: Vec<AnalysisReference>,
+    string_hashes: Vec<AnalysisStringHash>,
+    raw_strings: Vec<AnalysisRawString>,
+    arrays: Vec<AnalysisArray>,
+}
+
+#[derive(serde::Serialize)]
+struct AnalysisReference {
+    /// `u64::MAX` for tag header references
+    offset: u64,
+    tag: String,
+}
+
+#[derive(serde::Serialize)]
+struct AnalysisStringHash {
+    offset: u64,
+    hash: String,
+    strings: Vec<String>,
+}
+
+#[derive(serde::Serialize)]
+struct AnalysisRawString {
+    offset: u64,
+    string: String,
+    /// Offsets of values that could be relative pointers to this string
+    relpointers: Vec<u64>,
+}
+
+#[derive(serde::Serialize)]
+struct AnalysisArray {
+    offset: u64,
+    class: String,
+    class_name: Option<String>,
+    count: u64,
+    /// Offsets of potential references to this array
+    references: Vec<u64>,
+}
+
 #[binread]
 pub struct TagArray {
     pub count: u64,
@@ -1679,6 +4041,233 @@ fn find_potential_relpointers(data: &[u64], target_offset: u64) -> Vec<u64> {
     result
 }
 
+/// Clickable `@ offset` label, returns true if it was clicked
+fn offset_link(ui: &mut egui::Ui, offset_label: &str) -> bool {
+    ui.link(format!("@ {offset_label}"))
+        .on_hover_text("Show in hex view")
+        .clicked()
+}
+
+/// Returns a warning marker for tags whose data failed to read during scanning
+/// The reason is available through [`TagCache::read_error`]
+pub fn read_failed_marker(cache: &TagCache, tag: TagHash) -> &'static str {
+    if cache.read_failed(tag) {
+        "⚠ "
+    } else {
+        ""
+    }
+}
+
+/// Formats the offsets a tag is referenced at, eg. `0x10, 0x48 (+3)`
+fn format_reference_offsets(offsets: &[u64]) -> String {
+    const MAX_SHOWN: usize = 3;
+
+    let mut formatted = offsets
+        .iter()
+        .take(MAX_SHOWN)
+        .map(|&o| {
+            if o == u64::MAX {
+                "TagHeader reference".to_string()
+            } else {
+                format!("0x{o:X}")
+            }
+        })
+        .join(", ");
+    if offsets.len() > MAX_SHOWN {
+        write!(formatted, " (+{})", offsets.len() - MAX_SHOWN).ok();
+    }
+
+    formatted
+}
+
+/// Number of outgoing references of a tag, formatted as a suffix for reference lists
+fn reference_count_badge(cache: &TagCache, tag: TagHash) -> String {
+    let count = cache
+        .hashes
+        .get(&tag)
+        .map(|s| s.file_hashes.len() + s.file_hashes64.len())
+        .unwrap_or_default();
+
+    if count > 0 {
+        format!("  [{count} refs]")
+    } else {
+        String::new()
+    }
+}
+
+/// Renders a list of search results, returns the tag that was clicked (if any)
+#[must_use]
+pub fn tag_search_results_ui(
+    ui: &mut egui::Ui,
+    results: &[(TagHash, UEntryHeader)],
+    selection: &mut TagSelection,
+    cache: &TagCache,
+    texture_cache: &TextureCache,
+) -> Option<TagHash> {
+    selection.actions_ui(ui, results.iter().map(|(t, _)| *t));
+
+    let mut result = None;
+    let keyboard = selection.handle_keyboard(ui, ui.available_rect_before_wrap(), results.len());
+    if keyboard.activated {
+        result = selection
+            .cursor()
+            .and_then(|i| results.get(i))
+            .map(|(t, _)| *t);
+    }
+
+    egui::ScrollArea::vertical().show_rows(ui, 22.0, results.len(), |ui, range| {
+        if keyboard.moved {
+            if let Some(cursor) = selection.cursor() {
+                // The row might not be visible, so calculate where it would be
+                let row_stride = 22.0 + ui.spacing().item_spacing.y;
+                let top = ui.max_rect().top() + (cursor as f32 - range.start as f32) * row_stride;
+                ui.scroll_to_rect(
+                    egui::Rect::from_x_y_ranges(ui.max_rect().x_range(), top..=top + 22.0),
+                    None,
+                );
+            }
+        }
+
+        for (i, (tag, entry)) in results
+            .iter()
+            .enumerate()
+            .skip(range.start)
+            .take(range.len())
+        {
+            let tagtype = TagType::from_type_subtype(entry.file_type, entry.file_subtype);
+
+            let fancy_tag = format!(
+                "{}{}",
+                read_failed_marker(cache, *tag),
+                format_tag_entry(*tag, Some(entry))
+            );
+
+            let tag_label = egui::RichText::new(fancy_tag).color(tagtype.display_color());
+
+            let mut response = ui.selectable_label(
+                selection.is_selected(*tag) || selection.cursor() == Some(i),
+                tag_label,
+            );
+            if let Some(error) = cache.read_error(*tag) {
+                response = response.on_hover_text(format!("Failed to read tag data: {error}"));
+            }
+
+            if response
+                .tag_context_with_texture(
+                    *tag,
+                    texture_cache,
+                    tagtype.is_texture() && tagtype.is_header(),
+                )
+                .clicked()
+                && !selection.handle_click(
+                    ui.input(|i| i.modifiers),
+                    i,
+                    results.iter().map(|(t, _)| *t),
+                )
+            {
+                result = Some(*tag)
+            }
+        }
+    });
+
+    result
+}
+
+/// Search matches in a finished traversal, so the tree doesn't have to be searched every frame
+struct TraversalSearchResults {
+    /// Lowercase query the matches were computed for
+    query: String,
+    interactive: bool,
+    hide_already_traversed: bool,
+    /// Byte ranges of the lines of the static traversal
+    static_lines: Vec<Range<usize>>,
+    /// Indices of the static traversal lines that match
+    matching_lines: Vec<usize>,
+    /// Visible nodes of the interactive traversal, in the order they're drawn
+    nodes: Vec<TraversalSearchNode>,
+    match_count: usize,
+}
+
+#[derive(Clone, Copy)]
+struct TraversalSearchNode {
+    /// Number of matches that come before this node
+    matches_before: usize,
+    is_match: bool,
+    /// Number of visible nodes below this one
+    descendants: usize,
+    descendant_matches: usize,
+}
+
+impl TraversalSearchNode {
+    /// Match indices of the nodes below this one
+    fn descendant_match_range(&self) -> Range<usize> {
+        let start = self.matches_before + self.is_match as usize;
+        start..start + self.descendant_matches
+    }
+}
+
+fn search_match_color(is_current: bool) -> Color32 {
+    if is_current {
+        Color32::from_rgb(150, 90, 0)
+    } else {
+        Color32::from_rgb(70, 60, 0)
+    }
+}
+
+/// Splits a pretty-printed `Debug` dump into (depth, field, value) rows.
+/// Nested structs get a row with an empty value, list elements get an empty field name
+fn parse_debug_fields(s: &str) -> Vec<(usize, String, String)> {
+    s.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let depth = (line.len() - line.trim_start().len()) / 4;
+            let line = line.trim().trim_end_matches(',');
+            if depth == 0 || matches!(line, "}" | "]" | ")") {
+                return None;
+            }
+
+            let (key, value) = line.split_once(": ").unwrap_or(("", line));
+            let value = value.trim_end_matches(['{', '[', '(']).trim_end();
+            Some((depth - 1, key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn texture_size_mismatch_ui(ui: &mut egui::Ui, mismatch: &TextureSizeMismatch) {
+    ui.colored_label(Color32::YELLOW, "Texture data size mismatch");
+    egui::Grid::new("texture_size_mismatch")
+        .num_columns(2)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.strong("Expected size");
+            ui.label(format!("{} (0x{:X})", mismatch.expected, mismatch.expected));
+            ui.end_row();
+
+            ui.strong("Actual size");
+            ui.label(format!("{} (0x{:X})", mismatch.actual, mismatch.actual));
+            ui.end_row();
+
+            ui.strong("Dimensions");
+            ui.label(format!(
+                "{}x{}x{}",
+                mismatch.width, mismatch.height, mismatch.depth
+            ));
+            ui.end_row();
+
+            ui.strong("Format");
+            ui.label(&mismatch.format);
+            ui.end_row();
+
+            ui.strong("Large buffer");
+            if let Some(large_buffer) = mismatch.large_buffer {
+                ui.label(large_buffer.to_string());
+            } else {
+                ui.label("No");
+            }
+            ui.end_row();
+        });
+}
+
 pub fn strip_ansi_codes(input: &str) -> String {
     let ansi_escape_pattern = regex::Regex::new(r"\x1B\[[0-9;]*[mK]").unwrap();
     ansi_escape_pattern.replace_all(input, "").to_string()
@@ -1737,7 +4326,12 @@ fn perform_tagsearch(
     reference: u32,
     max_depth: usize,
     min_depth: usize,
-) -> Vec<(TagHash, UEntryHeader)> {
+    follow_skipped: bool,
+) -> (
+    Vec<(TagHash, UEntryHeader, Vec<TagHash>)>,
+    Vec<PrunedReference>,
+) {
+    let mut pruned = vec![];
     let results = search_for_tag(
         cache,
         start_tag,
@@ -1745,19 +4339,29 @@ fn perform_tagsearch(
         reference,
         0,
         max_depth,
+        follow_skipped,
         &mut FastHashSet::default(),
+        &mut vec![start_tag],
+        &mut pruned,
     );
 
     // Remove any duplicates, but keep the order by using an indexmap
-    let results_filtered: FastIndexMap<TagHash, UEntryHeader> = results
+    let results_filtered: FastIndexMap<TagHash, (UEntryHeader, Vec<TagHash>)> = results
         .into_iter()
-        .filter(|(_, _, depth)| *depth > min_depth)
-        .map(|(a, b, _)| (a, b))
+        .filter(|(_, _, depth, _)| *depth > min_depth)
+        .map(|(tag, entry, _, path)| (tag, (entry, path)))
         .collect();
 
-    results_filtered.into_iter().collect()
+    (
+        results_filtered
+            .into_iter()
+            .map(|(tag, (entry, path))| (tag, entry, path))
+            .collect(),
+        pruned,
+    )
 }
 
+#[allow(clippy::too_many_arguments)]
 fn search_for_tag(
     cache: &TagCache,
     tag: TagHash,
@@ -1765,8 +4369,11 @@ fn search_for_tag(
     target_reference: u32,
     depth: usize,
     max_depth: usize,
+    follow_skipped: bool,
     seen: &mut FastHashSet<TagHash>,
-) -> Vec<(TagHash, UEntryHeader, usize)> {
+    path: &mut Vec<TagHash>,
+    pruned: &mut Vec<PrunedReference>,
+) -> Vec<(TagHash, UEntryHeader, usize, Vec<TagHash>)> {
     if depth > max_depth {
         return vec![];
     }
@@ -1793,10 +4400,12 @@ fn search_for_tag(
         if let Some(entry) = package_manager().get_entry(r.hash) {
             let tagtype = TagType::from_type_subtype(entry.file_type, entry.file_subtype);
             if tagtype == target_tagtype {
-                results.push((r.hash, entry, depth));
+                let mut result_path = path.clone();
+                result_path.push(r.hash);
+                results.push((r.hash, entry, depth, result_path));
             } else if tagtype.is_tag() {
-                // Pesky material impact/footstep tags
-                if !matches!(entry.reference, 0x8080873D | 0x8080873F) {
+                if follow_skipped || !classes::is_traversal_skipped(entry.reference) {
+                    path.push(r.hash);
                     results.extend(search_for_tag(
                         cache,
                         r.hash,
@@ -1804,8 +4413,18 @@ fn search_for_tag(
                         target_reference,
                         depth + 1,
                         max_depth,
+                        follow_skipped,
                         seen,
+                        path,
+                        pruned,
                     ));
+                    path.pop();
+                } else {
+                    pruned.push(PrunedReference {
+                        tag: r.hash,
+                        parent: tag,
+                        reference: entry.reference,
+                    });
                 }
             }
         }
@@ -0,0 +1,165 @@
+use std::path::{Path, PathBuf};
+
+use eframe::egui::{self, Color32, RichText};
+use eframe::egui_wgpu::RenderState;
+use eframe::epaint::TextureId;
+use eframe::wgpu;
+use image::RgbaImage;
+
+use crate::texture::Texture;
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum ComparisonDisplay {
+    Texture,
+    Reference,
+    Difference,
+}
+
+/// Compares a texture against a known-good image, eg. to validate deswizzling code
+pub struct TextureComparison {
+    reference_path: PathBuf,
+    texture_image: RgbaImage,
+    reference_image: RgbaImage,
+
+    reference_texture: (Texture, TextureId),
+    difference_texture: (Texture, TextureId),
+
+    /// Maximum difference per channel for pixels to be considered equal
+    tolerance: u8,
+    matching_pixels: usize,
+    max_difference: u8,
+    pub display: ComparisonDisplay,
+}
+
+impl TextureComparison {
+    pub fn new(rs: &RenderState, texture: &Texture, reference_path: &Path) -> anyhow::Result<Self> {
+        let texture_image = texture.to_image(rs, 0)?.to_rgba8();
+        let reference_image = image::open(reference_path)?.to_rgba8();
+
+        if texture_image.dimensions() != reference_image.dimensions() {
+            anyhow::bail!(
+                "Reference image is {}x{}, but the texture is {}x{}",
+                reference_image.width(),
+                reference_image.height(),
+                texture_image.width(),
+                texture_image.height()
+            );
+        }
+
+        // Differences are amplified so small errors are still visible
+        let difference_image =
+            RgbaImage::from_fn(texture_image.width(), texture_image.height(), |x, y| {
+                let a = texture_image.get_pixel(x, y);
+                let b = reference_image.get_pixel(x, y);
+                image::Rgba([
+                    a[0].abs_diff(b[0]).saturating_mul(4),
+                    a[1].abs_diff(b[1]).saturating_mul(4),
+                    a[2].abs_diff(b[2]).saturating_mul(4),
+                    255,
+                ])
+            });
+
+        let reference_texture =
+            register_texture(rs, Texture::from_rgba8(rs, reference_image.clone(), false)?);
+        let difference_texture =
+            register_texture(rs, Texture::from_rgba8(rs, difference_image, false)?);
+
+        let mut comparison = Self {
+            reference_path: reference_path.to_path_buf(),
+            texture_image,
+            reference_image,
+            reference_texture,
+            difference_texture,
+            tolerance: 0,
+            matching_pixels: 0,
+            max_difference: 0,
+            display: ComparisonDisplay::Difference,
+        };
+        comparison.update_statistics();
+
+        Ok(comparison)
+    }
+
+    fn update_statistics(&mut self) {
+        self.matching_pixels = 0;
+        self.max_difference = 0;
+        for (a, b) in self
+            .texture_image
+            .pixels()
+            .zip(self.reference_image.pixels())
+        {
+            let difference = (0..4).map(|c| a[c].abs_diff(b[c])).max().unwrap_or(0);
+            self.max_difference = self.max_difference.max(difference);
+            if difference <= self.tolerance {
+                self.matching_pixels += 1;
+            }
+        }
+    }
+
+    /// The texture to show instead of the original texture, if any
+    pub fn displayed_texture(&self) -> Option<TextureId> {
+        match self.display {
+            ComparisonDisplay::Texture => None,
+            ComparisonDisplay::Reference => Some(self.reference_texture.1),
+            ComparisonDisplay::Difference => Some(self.difference_texture.1),
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.label(format!("Reference: {}", self.reference_path.display()));
+
+        ui.horizontal(|ui| {
+            ui.label("Show:");
+            ui.radio_value(&mut self.display, ComparisonDisplay::Texture, "Texture");
+            ui.radio_value(&mut self.display, ComparisonDisplay::Reference, "Reference");
+            ui.radio_value(
+                &mut self.display,
+                ComparisonDisplay::Difference,
+                "Difference",
+            )
+            .on_hover_text("Absolute difference per channel, amplified 4x");
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Tolerance:");
+            if ui
+                .add(egui::DragValue::new(&mut self.tolerance).range(0..=255))
+                .changed()
+            {
+                self.update_statistics();
+            }
+        });
+
+        let pixel_count =
+            (self.texture_image.width() * self.texture_image.height()).max(1) as usize;
+        let match_percentage = self.matching_pixels as f32 / pixel_count as f32 * 100.0;
+        let color = if self.matching_pixels == pixel_count {
+            Color32::GREEN
+        } else {
+            Color32::YELLOW
+        };
+        ui.label(
+            RichText::new(format!(
+                "{match_percentage:.2}% of pixels match ({}/{pixel_count}), max difference {}",
+                self.matching_pixels, self.max_difference
+            ))
+            .color(color),
+        );
+    }
+
+    pub fn free(self, rs: &RenderState) {
+        let mut renderer = rs.renderer.write();
+        renderer.free_texture(&self.reference_texture.1);
+        renderer.free_texture(&self.difference_texture.1);
+    }
+}
+
+fn register_texture(rs: &RenderState, texture: Texture) -> (Texture, TextureId) {
+    let egui_handle = rs.renderer.write().register_native_texture(
+        &rs.device,
+        &texture.view,
+        wgpu::FilterMode::Nearest,
+    );
+
+    (texture, egui_handle)
+}
@@ -1,7 +1,11 @@
 use destiny_pkg::{manager::PackagePath, TagHash};
 use eframe::egui::{self, pos2, vec2, Color32, Pos2, RichText, Stroke, Ui, Vec2, Widget};
 use eframe::emath::Rot2;
+use eframe::wgpu;
+use itertools::Itertools;
+use parking_lot::Mutex;
 use std::fmt::{Display, Formatter};
+use std::sync::Arc;
 
 use crate::util::ui_image_rotated;
 use crate::{
@@ -10,19 +14,35 @@ use crate::{
     texture::{Texture, TextureCache, TextureDesc},
 };
 
+use super::background_task::spawn_task;
 use super::{common::ResponseExt, View, ViewAction};
 
+/// `selected_package` value for the list of textures from all packages
+const ALL_PACKAGES: u16 = u16::MAX - 1;
+
+type TextureListEntry = (usize, TagHash, TagType, Option<TextureDesc>);
+
 pub struct TexturesView {
     selected_package: u16,
     packages_with_textures: Vec<u16>,
     package_filter: String,
     texture_cache: TextureCache,
-    textures: Vec<(usize, TagHash, TagType, Option<TextureDesc>)>,
+    textures: Vec<TextureListEntry>,
+    /// Filled by the background task that loads the descriptions of all textures
+    all_textures: Arc<Mutex<Option<Vec<TextureListEntry>>>>,
 
     keep_aspect_ratio: bool,
     zoom: f32,
     sorting: Sorting,
     filter_texdesc: String,
+    filter_format: Option<wgpu::TextureFormat>,
+    filter_kind: TextureKind,
+    /// Minimum width and height
+    filter_min_size: u32,
+    /// Maximum width and height
+    filter_max_size: u32,
+    /// Set if textures can't be loaded for the current version/platform at all
+    unsupported_reason: Option<String>,
 }
 
 impl TexturesView {
@@ -33,10 +53,16 @@ impl TexturesView {
             package_filter: String::new(),
             texture_cache,
             textures: vec![],
+            all_textures: Default::default(),
             keep_aspect_ratio: true,
             zoom: 1.0,
             sorting: Sorting::IndexAsc,
             filter_texdesc: String::new(),
+            filter_format: None,
+            filter_kind: TextureKind::Any,
+            filter_min_size: 0,
+            filter_max_size: 16384,
+            unsupported_reason: Texture::unsupported_reason(),
         }
     }
 
@@ -72,6 +98,114 @@ impl TexturesView {
         packages.into_iter().map(|(id, _path)| id).collect()
     }
 
+    /// Loads the descriptions of the textures in every package, so they can be filtered all at once
+    fn load_all_textures(&mut self) {
+        self.textures.clear();
+        let all_textures = self.all_textures.clone();
+        spawn_task("Loading texture descriptions", move |progress| {
+            let hashes = package_manager()
+                .package_entry_index
+                .iter()
+                .flat_map(|(pkg_id, entries)| {
+                    entries.iter().enumerate().filter_map(|(i, e)| {
+                        let st = TagType::from_type_subtype(e.file_type, e.file_subtype);
+                        (st.is_texture() && st.is_header()).then_some((
+                            (*pkg_id as usize) * 8192 + i,
+                            TagHash::new(*pkg_id, i as u16),
+                            st,
+                        ))
+                    })
+                })
+                .collect_vec();
+
+            progress.set_total(hashes.len());
+            let mut textures = Vec::with_capacity(hashes.len());
+            for (i, hash, st) in hashes {
+                progress.check_cancelled()?;
+                textures.push((i, hash, st, Texture::load_desc(hash).ok()));
+                progress.advance();
+            }
+
+            *all_textures.lock() = Some(textures);
+            Ok(())
+        });
+    }
+
+    fn matches_filters(&self, desc: Option<&TextureDesc>) -> bool {
+        let filters_active = self.filter_format.is_some()
+            || self.filter_kind != TextureKind::Any
+            || self.filter_min_size > 0
+            || self.filter_max_size < 16384;
+        let Some(desc) = desc else {
+            return !filters_active;
+        };
+
+        let filter = self.filter_texdesc.to_lowercase();
+        if !filter.is_empty() && !desc.info().to_lowercase().contains(&filter) {
+            return false;
+        }
+
+        (self.filter_format.is_none() || self.filter_format == Some(desc.format))
+            && (self.filter_kind == TextureKind::Any || TextureKind::of(desc) == self.filter_kind)
+            && desc.width.min(desc.height) >= self.filter_min_size
+            && desc.width.max(desc.height) <= self.filter_max_size
+    }
+
+    fn filters_ui(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Texture desc filter: ");
+            ui.text_edit_singleline(&mut self.filter_texdesc);
+        });
+
+        ui.horizontal(|ui| {
+            let formats = self
+                .textures
+                .iter()
+                .filter_map(|(_, _, _, desc)| Some(desc.as_ref()?.format))
+                .unique()
+                .sorted_by_cached_key(|f| format!("{f:?}"))
+                .collect_vec();
+
+            egui::ComboBox::from_label("Format")
+                .selected_text(
+                    self.filter_format
+                        .map(|f| format!("{f:?}"))
+                        .unwrap_or_else(|| "Any".to_string()),
+                )
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut self.filter_format, None, "Any");
+                    for format in formats {
+                        ui.selectable_value(
+                            &mut self.filter_format,
+                            Some(format),
+                            format!("{format:?}"),
+                        );
+                    }
+                });
+
+            egui::ComboBox::from_label("Type")
+                .selected_text(self.filter_kind.label())
+                .show_ui(ui, |ui| {
+                    for kind in [
+                        TextureKind::Any,
+                        TextureKind::Texture2D,
+                        TextureKind::Texture3D,
+                        TextureKind::Cubemap,
+                        TextureKind::Array,
+                    ] {
+                        ui.selectable_value(&mut self.filter_kind, kind, kind.label());
+                    }
+                });
+
+            ui.label("Size:");
+            ui.add(egui::DragValue::new(&mut self.filter_min_size).range(0..=self.filter_max_size));
+            ui.label("to");
+            ui.add(
+                egui::DragValue::new(&mut self.filter_max_size).range(self.filter_min_size..=16384),
+            );
+        });
+    }
+
     fn apply_sorting(&mut self) {
         match self.sorting {
             Sorting::IndexAsc | Sorting::IndexDesc => {
@@ -92,6 +226,11 @@ impl TexturesView {
 
 impl View for TexturesView {
     fn view(&mut self, _ctx: &egui::Context, ui: &mut egui::Ui) -> Option<ViewAction> {
+        if let Some(reason) = &self.unsupported_reason {
+            ui.colored_label(Color32::YELLOW, format!("⚠ {reason}"));
+            return None;
+        }
+
         let mut action = None;
         egui::SidePanel::left("textures_left_panel")
             .resizable(true)
@@ -114,6 +253,20 @@ impl View for TexturesView {
                     .max_width(f32::INFINITY)
                     .show(ui, |ui| {
                         let mut update_filters = false;
+                        if ui
+                            .selectable_value(
+                                &mut self.selected_package,
+                                ALL_PACKAGES,
+                                "All packages",
+                            )
+                            .on_hover_text(
+                                "Loads the description of every texture, which can take a while",
+                            )
+                            .changed()
+                        {
+                            self.load_all_textures();
+                        }
+
                         for id in &self.packages_with_textures {
                             let path = &package_manager().package_paths[id];
                             let package_name = format!("{}_{}", path.name, path.id);
@@ -194,6 +347,13 @@ impl View for TexturesView {
                     });
             });
 
+        if let Some(textures) = self.all_textures.lock().take() {
+            if self.selected_package == ALL_PACKAGES {
+                self.textures = textures;
+                self.apply_sorting();
+            }
+        }
+
         egui::CentralPanel::default().show_inside(ui, |ui| {
             ui.horizontal(|ui| {
                 ui.label("Zoom: ");
@@ -228,10 +388,7 @@ impl View for TexturesView {
                 }
             });
 
-            ui.horizontal(|ui| {
-                ui.label("Texture desc filter: ");
-                ui.text_edit_singleline(&mut self.filter_texdesc).changed();
-            });
+            self.filters_ui(ui);
 
             ui.separator();
             egui::ScrollArea::vertical()
@@ -250,14 +407,9 @@ impl View for TexturesView {
                                 s.interaction.tooltip_delay = 0.0;
                             });
 
-                            let filter = self.filter_texdesc.to_lowercase();
                             for (_i, hash, _tag_type, desc) in &self.textures {
-                                if let Some(desc) = desc {
-                                    if !filter.is_empty()
-                                        && !desc.info().to_lowercase().contains(&filter)
-                                    {
-                                        continue;
-                                    }
+                                if !self.matches_filters(desc.as_ref()) {
+                                    continue;
                                 }
 
                                 let img_container = ui.allocate_response(
@@ -339,6 +491,39 @@ impl View for TexturesView {
     }
 }
 
+#[derive(Clone, Copy, PartialEq)]
+enum TextureKind {
+    Any,
+    Texture2D,
+    Texture3D,
+    Cubemap,
+    Array,
+}
+
+impl TextureKind {
+    fn of(desc: &TextureDesc) -> Self {
+        if desc.array_size == 6 {
+            TextureKind::Cubemap
+        } else if desc.depth > 1 {
+            TextureKind::Texture3D
+        } else if desc.array_size > 1 {
+            TextureKind::Array
+        } else {
+            TextureKind::Texture2D
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            TextureKind::Any => "Any",
+            TextureKind::Texture2D => "2D",
+            TextureKind::Texture3D => "3D",
+            TextureKind::Cubemap => "Cubemap",
+            TextureKind::Array => "Array",
+        }
+    }
+}
+
 #[derive(Default, PartialEq)]
 pub enum Sorting {
     #[default]
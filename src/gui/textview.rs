@@ -0,0 +1,135 @@
+use eframe::egui::{self, RichText};
+
+/// Minimum fraction of printable characters for data to be considered text
+const PRINTABLE_THRESHOLD: f32 = 0.9;
+/// Shorter tags are too likely to pass the heuristic by accident
+const MIN_TEXT_LENGTH: usize = 16;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum TextEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+}
+
+/// Shows tag data that looks like an embedded text, JSON or XML payload as decoded text
+pub struct TagTextView {
+    encoding: TextEncoding,
+    text: String,
+    /// Pretty-printed version of the text, if it parses as JSON
+    pretty_json: Option<String>,
+    show_pretty: bool,
+}
+
+impl TagTextView {
+    /// Returns a text view if the data looks like text, based on the ratio of printable characters
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        // Data is usually padded with zeroes
+        let end = data.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+        let data = &data[..end];
+        if data.len() < MIN_TEXT_LENGTH {
+            return None;
+        }
+
+        let (encoding, text) = if let Some(text) = decode_utf16(data, false) {
+            (TextEncoding::Utf16Le, text)
+        } else if let Some(text) = decode_utf16(data, true) {
+            (TextEncoding::Utf16Be, text)
+        } else {
+            (
+                TextEncoding::Utf8,
+                String::from_utf8_lossy(data).into_owned(),
+            )
+        };
+
+        let text = text.trim_start_matches('\u{feff}').to_string();
+        let char_count = text.chars().count();
+        let printable = text.chars().filter(|&c| is_printable(c)).count();
+        if char_count == 0 || (printable as f32 / char_count as f32) < PRINTABLE_THRESHOLD {
+            return None;
+        }
+
+        let pretty_json = serde_json::from_str::<serde_json::Value>(text.trim_end_matches('\0'))
+            .ok()
+            .filter(|v| v.is_object() || v.is_array())
+            .and_then(|v| serde_json::to_string_pretty(&v).ok());
+
+        Some(Self {
+            encoding,
+            show_pretty: pretty_json.is_some(),
+            text,
+            pretty_json,
+        })
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "{:?}, {} characters",
+                self.encoding,
+                self.text.chars().count()
+            ));
+
+            if self.pretty_json.is_some() {
+                ui.checkbox(&mut self.show_pretty, "Pretty-print JSON");
+            } else {
+                ui.label(RichText::new("Not valid JSON").weak());
+            }
+
+            if ui.button("📋 Copy").clicked() {
+                let text = self.current_text().to_string();
+                ui.output_mut(|o| o.copied_text = text);
+            }
+        });
+
+        ui.separator();
+
+        let mut text = self.current_text();
+        egui::ScrollArea::both()
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                ui.add(
+                    egui::TextEdit::multiline(&mut text)
+                        .font(egui::TextStyle::Monospace)
+                        .desired_width(f32::INFINITY),
+                );
+            });
+    }
+
+    fn current_text(&self) -> &str {
+        match &self.pretty_json {
+            Some(pretty) if self.show_pretty => pretty,
+            _ => &self.text,
+        }
+    }
+}
+
+/// Decodes UTF-16 if (nearly) every other byte is zero, as is the case for mostly-ASCII text
+fn decode_utf16(data: &[u8], big_endian: bool) -> Option<String> {
+    if data.len() < 2 {
+        return None;
+    }
+
+    let units = data
+        .chunks(2)
+        .map(|c| {
+            let c = [c[0], c.get(1).copied().unwrap_or(0)];
+            if big_endian {
+                u16::from_be_bytes(c)
+            } else {
+                u16::from_le_bytes(c)
+            }
+        })
+        .collect::<Vec<u16>>();
+
+    let ascii_units = units.iter().filter(|&&u| u != 0 && u < 0x80).count();
+    if (ascii_units as f32 / units.len() as f32) < PRINTABLE_THRESHOLD {
+        return None;
+    }
+
+    Some(String::from_utf16_lossy(&units))
+}
+
+fn is_printable(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r') || (!c.is_control() && c != char::REPLACEMENT_CHARACTER)
+}
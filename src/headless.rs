@@ -0,0 +1,72 @@
+//! Runs quicktag without a window, so scanning and exports work on machines without a GPU (CI, SSH sessions)
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use destiny_pkg::TagHash;
+use log::{info, warn};
+
+use crate::{
+    gui::{traversal_text, TraversalDirection},
+    package_manager::package_manager,
+    scanner::{self, load_tag_cache},
+    settings::settings,
+    text::{
+        create_raw_string_hash_cache, create_stringmap, dump_strings, export_raw_string_hashes,
+        export_string_sources,
+    },
+};
+
+/// Exports to run after the tag cache has been loaded
+pub struct HeadlessOptions {
+    pub export_raw_strings: bool,
+    pub export_string_sources: bool,
+    /// Directory to write the localized and raw string caches to
+    pub dump_strings: Option<PathBuf>,
+    /// Tag whose traversal tree is printed to stdout
+    pub dump_traversal: Option<TraversalDump>,
+}
+
+pub struct TraversalDump {
+    pub tag: TagHash,
+    pub depth_limit: usize,
+    pub direction: TraversalDirection,
+}
+
+/// Loads (or generates) the tag cache and runs the requested exports. Never touches wgpu or the texture cache
+pub fn run(options: HeadlessOptions) -> anyhow::Result<()> {
+    if let Some(warning) = scanner::check_version_endianness(&package_manager()) {
+        warn!("{warning}");
+    }
+
+    let cache = Arc::new(load_tag_cache()?);
+    info!("Tag cache loaded ({} tags)", cache.hashes.len());
+
+    if options.export_raw_strings {
+        export_raw_string_hashes(&create_raw_string_hash_cache(&cache))?;
+        info!("Raw string hashes written to raw_strings.csv and raw_strings.json");
+    }
+
+    if options.export_string_sources {
+        let (strings, sources) =
+            create_stringmap(&package_manager(), &settings().string_languages.clone())?;
+        export_string_sources(&strings, &sources)?;
+        info!("String sources written to string_sources.csv");
+    }
+
+    if let Some(dir) = &options.dump_strings {
+        let (strings, _) =
+            create_stringmap(&package_manager(), &settings().string_languages.clone())?;
+        dump_strings(dir, &strings, &create_raw_string_hash_cache(&cache))?;
+        info!("Strings dumped to {}", dir.display());
+    }
+
+    if let Some(dump) = options.dump_traversal {
+        print!(
+            "{}",
+            traversal_text(dump.tag, dump.depth_limit, dump.direction, cache)
+        );
+    }
+
+    Ok(())
+}
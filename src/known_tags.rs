@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Context;
+use arc_swap::ArcSwap;
+use destiny_pkg::TagHash;
+use rustc_hash::FxHashMap;
+
+use crate::{package_manager::package_manager, scanner::exe_relative_path, util::parse_tag_hash};
+
+lazy_static::lazy_static! {
+    static ref KNOWN_TAGS: ArcSwap<FxHashMap<TagHash, String>> = ArcSwap::new(Default::default());
+}
+
+/// Path of the user manifest next to the executable, keyed by the same cache key as the tag cache
+pub fn known_tags_path() -> PathBuf {
+    exe_relative_path(format!("known_tags_{}.csv", package_manager().cache_key()))
+}
+
+/// Returns the user-provided label for a tag, if any
+pub fn get_known_tag_label(tag: TagHash) -> Option<String> {
+    KNOWN_TAGS.load().get(&tag).cloned()
+}
+
+/// All labels currently in use, either from the manifest or a loaded session
+pub fn known_tags() -> Arc<FxHashMap<TagHash, String>> {
+    KNOWN_TAGS.load_full()
+}
+
+/// Replaces the labels in use without touching the manifest on disk
+pub fn set_known_tags(known_tags: FxHashMap<TagHash, String>) {
+    KNOWN_TAGS.store(Arc::new(known_tags));
+}
+
+/// (Re)loads the known tags manifest. A missing manifest clears all labels
+pub fn load_known_tags() {
+    let path = known_tags_path();
+    let Ok(manifest) = std::fs::read_to_string(&path) else {
+        KNOWN_TAGS.store(Default::default());
+        return;
+    };
+
+    match parse_known_tags(&manifest) {
+        Ok(o) => {
+            log::info!("Loaded {} known tags from {}", o.len(), path.display());
+            KNOWN_TAGS.store(Arc::new(o));
+        }
+        Err(e) => {
+            log::error!(
+                "Failed to parse known tags manifest {}: {:?}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
+fn parse_known_tags(s: &str) -> anyhow::Result<FxHashMap<TagHash, String>> {
+    let mut known_tags: FxHashMap<TagHash, String> = Default::default();
+
+    // Manifest lines are formatted as `<hash>,<label>`, where the hash can be in any format accepted by the tag input
+    // Empty lines and lines starting with '#' are ignored, as is an optional `hash,label` header
+    for (i, l) in s.lines().enumerate() {
+        let l = l.trim();
+        if l.is_empty() || l.starts_with('#') || (i == 0 && l.eq_ignore_ascii_case("hash,label")) {
+            continue;
+        }
+
+        let (hash, label) = l
+            .split_once(',')
+            .with_context(|| format!("Line {}: missing label", i + 1))?;
+        let tag = parse_tag_hash(hash)
+            .with_context(|| format!("Line {}: invalid tag hash '{hash}'", i + 1))?;
+
+        known_tags.insert(tag, label.trim().trim_matches('"').to_string());
+    }
+
+    Ok(known_tags)
+}
@@ -1,13 +1,18 @@
+mod cache_diff;
 mod classes;
 mod gui;
+mod headless;
+mod known_tags;
 mod package_manager;
 mod panic_handler;
 mod scanner;
+mod settings;
 mod tagtypes;
 mod text;
 mod texture;
 mod util;
 
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use clap::Parser;
@@ -17,10 +22,12 @@ use eframe::egui_wgpu::WgpuConfiguration;
 use eframe::wgpu;
 use env_logger::Env;
 use game_detector::InstalledGame;
-use log::info;
+use log::{error, info, warn};
 
 use crate::classes::initialize_reference_names;
+use crate::gui::TraversalDirection;
 use crate::package_manager::initialize_package_manager;
+use crate::util::parse_tag_hash;
 use crate::{gui::QuickTagApp, package_manager::package_manager};
 
 #[derive(clap::Parser, Debug)]
@@ -32,6 +39,100 @@ struct Args {
     /// Game version for the specified packages directory
     #[arg(short, value_enum)]
     version: Option<GameVersion>,
+
+    /// Tag to open once the cache has been loaded (32/64-bit hex)
+    #[arg(long)]
+    open: Option<String>,
+
+    /// Load or generate the tag cache without opening a window or initializing the GPU
+    #[arg(long)]
+    headless: bool,
+
+    /// Write raw_strings.csv and raw_strings.json (headless only)
+    #[arg(long, requires = "headless")]
+    export_raw_strings: bool,
+
+    /// Write string_sources.csv (headless only)
+    #[arg(long, requires = "headless")]
+    export_string_sources: bool,
+
+    /// Print the traversal tree of this tag (32/64-bit hex) to stdout and exit, without opening a window
+    #[arg(long, value_name = "TAG")]
+    dump_traversal: Option<String>,
+
+    /// Maximum traversal depth for --dump-traversal
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 16,
+        requires = "dump_traversal"
+    )]
+    depth: usize,
+
+    /// Whether --dump-traversal follows the references of the tag (down) or the tags referencing it (up)
+    #[arg(long, value_enum, default_value = "down", requires = "dump_traversal")]
+    direction: TraversalDirection,
+
+    /// Write the localized strings and raw string hashes (including the wordlist) as CSV and JSON to this directory once loaded
+    #[arg(long, value_name = "DIR")]
+    dump_strings: Option<PathBuf>,
+
+    /// Additional entry type to scan for references, as `type` or `type:subtype` (eg. `32:1`). Can be given multiple times.
+    /// Caches with extra types are stored separately from the default cache
+    #[arg(long = "scan-type", value_name = "TYPE[:SUBTYPE]", value_parser = parse_scan_type)]
+    scan_types: Vec<(u8, Option<u8>)>,
+
+    /// Compare two cache files (eg. from before and after a patch) and print the added, removed and changed tags, then exit.
+    /// Doesn't need a packages directory
+    #[arg(long, num_args = 2, value_names = ["OLD", "NEW"])]
+    diff_caches: Option<Vec<PathBuf>>,
+
+    /// Also write the cache diff as JSON to this file
+    #[arg(long, value_name = "PATH", requires = "diff_caches")]
+    diff_json: Option<PathBuf>,
+}
+
+fn parse_scan_type(s: &str) -> Result<(u8, Option<u8>), String> {
+    let (ftype, fsubtype) = match s.split_once(':') {
+        Some((ftype, fsubtype)) => (ftype, Some(fsubtype)),
+        None => (s, None),
+    };
+
+    let ftype = ftype
+        .trim()
+        .parse::<u8>()
+        .map_err(|e| format!("invalid type '{ftype}': {e}"))?;
+    let fsubtype = fsubtype
+        .map(|st| {
+            st.trim()
+                .parse::<u8>()
+                .map_err(|e| format!("invalid subtype '{st}': {e}"))
+        })
+        .transpose()?;
+
+    Ok((ftype, fsubtype))
+}
+
+/// Includes the platform and the highest package patch, so windows for different datasets can be told apart
+fn window_title() -> String {
+    let pm = package_manager();
+    let max_patch = pm
+        .package_paths
+        .values()
+        .map(|p| p.patch)
+        .max()
+        .unwrap_or(0);
+    let patch = if max_patch > 0 {
+        format!(", patch {max_patch}")
+    } else {
+        String::new()
+    };
+
+    format!(
+        "Quicktag - {} ({:?}{patch})",
+        pm.version.name(),
+        pm.platform
+    )
 }
 
 fn main() -> eframe::Result<()> {
@@ -50,6 +151,15 @@ fn main() -> eframe::Result<()> {
     .init();
     let args = Args::parse();
 
+    if let Some(paths) = &args.diff_caches {
+        if let Err(e) = cache_diff::run(&paths[0], &paths[1], args.diff_json.as_deref()) {
+            error!("Cache diff failed: {e:?}");
+            std::process::exit(1);
+        }
+
+        return Ok(());
+    }
+
     let packages_path = if let Some(packages_path) = args.packages_path {
         packages_path
     } else if let Some(path) = find_d2_packages_path() {
@@ -75,10 +185,51 @@ fn main() -> eframe::Result<()> {
 
     initialize_reference_names();
 
+    if !args.scan_types.is_empty() {
+        info!("Scanning additional entry types: {:?}", args.scan_types);
+        scanner::set_extra_scan_types(args.scan_types);
+    }
+
+    if args.headless || args.dump_traversal.is_some() {
+        let dump_traversal = args.dump_traversal.as_deref().map(|s| {
+            let Some(tag) = parse_tag_hash(s) else {
+                error!("'{s}' is not a valid tag hash");
+                std::process::exit(1);
+            };
+
+            headless::TraversalDump {
+                tag,
+                depth_limit: args.depth,
+                direction: args.direction,
+            }
+        });
+
+        let options = headless::HeadlessOptions {
+            export_raw_strings: args.export_raw_strings,
+            export_string_sources: args.export_string_sources,
+            dump_strings: args.dump_strings,
+            dump_traversal,
+        };
+        if let Err(e) = headless::run(options) {
+            error!("Headless run failed: {e:?}");
+            std::process::exit(1);
+        }
+
+        return Ok(());
+    }
+
+    let open_tag = args.open.as_deref().and_then(|s| {
+        let tag = parse_tag_hash(s);
+        if tag.is_none() {
+            warn!("'{s}' is not a valid tag hash, ignoring --open");
+        }
+        tag
+    });
+
     let native_options = eframe::NativeOptions {
         renderer: eframe::Renderer::Wgpu,
         viewport: ViewportBuilder::default()
-            .with_title(format!("Quicktag - {}", package_manager().version.name()))
+            .with_title(window_title())
             .with_icon(
                 eframe::icon_data::from_png_bytes(include_bytes!("../quicktag.png"))
                     .expect("Failed to load icon"),
@@ -102,7 +253,7 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "Quicktag",
         native_options,
-        Box::new(|cc| Ok(Box::new(QuickTagApp::new(cc)))),
+        Box::new(move |cc| Ok(Box::new(QuickTagApp::new(cc, open_tag, args.dump_strings)))),
     )
 }
 
@@ -1,45 +1,147 @@
 use std::{
-    fmt::Display,
+    fmt::{Display, Write as _},
     fs::File,
+    hash::{Hash, Hasher},
     io::{Cursor, Read, Seek, SeekFrom},
+    ops::Range,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
     time::SystemTime,
 };
 
+use anyhow::Context;
 use binrw::{BinReaderExt, Endian};
-use destiny_pkg::{GameVersion, PackageManager, TagHash, TagHash64};
+use destiny_pkg::{manager::PackagePath, GameVersion, PackageManager, TagHash, TagHash64};
 use eframe::epaint::mutex::RwLock;
 use itertools::Itertools;
 use log::{error, info, warn};
 use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHasher};
 
 use crate::{
     classes::get_class_by_id,
     package_manager::package_manager,
+    settings::settings,
     text::{create_stringmap, StringCache},
     util::{u32_from_endian, u64_from_endian},
 };
 
-#[derive(bincode::Encode, bincode::Decode)]
+#[derive(Clone, bincode::Encode, bincode::Decode)]
 pub struct TagCache {
     /// Timestamp of the packages directory
     pub timestamp: u64,
 
+    /// Fingerprint of the package set the cache was generated from, see [`package_set_fingerprint`]
+    pub package_fingerprint: u64,
+
     pub version: u32,
 
     pub hashes: FxHashMap<TagHash, ScanResult>,
+
+    /// Groups of tags with the same [`ScanResult::data_hash`]. The data itself isn't compared
+    pub identical_data: FxHashMap<u64, Vec<TagHash>>,
+
+    /// Tags larger than this many bytes weren't scanned, 0 if there was no limit
+    pub max_tag_size: u32,
 }
 
 impl Default for TagCache {
     fn default() -> Self {
         Self {
             timestamp: 0,
-            version: 7,
+            package_fingerprint: 0,
+            version: 13,
             hashes: Default::default(),
+            identical_data: Default::default(),
+            max_tag_size: 0,
+        }
+    }
+}
+
+impl TagCache {
+    /// Reads a zstd-compressed cache file. Doesn't check whether the cache version or timestamp are up to date
+    pub fn read_from(path: impl AsRef<Path>) -> anyhow::Result<TagCache> {
+        let mut reader = zstd::Decoder::new(File::open(path)?)?;
+        let mut cache_data = vec![];
+        reader.read_to_end(&mut cache_data)?;
+
+        let (cache, _) =
+            bincode::decode_from_slice::<TagCache, _>(&cache_data, bincode::config::standard())?;
+        Ok(cache)
+    }
+
+    /// Like [`TagCache::read_from`], but also catches panics from decoding corrupted data
+    pub fn load(path: impl AsRef<Path>) -> CacheLoadResult {
+        let path = path.as_ref();
+        if !path.exists() {
+            return CacheLoadResult::Missing;
+        }
+
+        match std::panic::catch_unwind(|| Self::read_from(path)) {
+            Ok(Ok(cache)) => CacheLoadResult::Loaded(cache),
+            Ok(Err(e)) => CacheLoadResult::Unrecoverable(e),
+            Err(_) => {
+                CacheLoadResult::Unrecoverable(anyhow::anyhow!("Panicked while decoding cache"))
+            }
         }
     }
+
+    /// Writes the cache to a zstd-compressed file
+    pub fn write_to(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let mut writer = zstd::Encoder::new(File::create(path)?, 3)?;
+        bincode::encode_into_std_write(self, &mut writer, bincode::config::standard())?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    /// Returns true if the tag is in the cache, but its data could not be read during scanning
+    pub fn read_failed(&self, tag: TagHash) -> bool {
+        self.hashes
+            .get(&tag)
+            .map(|s| !s.successful)
+            .unwrap_or_default()
+    }
+
+    /// Returns the other tags whose data has the same content hash as this tag
+    pub fn identical_tags(&self, tag: TagHash) -> Vec<TagHash> {
+        self.hashes
+            .get(&tag)
+            .filter(|s| s.data_hash != 0)
+            .and_then(|s| self.identical_data.get(&s.data_hash))
+            .map(|tags| tags.iter().copied().filter(|t| *t != tag).collect())
+            .unwrap_or_default()
+    }
+
+    /// Size of the tag data as recorded during scanning, without reading the tag
+    pub fn data_size(&self, tag: TagHash) -> Option<u32> {
+        self.hashes
+            .get(&tag)
+            .filter(|s| s.successful)
+            .map(|s| s.data_size)
+    }
+
+    /// Returns true if the tag wasn't scanned because it exceeded [`TagCache::max_tag_size`]
+    pub fn skipped_too_large(&self, tag: TagHash) -> bool {
+        self.hashes
+            .get(&tag)
+            .map(|s| s.skipped_too_large)
+            .unwrap_or_default()
+    }
+
+    /// Returns the reason the tag data could not be read during scanning, if any
+    pub fn read_error(&self, tag: TagHash) -> Option<&str> {
+        self.hashes.get(&tag).and_then(|s| s.read_error.as_deref())
+    }
+}
+
+pub enum CacheLoadResult {
+    Loaded(TagCache),
+    Missing,
+    /// The cache file is truncated or otherwise corrupted, and has to be regenerated
+    Unrecoverable(anyhow::Error),
 }
 
 // Shareable read-only context
@@ -49,12 +151,21 @@ pub struct ScannerContext {
     pub known_string_hashes: Vec<u32>,
     pub known_wordlist_hashes: Vec<u32>,
     pub endian: Endian,
+    pub version: GameVersion,
 }
 
 #[derive(Clone, bincode::Encode, bincode::Decode, Debug)]
 pub struct ScanResult {
     /// Were we able to read the tag data?
     pub successful: bool,
+    /// Why the tag data couldn't be read, if `successful` is false
+    pub read_error: Option<String>,
+    /// Hash of the tag data, used to find tags with the same content. 0 if the tag is empty or wasn't read
+    pub data_hash: u64,
+    /// Size of the tag data in bytes. 0 if the tag wasn't read
+    pub data_size: u32,
+    /// The tag exceeded the max tag size, so only its size was recorded
+    pub skipped_too_large: bool,
 
     pub file_hashes: Vec<ScannedHash<TagHash>>,
     pub file_hashes64: Vec<ScannedHash<TagHash64>>,
@@ -62,14 +173,18 @@ pub struct ScanResult {
     pub wordlist_hashes: Vec<ScannedHash<u32>>,
     pub raw_strings: Vec<String>,
 
-    /// References from other files
-    pub references: Vec<TagHash>,
+    /// References from other files, along with the offset of the reference in that file
+    pub references: Vec<(TagHash, u64)>,
 }
 
 impl Default for ScanResult {
     fn default() -> Self {
         ScanResult {
             successful: true,
+            read_error: None,
+            data_hash: 0,
+            data_size: 0,
+            skipped_too_large: false,
             file_hashes: Default::default(),
             file_hashes64: Default::default(),
             string_hashes: Default::default(),
@@ -93,6 +208,17 @@ pub struct ScannedArray {
     pub class: u32,
 }
 
+/// Cheap 64-bit hash of tag data for finding duplicates. Returns 0 for empty data
+pub fn hash_data(data: &[u8]) -> u64 {
+    if data.is_empty() {
+        return 0;
+    }
+
+    let mut hasher = FxHasher::default();
+    hasher.write(data);
+    hasher.finish()
+}
+
 pub const FNV1_BASE: u32 = 0x811c9dc5;
 pub const FNV1_PRIME: u32 = 0x01000193;
 pub fn fnv1(data: &[u8]) -> u32 {
@@ -107,48 +233,14 @@ pub fn scan_file(context: &ScannerContext, data: &[u8], tags_only: bool) -> Scan
         format!("data len = {} bytes", data.len()).as_str()
     );
 
-    let mut r = ScanResult::default();
+    let mut r = ScanResult {
+        data_hash: hash_data(data),
+        data_size: data.len() as u32,
+        ..Default::default()
+    };
 
     // Pass 1: find array ranges we should skip (classes marked with @block_tags)
-    let mut blocked_ranges = vec![];
-    for offset in (0..data.len()).step_by(4) {
-        if offset + 4 > data.len() {
-            break;
-        }
-        let m: [u8; 4] = data[offset..offset + 4].try_into().unwrap();
-        let value = u32_from_endian(context.endian, m);
-
-        if matches!(
-            value,
-            0x80809fbd | // Pre-BL
-            0x80809fb8 | // Post-BL
-            0x80800184 |
-            0x80800142
-        ) {
-            let array_offset = offset as u64 + 4;
-            let array: Option<(u64, u32)> = (|| {
-                let mut c = Cursor::new(&data);
-                c.seek(SeekFrom::Start(array_offset)).ok()?;
-                if matches!(
-                    package_manager().version,
-                    GameVersion::DestinyInternalAlpha | GameVersion::DestinyTheTakenKing
-                ) {
-                    Some((c.read_be::<u32>().ok()? as u64, c.read_be::<u32>().ok()?))
-                } else {
-                    Some((c.read_le::<u64>().ok()?, c.read_le::<u32>().ok()?))
-                }
-            })();
-
-            if let Some((count, class)) = array {
-                if let Some(class) = get_class_by_id(class) {
-                    if class.block_tags {
-                        let array_size = class.array_size(count as usize).unwrap_or(count as usize);
-                        blocked_ranges.push(array_offset..array_offset + array_size as u64);
-                    }
-                }
-            }
-        }
-    }
+    let blocked_ranges = find_blocked_ranges(context.endian, context.version, data);
 
     // Pass 2: everything else
     for offset in (0..data.len()).step_by(4) {
@@ -174,15 +266,12 @@ pub fn scan_file(context: &ScannerContext, data: &[u8], tags_only: bool) -> Scan
             });
         }
 
-        if !tags_only {
-            // cohae: 0x808000CB is used in the alpha
-            if matches!(value, 0x80800065 | 0x808000CB) {
-                r.raw_strings.extend(
-                    read_raw_string_blob(data, offset as u64)
-                        .into_iter()
-                        .map(|(_, s)| s),
-                );
-            }
+        if !tags_only && RAW_STRING_BLOB_CLASSES.contains(&value) {
+            r.raw_strings.extend(
+                read_raw_string_blob_for_version(context.version, data, offset as u64)
+                    .into_iter()
+                    .map(|(_, s)| s),
+            );
         }
 
         if value != 0x811c9dc5 && context.known_string_hashes.binary_search(&value).is_ok() {
@@ -220,15 +309,88 @@ pub fn scan_file(context: &ScannerContext, data: &[u8], tags_only: bool) -> Scan
     r
 }
 
+/// Finds the byte ranges of arrays whose class is marked with `@block_tags`.
+/// The scanner doesn't pick up any hashes inside of these ranges
+pub fn find_blocked_ranges(endian: Endian, version: GameVersion, data: &[u8]) -> Vec<Range<u64>> {
+    let mut blocked_ranges = vec![];
+    for offset in (0..data.len()).step_by(4) {
+        if offset + 4 > data.len() {
+            break;
+        }
+        let m: [u8; 4] = data[offset..offset + 4].try_into().unwrap();
+        let value = u32_from_endian(endian, m);
+
+        if matches!(
+            value,
+            0x80809fbd | // Pre-BL
+            0x80809fb8 | // Post-BL
+            0x80800184 |
+            0x80800142
+        ) {
+            let array_offset = offset as u64 + 4;
+            let array: Option<(u64, u32)> = (|| {
+                let mut c = Cursor::new(&data);
+                c.seek(SeekFrom::Start(array_offset)).ok()?;
+                if matches!(
+                    version,
+                    GameVersion::DestinyInternalAlpha | GameVersion::DestinyTheTakenKing
+                ) {
+                    Some((c.read_be::<u32>().ok()? as u64, c.read_be::<u32>().ok()?))
+                } else {
+                    Some((c.read_le::<u64>().ok()?, c.read_le::<u32>().ok()?))
+                }
+            })();
+
+            if let Some((count, class)) = array {
+                if let Some(class) = get_class_by_id(class) {
+                    if class.block_tags {
+                        let array_size = class.array_size(count as usize).unwrap_or(count as usize);
+                        blocked_ranges.push(array_offset..array_offset + array_size as u64);
+                    }
+                }
+            }
+        }
+    }
+
+    blocked_ranges
+}
+
+/// Class hashes of raw string blobs. 0x808000CB is used in the alpha
+pub const RAW_STRING_BLOB_CLASSES: [u32; 2] = [0x80800065, 0x808000CB];
+
+/// Reads every raw string blob in the data, the same way [`scan_file`] does
+///
+/// Returns (offset, string) pairs. Blobs inside `blocked_ranges` are skipped
 #[profiling::function]
-pub fn read_raw_string_blob(data: &[u8], offset: u64) -> Vec<(u64, String)> {
+pub fn find_raw_strings(
+    endian: Endian,
+    version: GameVersion,
+    data: &[u8],
+    blocked_ranges: &[Range<u64>],
+) -> Vec<(u64, String)> {
+    data.chunks_exact(4)
+        .enumerate()
+        .map(|(i, c)| (i as u64 * 4, u32_from_endian(endian, c.try_into().unwrap())))
+        .filter(|(offset, value)| {
+            RAW_STRING_BLOB_CLASSES.contains(value)
+                && !blocked_ranges.iter().any(|range| range.contains(offset))
+        })
+        .flat_map(|(offset, _)| read_raw_string_blob_for_version(version, data, offset))
+        .collect()
+}
+
+pub fn read_raw_string_blob_for_version(
+    version: GameVersion,
+    data: &[u8],
+    offset: u64,
+) -> Vec<(u64, String)> {
     let mut strings = vec![];
 
     let mut c = Cursor::new(data);
     (|| {
         c.seek(SeekFrom::Start(offset + 4))?;
         let (buffer_size, buffer_base_offset) = if matches!(
-            package_manager().version,
+            version,
             GameVersion::DestinyInternalAlpha | GameVersion::DestinyTheTakenKing
         ) {
             let buffer_size: u32 = c.read_be()?;
@@ -270,13 +432,89 @@ pub fn read_raw_string_blob(data: &[u8], offset: u64) -> Vec<(u64, String)> {
     strings
 }
 
-pub fn create_scanner_context(package_manager: &PackageManager) -> anyhow::Result<ScannerContext> {
+/// Samples tag data to check whether it matches the endianness of the selected game version
+///
+/// Returns a warning if the data looks like it was written with the opposite endianness, which usually means the wrong version was selected
+pub fn check_version_endianness(package_manager: &PackageManager) -> Option<String> {
+    const MAX_SAMPLES: usize = 64;
+    const MAX_SAMPLE_SIZE: usize = 0x10000;
+
+    let endian = package_manager.version.endian();
+    let swapped_endian = match endian {
+        Endian::Big => Endian::Little,
+        Endian::Little => Endian::Big,
+    };
+
+    let mut samples = package_manager
+        .package_entry_index
+        .iter()
+        .sorted_by_key(|(pkg_id, _)| **pkg_id)
+        .flat_map(|(pkg_id, entries)| {
+            entries
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| (16..=MAX_SAMPLE_SIZE as u32).contains(&e.file_size))
+                .map(|(i, _)| TagHash::new(*pkg_id, i as u16))
+                .take(4)
+        });
+
+    let mut expected_hits = 0;
+    let mut swapped_hits = 0;
+    let mut sampled = 0;
+    while sampled < MAX_SAMPLES {
+        let Some(tag) = samples.next() else {
+            break;
+        };
+
+        let Ok(data) = package_manager.read_tag(tag) else {
+            continue;
+        };
+
+        sampled += 1;
+        for chunk in data.chunks_exact(4) {
+            let m: [u8; 4] = chunk.try_into().unwrap();
+            if u32_from_endian(endian, m) >> 16 == 0x8080 {
+                expected_hits += 1;
+            }
+            if u32_from_endian(swapped_endian, m) >> 16 == 0x8080 {
+                swapped_hits += 1;
+            }
+        }
+    }
+
+    if swapped_hits < 16 || swapped_hits < expected_hits * 4 {
+        return None;
+    }
+
+    let suggested_versions = <GameVersion as clap::ValueEnum>::value_variants()
+        .iter()
+        .filter(|v| v.endian() == swapped_endian)
+        .map(|v| v.name())
+        .join(", ");
+
+    let endian_name = |e: Endian| match e {
+        Endian::Big => "big",
+        Endian::Little => "little",
+    };
+
+    Some(format!(
+        "Tag data looks {}-endian, but {} expects {}-endian data. Did you select the right version? (possibly one of: {suggested_versions})",
+        endian_name(swapped_endian),
+        package_manager.version.name(),
+        endian_name(endian),
+    ))
+}
+
+pub fn create_scanner_context(
+    package_manager: &PackageManager,
+    string_languages: &[String],
+) -> anyhow::Result<ScannerContext> {
     info!("Creating scanner context");
 
     // TODO(cohae): TTK PS4 is little endian
     let endian = package_manager.version.endian();
 
-    let stringmap = create_stringmap()?;
+    let (stringmap, _) = create_stringmap(package_manager, string_languages)?;
 
     let mut wordlist = StringCache::default();
     {
@@ -313,6 +551,7 @@ pub fn create_scanner_context(package_manager: &PackageManager) -> anyhow::Resul
         known_string_hashes: stringmap.keys().cloned().collect(),
         known_wordlist_hashes: wordlist.keys().cloned().collect(),
         endian,
+        version: package_manager.version,
     };
 
     res.valid_file_hashes.sort_unstable();
@@ -363,256 +602,539 @@ impl Display for ScanStatus {
 
 lazy_static::lazy_static! {
     static ref SCANNER_PROGRESS: RwLock<ScanStatus> = RwLock::new(ScanStatus::None);
+    /// Entry types scanned on top of the default tag types, as (type, subtype). A subtype of `None` matches any subtype
+    static ref EXTRA_SCAN_TYPES: RwLock<Vec<(u8, Option<u8>)>> = RwLock::new(vec![]);
+}
+
+/// Sets additional entry types to scan. Must be called before the cache is loaded, as it changes the cache file
+pub fn set_extra_scan_types(mut types: Vec<(u8, Option<u8>)>) {
+    types.sort();
+    types.dedup();
+    *EXTRA_SCAN_TYPES.write() = types;
+}
+
+/// Caches with extra scan types get their own file, so they don't get mixed up with the default cache.
+/// The max tag size is stored in the cache itself instead, and a cache built with a different one is rebuilt on load
+fn cache_file_name() -> String {
+    let mut name = format!("tags_{}", package_manager().cache_key());
+
+    let extra_types = EXTRA_SCAN_TYPES.read();
+    if !extra_types.is_empty() {
+        let suffix = extra_types
+            .iter()
+            .map(|(ftype, fsubtype)| match fsubtype {
+                Some(fsubtype) => format!("{ftype}-{fsubtype}"),
+                None => ftype.to_string(),
+            })
+            .join("_");
+        write!(name, "_extra_{suffix}").ok();
+    }
+
+    name + ".cache"
 }
 
 pub fn scanner_progress() -> ScanStatus {
     *SCANNER_PROGRESS.read()
 }
 
-pub fn load_tag_cache() -> TagCache {
-    let cache_name = format!("tags_{}.cache", package_manager().cache_key());
-    let cache_file_path = exe_relative_path(&cache_name);
+/// Options for driving [`load_tag_cache_with`] from outside the GUI
+#[derive(Default)]
+pub struct ScanOptions {
+    /// Once set, scanning stops before the next package and no cache file is written
+    pub cancel: Arc<AtomicBool>,
+    /// Called on every status change, on top of updating [`scanner_progress`]
+    pub progress: Option<Box<dyn Fn(ScanStatus) + Send + Sync>>,
+}
 
-    if let Ok(cache_file) = File::open(&cache_file_path) {
-        info!("Existing cache file found, loading");
-        *SCANNER_PROGRESS.write() = ScanStatus::LoadingCache;
+impl ScanOptions {
+    fn report(&self, status: ScanStatus) {
+        *SCANNER_PROGRESS.write() = status;
+        if let Some(progress) = &self.progress {
+            progress(status);
+        }
+    }
+}
 
-        let cache_data = zstd::Decoder::new(cache_file).and_then(|mut r| {
-            let mut buf = vec![];
-            r.read_to_end(&mut buf)?;
-            Ok(buf)
-        });
+/// Loads the tag cache, building it if needed. Fails if the cache file is from a newer version of quicktag
+pub fn load_tag_cache() -> anyhow::Result<TagCache> {
+    Ok(load_tag_cache_with(&ScanOptions::default())?
+        .expect("Cache loading can't be cancelled without a cancel flag"))
+}
 
-        match cache_data {
-            Ok(cache_data) => {
-                if let Ok((cache, _)) = bincode::decode_from_slice::<TagCache, _>(
-                    &cache_data,
-                    bincode::config::standard(),
-                ) {
-                    match cache.version.cmp(&TagCache::default().version) {
-                        std::cmp::Ordering::Equal => {
-                            let current_pkg_timestamp =
-                                std::fs::metadata(&package_manager().package_dir)
-                                    .ok()
-                                    .and_then(|m| {
-                                        Some(
-                                            m.modified()
-                                                .ok()?
-                                                .duration_since(SystemTime::UNIX_EPOCH)
-                                                .ok()?
-                                                .as_secs(),
-                                        )
-                                    })
-                                    .unwrap_or(0);
-
-                            if cache.timestamp < current_pkg_timestamp {
-                                info!(
-                                    "Cache is out of date, rebuilding (cache: {}, package dir: {})",
-                                    chrono::DateTime::from_timestamp(cache.timestamp as i64, 0)
-                                        .unwrap()
-                                        .format("%Y-%m-%d"),
-                                    chrono::DateTime::from_timestamp(
-                                        current_pkg_timestamp as i64,
-                                        0
-                                    )
-                                    .unwrap()
-                                    .format("%Y-%m-%d"),
-                                );
-                            } else {
-                                *SCANNER_PROGRESS.write() = ScanStatus::None;
-                                return cache;
-                            }
-                        }
-                        std::cmp::Ordering::Less => {
-                            info!(
-                                "Cache is out of date, rebuilding (cache: {}, quicktag: {})",
-                                cache.version,
-                                TagCache::default().version
-                            );
-                        }
-                        std::cmp::Ordering::Greater => {
-                            error!("Tried to open a future version cache with an old quicktag version (cache: {}, quicktag: {})",
-                                cache.version,
-                                TagCache::default().version
-                            );
-
-                            native_dialog::MessageDialog::new()
-                                .set_type(native_dialog::MessageType::Error)
-                                .set_title("Future cache")
-                                .set_text(&format!("Your cache file ({cache_name}) is newer than this build of quicktag\n\nCache version: v{}\nExpected version: v{}", cache.version, TagCache::default().version))
-                                .show_alert()
-                                .unwrap();
-
-                            std::process::exit(21);
-                        }
+/// Loads the tag cache, building it if needed. Returns `None` if the scan was cancelled through `options.cancel`,
+/// and fails if the cache file is from a newer version of quicktag
+pub fn load_tag_cache_with(options: &ScanOptions) -> anyhow::Result<Option<TagCache>> {
+    let cache_name = cache_file_name();
+    let cache_file_path = exe_relative_path(&cache_name);
+
+    options.report(ScanStatus::LoadingCache);
+    match TagCache::load(&cache_file_path) {
+        CacheLoadResult::Loaded(cache) => {
+            info!("Existing cache file found");
+            match cache.version.cmp(&TagCache::default().version) {
+                std::cmp::Ordering::Equal => {
+                    let current_pkg_timestamp =
+                        package_dir_timestamp(&package_manager().package_dir);
+
+                    let max_tag_size = settings().scan_max_tag_size;
+                    if cache.timestamp < current_pkg_timestamp {
+                        info!(
+                            "Cache is out of date, rebuilding (cache: {}, package dir: {})",
+                            chrono::DateTime::from_timestamp(cache.timestamp as i64, 0)
+                                .unwrap()
+                                .format("%Y-%m-%d"),
+                            chrono::DateTime::from_timestamp(current_pkg_timestamp as i64, 0)
+                                .unwrap()
+                                .format("%Y-%m-%d"),
+                        );
+                    } else if cache.max_tag_size != max_tag_size {
+                        info!(
+                            "Max tag size changed, rebuilding (cache: {}, settings: {max_tag_size})",
+                            cache.max_tag_size
+                        );
+                    } else {
+                        options.report(ScanStatus::None);
+                        return Ok(Some(cache));
                     }
-                } else {
-                    warn!("Cache file is invalid, creating a new one");
                 }
+                std::cmp::Ordering::Less => {
+                    info!(
+                        "Cache is out of date, rebuilding (cache: {}, quicktag: {})",
+                        cache.version,
+                        TagCache::default().version
+                    );
+                }
+                std::cmp::Ordering::Greater => {
+                    options.report(ScanStatus::None);
+                    anyhow::bail!(
+                        "Your cache file ({cache_name}) is newer than this build of quicktag\n\nCache version: v{}\nExpected version: v{}",
+                        cache.version,
+                        TagCache::default().version
+                    );
+                }
+            }
+        }
+        CacheLoadResult::Missing => {}
+        CacheLoadResult::Unrecoverable(e) => {
+            warn!("Cache file is corrupted, deleting it and creating a new one: {e:?}");
+            if let Err(e) = std::fs::remove_file(&cache_file_path) {
+                error!("Failed to delete corrupted cache file: {e}");
             }
-            Err(e) => error!("Cache file is invalid: {e}"),
         }
     }
 
+    scan_and_write_cache(&cache_file_path, options)
+}
+
+/// Rescans all packages and overwrites the cache file, even if the existing cache is up to date
+pub fn rebuild_tag_cache() -> anyhow::Result<TagCache> {
+    info!("Rebuilding tag cache");
+    Ok(scan_and_write_cache(
+        &exe_relative_path(&cache_file_name()),
+        &ScanOptions::default(),
+    )
+    .context("Failed to rebuild tag cache")?
+    .expect("Cache rebuilding can't be cancelled without a cancel flag"))
+}
+
+/// Rescans a single package, merges the results into `cache` and writes the updated cache file
+pub fn rescan_package(cache: &TagCache, pkg_id: u16) -> anyhow::Result<TagCache> {
     *SCANNER_PROGRESS.write() = ScanStatus::CreatingScanner;
-    let scanner_context = Arc::new(
-        create_scanner_context(&package_manager()).expect("Failed to create scanner context"),
-    );
+    let new_cache = scanner_from_settings()
+        .and_then(|scanner| {
+            scanner.rescan_package(cache, pkg_id, |status| *SCANNER_PROGRESS.write() = status)
+        })
+        .and_then(|new_cache| {
+            *SCANNER_PROGRESS.write() = ScanStatus::WritingCache;
+            new_cache
+                .write_to(exe_relative_path(cache_file_name()))
+                .context("Failed to write tag cache")?;
+            Ok(new_cache)
+        })
+        .with_context(|| format!("Failed to rescan package {pkg_id:04X}"));
+    *SCANNER_PROGRESS.write() = ScanStatus::None;
 
-    let all_pkgs = package_manager()
-        .package_paths
-        .values()
-        .cloned()
-        .collect_vec();
+    new_cache
+}
 
-    let version = package_manager().version;
-    let package_count = all_pkgs.len();
-    let cache: FxHashMap<TagHash, ScanResult> = all_pkgs
-        .par_iter()
-        .map_with(scanner_context, |context, path| {
-            profiling::scope!("scan_pkg", &path.path);
-            let current_package = {
-                let mut p = SCANNER_PROGRESS.write();
-                let current_package = if let ScanStatus::Scanning {
-                    current_package, ..
-                } = *p
-                {
-                    current_package
-                } else {
-                    0
+/// Returns `None` without touching the cache file if the scan was cancelled
+fn scan_and_write_cache(
+    cache_file_path: &Path,
+    options: &ScanOptions,
+) -> anyhow::Result<Option<TagCache>> {
+    options.report(ScanStatus::CreatingScanner);
+    let scanned = scanner_from_settings().and_then(|scanner| {
+        scanner.scan_all_cancellable(|status| options.report(status), &options.cancel)
+    });
+    let cache = match scanned {
+        Ok(Some(cache)) => cache,
+        Ok(None) => {
+            info!("Cache build cancelled");
+            options.report(ScanStatus::None);
+            return Ok(None);
+        }
+        Err(e) => {
+            options.report(ScanStatus::None);
+            return Err(e);
+        }
+    };
+
+    options.report(ScanStatus::WritingCache);
+    info!("Compressing tag cache...");
+    let written = cache.write_to(cache_file_path);
+    options.report(ScanStatus::None);
+    written.context("Failed to write tag cache")?;
+
+    Ok(Some(cache))
+}
+
+/// Creates a scanner configured with the string languages and max tag size from the settings, and the types set with [`set_extra_scan_types`]
+pub fn scanner_from_settings() -> anyhow::Result<Scanner> {
+    let (string_languages, max_tag_size) = {
+        let settings = settings();
+        (
+            settings.string_languages.clone(),
+            settings.scan_max_tag_size,
+        )
+    };
+
+    Ok(Scanner::new(package_manager(), &string_languages)?
+        .with_extra_types(EXTRA_SCAN_TYPES.read().clone())
+        .with_max_tag_size(max_tag_size))
+}
+
+/// Tag scanner that can be used without the GUI
+///
+/// Unlike [`load_tag_cache`], this doesn't report progress through [`scanner_progress`] and doesn't read or write the cache file, see [`TagCache::read_from`] and [`TagCache::write_to`] for that.
+pub struct Scanner {
+    package_manager: Arc<PackageManager>,
+    context: Arc<ScannerContext>,
+    extra_types: Vec<(u8, Option<u8>)>,
+    max_tag_size: u32,
+}
+
+impl Scanner {
+    /// Creates a scanner that reads strings in `string_languages` (by language code), see [`crate::text::StringContainer::selected_languages`]
+    pub fn new(
+        package_manager: Arc<PackageManager>,
+        string_languages: &[String],
+    ) -> anyhow::Result<Self> {
+        let context = Arc::new(create_scanner_context(&package_manager, string_languages)?);
+        Ok(Self {
+            package_manager,
+            context,
+            extra_types: vec![],
+            max_tag_size: 0,
+        })
+    }
+
+    /// Also scans entries of these `(type, subtype)` pairs, `None` matches every subtype
+    pub fn with_extra_types(mut self, extra_types: Vec<(u8, Option<u8>)>) -> Self {
+        self.extra_types = extra_types;
+        self
+    }
+
+    /// Skips scanning tags larger than `max_tag_size` bytes, 0 means no limit
+    pub fn with_max_tag_size(mut self, max_tag_size: u32) -> Self {
+        self.max_tag_size = max_tag_size;
+        self
+    }
+
+    pub fn context(&self) -> &ScannerContext {
+        &self.context
+    }
+
+    /// Scans a single tag. The `references` field of the result is not filled in, as that requires scanning every tag
+    pub fn scan_tag(&self, tag: TagHash) -> ScanResult {
+        let data = match self.package_manager.read_tag(tag) {
+            Ok(d) => d,
+            Err(e) => {
+                error!("Failed to read tag {tag}: {e}");
+                return ScanResult {
+                    successful: false,
+                    read_error: Some(e.to_string()),
+                    ..Default::default()
                 };
+            }
+        };
+
+        let mut scan_result = scan_file(&self.context, &data, false);
+        if let Some(entry) = self.package_manager.get_entry(tag) {
+            add_header_reference(&self.context, entry.reference, &mut scan_result);
+        }
+
+        scan_result
+    }
 
-                *p = ScanStatus::Scanning {
+    /// Scans every tag in every package and builds the reference tables.
+    /// Stops before the next package once `cancel` is set, returning `None`
+    pub fn scan_all_cancellable(
+        &self,
+        progress: impl Fn(ScanStatus) + Sync,
+        cancel: &AtomicBool,
+    ) -> anyhow::Result<Option<TagCache>> {
+        let all_pkgs = self
+            .package_manager
+            .package_paths
+            .values()
+            .cloned()
+            .collect_vec();
+
+        let version = self.package_manager.version;
+        let max_tag_size = self.max_tag_size;
+        let package_count = all_pkgs.len();
+        let packages_started = AtomicUsize::new(0);
+        let package_results: Vec<FxHashMap<TagHash, ScanResult>> = all_pkgs
+            .par_iter()
+            .map_with(self.context.clone(), |context, path| {
+                if cancel.load(Ordering::Relaxed) {
+                    return Ok(FxHashMap::default());
+                }
+
+                profiling::scope!("scan_pkg", &path.path);
+                let current_package = packages_started.fetch_add(1, Ordering::Relaxed);
+                progress(ScanStatus::Scanning {
                     current_package: current_package + 1,
                     total_packages: package_count,
-                };
+                });
 
-                current_package
-            };
-
-            info!("Opening pkg {path} ({}/{package_count})", current_package);
-            let pkg = {
-                profiling::scope!("open package");
-                version.open(&path.path).unwrap()
-            };
-
-            let mut all_tags = match version {
-                GameVersion::DestinyInternalAlpha => [
-                    pkg.get_all_by_type(16, None),
-                    pkg.get_all_by_type(128, None),
-                ]
-                .concat(),
-                GameVersion::DestinyRiseOfIron | GameVersion::DestinyTheTakenKing => [
-                    pkg.get_all_by_type(16, None),
-                    pkg.get_all_by_type(128, None),
-                ]
-                .concat(),
-                GameVersion::Destiny2Beta
-                | GameVersion::Destiny2Forsaken
-                | GameVersion::Destiny2Shadowkeep
-                | GameVersion::Destiny2BeyondLight
-                | GameVersion::Destiny2WitchQueen
-                | GameVersion::Destiny2Lightfall
-                | GameVersion::Destiny2TheFinalShape => {
-                    [pkg.get_all_by_type(8, None), pkg.get_all_by_type(16, None)].concat()
-                }
-            };
-
-            // Sort tags by starting block index to optimize sequential block reads
-            all_tags.sort_by_key(|v| v.1.starting_block);
-
-            let mut results = FxHashMap::default();
-            for (t, _) in all_tags {
-                let hash = TagHash::new(pkg.pkg_id(), t as u16);
-                profiling::scope!("scan_tag", format!("tag {hash}").as_str());
-
-                let data = match pkg.read_entry(t) {
-                    Ok(d) => d,
-                    Err(e) => {
-                        error!("Failed to read entry {path}:{t}: {e}");
-                        results.insert(
-                            hash,
-                            ScanResult {
-                                successful: false,
-                                ..Default::default()
-                            },
-                        );
-                        continue;
-                    }
-                };
+                info!("Opening pkg {path} ({}/{package_count})", current_package);
+                scan_package(context, version, path, &self.extra_types, max_tag_size)
+                    .with_context(|| format!("Failed to scan package {path}"))
+            })
+            .collect::<anyhow::Result<_>>()?;
 
-                let mut scan_result = scan_file(context, &data, false);
-                if version.is_d1() {
-                    if let Some(entry) = pkg.entry(t) {
-                        let ref_tag = TagHash(entry.reference);
-                        if context.valid_file_hashes.contains(&ref_tag) {
-                            scan_result.file_hashes.insert(
-                                0,
-                                ScannedHash {
-                                    offset: u64::MAX,
-                                    hash: ref_tag,
-                                },
-                            );
-                        }
-                    }
-                }
-                results.insert(hash, scan_result);
-            }
+        // Packages that were already being scanned still finish, their results are thrown away
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
 
-            results
-        })
-        .flatten()
-        .collect();
+        let cache: FxHashMap<TagHash, ScanResult> = package_results.into_iter().flatten().collect();
 
-    let cache = transform_tag_cache(cache);
+        let skipped = cache.values().filter(|s| s.skipped_too_large).count();
+        if skipped > 0 {
+            info!("Skipped scanning {skipped} tags larger than {max_tag_size} bytes");
+        }
 
-    *SCANNER_PROGRESS.write() = ScanStatus::WritingCache;
-    info!("Compressing tag cache...");
-    let mut writer = zstd::Encoder::new(File::create(cache_file_path).unwrap(), 3).unwrap();
+        let mut cache = transform_tag_cache(&self.package_manager, cache, &progress);
+        cache.max_tag_size = max_tag_size;
+        Ok(Some(cache))
+    }
 
-    bincode::encode_into_std_write(&cache, &mut writer, bincode::config::standard()).unwrap();
-    writer.finish().unwrap();
-    *SCANNER_PROGRESS.write() = ScanStatus::None;
+    /// Rescans a single package and merges the results into `cache`, rebuilding the reference tables.
+    /// Uses the max tag size `cache` was built with rather than the scanner's, so the results of all packages stay comparable
+    pub fn rescan_package(
+        &self,
+        cache: &TagCache,
+        pkg_id: u16,
+        progress: impl Fn(ScanStatus),
+    ) -> anyhow::Result<TagCache> {
+        let path = self
+            .package_manager
+            .package_paths
+            .get(&pkg_id)
+            .with_context(|| format!("Package {pkg_id:04X} not found"))?;
+
+        progress(ScanStatus::Scanning {
+            current_package: 1,
+            total_packages: 1,
+        });
+        info!("Rescanning pkg {path}");
+        let version = self.package_manager.version;
+        let max_tag_size = cache.max_tag_size;
+        let package_results = scan_package(
+            &self.context,
+            version,
+            path,
+            &self.extra_types,
+            max_tag_size,
+        )?;
+
+        // Only keep the scanned tags of other packages, the references and non-structure tags are added back by the transform
+        let mut results: FxHashMap<TagHash, ScanResult> = cache
+            .hashes
+            .iter()
+            .filter(|(tag, _)| {
+                tag.pkg_id() != pkg_id
+                    && self.package_manager.get_entry(**tag).is_some_and(|e| {
+                        is_scanned_type(version, &self.extra_types, e.file_type, e.file_subtype)
+                    })
+            })
+            .map(|(tag, scan)| {
+                (
+                    *tag,
+                    ScanResult {
+                        references: vec![],
+                        ..scan.clone()
+                    },
+                )
+            })
+            .collect();
+        results.extend(package_results);
+
+        let mut new_cache = transform_tag_cache(&self.package_manager, results, &progress);
+        new_cache.max_tag_size = max_tag_size;
+        Ok(new_cache)
+    }
+}
+
+/// Entry types that are scanned for references by default, on top of the extra types set with [`set_extra_scan_types`]
+fn is_scanned_type(
+    version: GameVersion,
+    extra_types: &[(u8, Option<u8>)],
+    file_type: u8,
+    file_subtype: u8,
+) -> bool {
+    let default_types: &[u8] = match version {
+        GameVersion::DestinyInternalAlpha
+        | GameVersion::DestinyRiseOfIron
+        | GameVersion::DestinyTheTakenKing => &[16, 128],
+        GameVersion::Destiny2Beta
+        | GameVersion::Destiny2Forsaken
+        | GameVersion::Destiny2Shadowkeep
+        | GameVersion::Destiny2BeyondLight
+        | GameVersion::Destiny2WitchQueen
+        | GameVersion::Destiny2Lightfall
+        | GameVersion::Destiny2TheFinalShape => &[8, 16],
+    };
+
+    default_types.contains(&file_type)
+        || extra_types
+            .iter()
+            .any(|(t, st)| *t == file_type && st.map_or(true, |st| st == file_subtype))
+}
+
+/// Scans all tags of the scanned types in a single package. The `references` fields are filled in by [`transform_tag_cache`]
+fn scan_package(
+    context: &ScannerContext,
+    version: GameVersion,
+    path: &PackagePath,
+    extra_types: &[(u8, Option<u8>)],
+    max_tag_size: u32,
+) -> anyhow::Result<FxHashMap<TagHash, ScanResult>> {
+    let pkg = {
+        profiling::scope!("open package");
+        version
+            .open(&path.path)
+            .with_context(|| format!("Failed to open package {path}"))?
+    };
+
+    let mut all_tags = pkg
+        .entries()
+        .iter()
+        .cloned()
+        .enumerate()
+        .filter(|(_, e)| is_scanned_type(version, extra_types, e.file_type, e.file_subtype))
+        .collect_vec();
+
+    // Sort tags by starting block index to optimize sequential block reads
+    all_tags.sort_by_key(|v| v.1.starting_block);
 
-    cache
+    let mut results = FxHashMap::default();
+    for (t, entry) in all_tags {
+        let hash = TagHash::new(pkg.pkg_id(), t as u16);
+        profiling::scope!("scan_tag", format!("tag {hash}").as_str());
+
+        // Checked before reading, as reading the data is most of the cost for big tags
+        if max_tag_size != 0 && entry.file_size > max_tag_size {
+            results.insert(
+                hash,
+                ScanResult {
+                    data_size: entry.file_size,
+                    skipped_too_large: true,
+                    ..Default::default()
+                },
+            );
+            continue;
+        }
+
+        let data = match pkg.read_entry(t) {
+            Ok(d) => d,
+            Err(e) => {
+                error!("Failed to read entry {path}:{t}: {e}");
+                results.insert(
+                    hash,
+                    ScanResult {
+                        successful: false,
+                        read_error: Some(e.to_string()),
+                        ..Default::default()
+                    },
+                );
+                continue;
+            }
+        };
+
+        let mut scan_result = scan_file(context, &data, false);
+        add_header_reference(context, entry.reference, &mut scan_result);
+        results.insert(hash, scan_result);
+    }
+
+    Ok(results)
+}
+
+/// Destiny 1 tags reference their tag header through the entry reference field
+fn add_header_reference(context: &ScannerContext, reference: u32, scan_result: &mut ScanResult) {
+    if !context.version.is_d1() {
+        return;
+    }
+
+    let ref_tag = TagHash(reference);
+    if context.valid_file_hashes.binary_search(&ref_tag).is_ok() {
+        scan_result.file_hashes.insert(
+            0,
+            ScannedHash {
+                offset: u64::MAX,
+                hash: ref_tag,
+            },
+        );
+    }
 }
 
 /// Transforms the tag cache to include reference lookup tables
-fn transform_tag_cache(cache: FxHashMap<TagHash, ScanResult>) -> TagCache {
+fn transform_tag_cache(
+    package_manager: &PackageManager,
+    cache: FxHashMap<TagHash, ScanResult>,
+    progress: &impl Fn(ScanStatus),
+) -> TagCache {
     info!("Transforming tag cache...");
 
     let mut new_cache: TagCache = Default::default();
 
-    *SCANNER_PROGRESS.write() = ScanStatus::TransformGathering;
+    progress(ScanStatus::TransformGathering);
     info!("\t- Gathering references");
-    let mut direct_reference_cache: FxHashMap<TagHash, Vec<TagHash>> = Default::default();
+    let mut direct_reference_cache: FxHashMap<TagHash, Vec<(TagHash, u64)>> = Default::default();
     for (k2, v2) in &cache {
         for t32 in &v2.file_hashes {
             match direct_reference_cache.entry(t32.hash) {
                 std::collections::hash_map::Entry::Occupied(mut o) => {
-                    o.get_mut().push(*k2);
+                    o.get_mut().push((*k2, t32.offset));
                 }
                 std::collections::hash_map::Entry::Vacant(v) => {
-                    v.insert(vec![*k2]);
+                    v.insert(vec![(*k2, t32.offset)]);
                 }
             }
         }
 
         for t64 in &v2.file_hashes64 {
-            if let Some(t32) = package_manager().hash64_table.get(&t64.hash.0) {
+            if let Some(t32) = package_manager.hash64_table.get(&t64.hash.0) {
                 match direct_reference_cache.entry(t32.hash32) {
                     std::collections::hash_map::Entry::Occupied(mut o) => {
-                        o.get_mut().push(*k2);
+                        o.get_mut().push((*k2, t64.offset));
                     }
                     std::collections::hash_map::Entry::Vacant(v) => {
-                        v.insert(vec![*k2]);
+                        v.insert(vec![(*k2, t64.offset)]);
                     }
                 }
             }
         }
     }
 
-    *SCANNER_PROGRESS.write() = ScanStatus::TransformApplying;
+    // Gathering iterates a hashmap, sort the references so the cache contents are reproducible
+    for refs in direct_reference_cache.values_mut() {
+        refs.sort_unstable_by_key(|(t, offset)| (t.pkg_id(), t.entry_index(), *offset));
+    }
+
+    progress(ScanStatus::TransformApplying);
     info!("\t- Applying references");
     for (k, v) in &cache {
         let mut scan = v.clone();
@@ -624,6 +1146,21 @@ fn transform_tag_cache(cache: FxHashMap<TagHash, ScanResult>) -> TagCache {
         new_cache.hashes.insert(*k, scan);
     }
 
+    info!("\t- Indexing tag data hashes");
+    for (tag, scan) in &new_cache.hashes {
+        if scan.data_hash != 0 {
+            new_cache
+                .identical_data
+                .entry(scan.data_hash)
+                .or_default()
+                .push(*tag);
+        }
+    }
+    new_cache.identical_data.retain(|_, tags| tags.len() > 1);
+    for tags in new_cache.identical_data.values_mut() {
+        tags.sort_unstable_by_key(|t| (t.pkg_id(), t.entry_index()));
+    }
+
     info!("\t- Adding remaining non-structure tags");
     for (k, v) in direct_reference_cache {
         if !v.is_empty() && !new_cache.hashes.contains_key(&k) {
@@ -631,13 +1168,26 @@ fn transform_tag_cache(cache: FxHashMap<TagHash, ScanResult>) -> TagCache {
                 k,
                 ScanResult {
                     references: v,
+                    // These tags aren't scanned, so take the size from the entry header instead
+                    data_size: package_manager
+                        .get_entry(k)
+                        .map(|e| e.file_size)
+                        .unwrap_or_default(),
                     ..Default::default()
                 },
             );
         }
     }
 
-    let timestamp = std::fs::metadata(&package_manager().package_dir)
+    new_cache.timestamp = package_dir_timestamp(&package_manager.package_dir);
+    new_cache.package_fingerprint = package_set_fingerprint(package_manager);
+
+    new_cache
+}
+
+/// Modification time of the packages directory in seconds since the epoch, or 0 if it can't be read
+pub fn package_dir_timestamp(package_dir: impl AsRef<Path>) -> u64 {
+    std::fs::metadata(package_dir)
         .ok()
         .and_then(|m| {
             Some(
@@ -648,11 +1198,21 @@ fn transform_tag_cache(cache: FxHashMap<TagHash, ScanResult>) -> TagCache {
                     .as_secs(),
             )
         })
-        .unwrap_or(0);
+        .unwrap_or(0)
+}
 
-    new_cache.timestamp = timestamp;
+/// Hashes the id, name and patch of every package, so a game update that adds or patches packages changes the fingerprint
+pub fn package_set_fingerprint(package_manager: &PackageManager) -> u64 {
+    let mut packages: Vec<_> = package_manager
+        .package_paths
+        .iter()
+        .map(|(id, path)| (*id, path.name.as_str(), path.patch))
+        .collect();
+    packages.sort_unstable();
 
-    new_cache
+    let mut hasher = FxHasher::default();
+    packages.hash(&mut hasher);
+    hasher.finish()
 }
 
 fn exe_directory() -> PathBuf {
@@ -663,6 +1223,6 @@ fn exe_directory() -> PathBuf {
         .to_path_buf()
 }
 
-fn exe_relative_path<P: AsRef<Path>>(path: P) -> PathBuf {
+pub fn exe_relative_path<P: AsRef<Path>>(path: P) -> PathBuf {
     exe_directory().join(path.as_ref())
 }
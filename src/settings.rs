@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+
+use lazy_static::lazy_static;
+use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use serde::{Deserialize, Serialize};
+
+const SETTINGS_KEY: &str = "quicktag_settings";
+
+/// User settings, persisted through eframe's storage
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Settings {
+    /// Follow classes marked with `@skip_traversal` by default
+    pub traverse_skipped_classes: bool,
+    /// Pre-multiply the alpha channel of textures when loading them for display
+    pub premultiply_alpha: bool,
+    /// Interpret non-sRGB rgba8 textures as sRGB
+    pub rgba8_as_srgb: bool,
+    /// Strings shorter than this are hidden from the raw strings view
+    pub raw_string_min_length: usize,
+    /// Hide raw strings that consist mostly of non-printable characters
+    pub raw_string_hide_unprintable: bool,
+    /// Color theme of the UI
+    pub theme: Theme,
+    /// Open new tags in the hex view instead of the traversal view
+    pub open_tags_in_hex_view: bool,
+    /// Scroll the hex view of new tags to the first outgoing reference
+    pub hex_scroll_to_first_reference: bool,
+    /// Number of bytes in each hex view row (8, 16 or 32). Float rows are only detected at 16
+    pub hex_bytes_per_row: usize,
+    /// Bytes per group in hex view rows (4 or 8)
+    pub hex_group_size: usize,
+    /// Show the ASCII representation of each hex view row
+    pub hex_show_ascii: bool,
+    /// Tags larger than this many bytes aren't scanned for references, 0 for no limit
+    pub scan_max_tag_size: u32,
+    /// RGB colors overriding the default tag type colors, keyed by [`TagType::color_key`](crate::tagtypes::TagType::color_key)
+    pub tag_type_colors: BTreeMap<String, [u8; 3]>,
+    /// Codes of the languages loaded into the string cache, see [`LANGUAGE_CODES`](crate::text::LANGUAGE_CODES)
+    pub string_languages: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            traverse_skipped_classes: false,
+            premultiply_alpha: true,
+            rgba8_as_srgb: true,
+            raw_string_min_length: 3,
+            raw_string_hide_unprintable: false,
+            theme: Theme::Dark,
+            open_tags_in_hex_view: false,
+            hex_scroll_to_first_reference: false,
+            hex_bytes_per_row: 16,
+            hex_group_size: 4,
+            hex_show_ascii: true,
+            scan_max_tag_size: 0,
+            tag_type_colors: BTreeMap::new(),
+            string_languages: vec!["en".to_string()],
+        }
+    }
+}
+
+lazy_static! {
+    static ref SETTINGS: RwLock<Settings> = RwLock::new(Settings::default());
+}
+
+pub fn settings() -> RwLockReadGuard<'static, Settings> {
+    SETTINGS.read()
+}
+
+pub fn settings_mut() -> RwLockWriteGuard<'static, Settings> {
+    SETTINGS.write()
+}
+
+pub fn load_settings(storage: Option<&dyn eframe::Storage>) {
+    if let Some(settings) = storage.and_then(|s| eframe::get_value::<Settings>(s, SETTINGS_KEY)) {
+        *SETTINGS.write() = settings;
+    }
+}
+
+pub fn save_settings(storage: &mut dyn eframe::Storage) {
+    eframe::set_value(storage, SETTINGS_KEY, &*SETTINGS.read());
+}
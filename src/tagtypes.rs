@@ -4,6 +4,7 @@ use destiny_pkg::GameVersion;
 use eframe::epaint::Color32;
 
 use crate::package_manager::package_manager;
+use crate::settings::settings;
 
 #[derive(PartialEq, Copy, Clone)]
 pub enum TagType {
@@ -70,7 +71,16 @@ impl TagType {
         matches!(self, TagType::WwiseBank | TagType::WwiseStream)
     }
 
+    /// The color used for this type throughout the UI, taking user overrides from the settings into account
     pub fn display_color(&self) -> Color32 {
+        if let Some(&[r, g, b]) = settings().tag_type_colors.get(self.color_key()) {
+            return Color32::from_rgb(r, g, b);
+        }
+
+        self.default_display_color()
+    }
+
+    pub fn default_display_color(&self) -> Color32 {
         match self {
             TagType::TextureOld
             | TagType::Texture2D { .. }
@@ -98,6 +108,44 @@ impl TagType {
         }
     }
 
+    /// Key used for color overrides in the settings. Header and data tags of the same type share a color
+    pub fn color_key(&self) -> &'static str {
+        match self {
+            TagType::TextureOld => "TextureOld",
+            TagType::Texture2D { .. } => "Texture2D",
+            TagType::TextureCube { .. } => "TextureCube",
+            TagType::Texture3D { .. } => "Texture3D",
+            TagType::TextureSampler { .. } => "TextureSampler",
+            TagType::TextureLargeBuffer => "TextureLargeBuffer",
+            TagType::VertexBuffer { .. } => "VertexBuffer",
+            TagType::IndexBuffer { .. } => "IndexBuffer",
+            TagType::ConstantBuffer { .. } => "ConstantBuffer",
+            TagType::PixelShader { .. } => "PixelShader",
+            TagType::VertexShader { .. } => "VertexShader",
+            TagType::GeometryShader { .. } => "GeometryShader",
+            TagType::ComputeShader { .. } => "ComputeShader",
+            TagType::WwiseBank => "WwiseBank",
+            TagType::WwiseStream => "WwiseStream",
+            TagType::Havok => "Havok",
+            TagType::OtfFontOrUmbraTome => "OtfFontOrUmbraTome",
+            TagType::CriwareUsm => "CriwareUsm",
+            TagType::Tag => "Tag",
+            TagType::TagGlobal => "TagGlobal",
+            TagType::Unknown { .. } => "Unknown",
+        }
+    }
+
+    /// One type for every color key, used to list the customizable colors
+    pub fn all_colorable() -> impl Iterator<Item = Self> {
+        [Self::TextureOld]
+            .into_iter()
+            .chain(Self::all_filterable().iter().copied())
+            .chain([Self::Unknown {
+                ftype: 0,
+                fsubtype: 0,
+            }])
+    }
+
     pub fn all_filterable() -> &'static [Self] {
         &[
             Self::Texture2D { is_header: true },
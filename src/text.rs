@@ -1,16 +1,20 @@
 // TODO(cohae): This is all copied from alkahest, and needs to be moved into alkahest-data when it becomes available
 
 use std::fmt::{Debug, Formatter, Write};
+use std::io::Write as _;
 use std::io::{Cursor, Read, Seek, SeekFrom};
 use std::ops::Deref;
+use std::path::Path;
 use std::slice::Iter;
 
 use binrw::{BinRead, BinReaderExt, BinResult, Endian, VecArgs};
-use destiny_pkg::{GameVersion, TagHash};
+use destiny_pkg::{GameVersion, PackageManager, TagHash};
+use itertools::Itertools;
 use log::error;
 use rustc_hash::{FxHashMap, FxHashSet};
 
-use crate::package_manager::package_manager;
+use crate::scanner::{fnv1, TagCache};
+use crate::util::GameVersionExt;
 
 pub type TablePointer32<T> = _TablePointer<i32, u32, T>;
 pub type TablePointer64<T> = _TablePointer<i64, u64, T>;
@@ -214,23 +218,46 @@ pub struct StringContainer {
     pub language_russian: TagHash,
 }
 
+/// Codes of the languages in a [`StringContainer`], in the order of [`StringContainer::all_languages`]
+pub const LANGUAGE_CODES: [&str; 13] = [
+    "en", "jp", "de", "fr", "es", "es_latam", "it", "ko", "zh_t", "zh_s", "pt", "pl", "ru",
+];
+
 impl StringContainer {
     pub fn all_languages(&self) -> Vec<(&'static str, TagHash)> {
-        vec![
-            ("en", self.language_english),
-            ("jp", self.language_japanese),
-            ("de", self.language_german),
-            ("fr", self.language_french),
-            ("es", self.language_spanish),
-            ("es_latam", self.language_spanish_latam),
-            ("it", self.language_italian),
-            ("ko", self.language_korean),
-            ("zh_t", self.language_chinese_traditional),
-            ("zh_s", self.language_chinese_simplified),
-            ("pt", self.language_portuguese),
-            ("pl", self.language_polish),
-            ("ru", self.language_russian),
-        ]
+        LANGUAGE_CODES
+            .into_iter()
+            .zip([
+                self.language_english,
+                self.language_japanese,
+                self.language_german,
+                self.language_french,
+                self.language_spanish,
+                self.language_spanish_latam,
+                self.language_italian,
+                self.language_korean,
+                self.language_chinese_traditional,
+                self.language_chinese_simplified,
+                self.language_portuguese,
+                self.language_polish,
+                self.language_russian,
+            ])
+            .collect()
+    }
+
+    /// The languages in `selected` (by language code), falling back to English if none match
+    pub fn selected_languages(&self, selected: &[String]) -> Vec<(&'static str, TagHash)> {
+        let languages = self
+            .all_languages()
+            .into_iter()
+            .filter(|(code, _)| selected.iter().any(|s| s == code))
+            .collect_vec();
+
+        if languages.is_empty() {
+            vec![("en", self.language_english)]
+        } else {
+            languages
+        }
     }
 }
 
@@ -363,9 +390,13 @@ pub fn decode_text(data: &[u8], cipher: u16) -> String {
     String::from_utf8_lossy(&data_clone).to_string()
 }
 
-pub fn create_stringmap() -> anyhow::Result<StringCache> {
+/// Reads all localized strings in the given languages (English only for Destiny 1), along with the string container tags they came from
+pub fn create_stringmap(
+    pm: &PackageManager,
+    languages: &[String],
+) -> anyhow::Result<(StringCache, StringSourceMap)> {
     // TODO: Change this match to use ordered version checking after destiny-pkg 0.11
-    match package_manager().version {
+    match pm.version {
         GameVersion::Destiny2Beta
         | GameVersion::Destiny2Forsaken
         | GameVersion::Destiny2Shadowkeep
@@ -374,29 +405,32 @@ pub fn create_stringmap() -> anyhow::Result<StringCache> {
         | GameVersion::Destiny2Lightfall
         | GameVersion::Destiny2TheFinalShape
         // cohae: Rise of Iron uses the same string format as D2
-        | GameVersion::DestinyRiseOfIron => create_stringmap_d2(),
-        GameVersion::DestinyTheTakenKing => create_stringmap_d1(),
-        GameVersion::DestinyInternalAlpha => create_stringmap_d1_devalpha(),
+        | GameVersion::DestinyRiseOfIron => create_stringmap_d2(pm, languages),
+        GameVersion::DestinyTheTakenKing => create_stringmap_d1(pm),
+        GameVersion::DestinyInternalAlpha => create_stringmap_d1_devalpha(pm),
 
     }
 }
 
-pub fn create_stringmap_d2() -> anyhow::Result<StringCache> {
+pub fn create_stringmap_d2(
+    pm: &PackageManager,
+    languages: &[String],
+) -> anyhow::Result<(StringCache, StringSourceMap)> {
     // TODO(cohae): We should probably derive PartialOrd for GameVersion
-    let prebl = matches!(
-        package_manager().version,
-        GameVersion::DestinyTheTakenKing
-            | GameVersion::DestinyRiseOfIron
-            | GameVersion::Destiny2Beta
-            | GameVersion::Destiny2Forsaken
-            | GameVersion::Destiny2Shadowkeep
-    );
+    // Destiny 1 versions share the prebl string layout
+    let prebl = pm.version.is_prebl()
+        || matches!(
+            pm.version,
+            GameVersion::DestinyTheTakenKing | GameVersion::DestinyRiseOfIron
+        );
     // Beyond Light still uses the same struct layout as prebl, was updated in WQ
-    let bl = package_manager().version == GameVersion::Destiny2BeyondLight;
+    let bl = pm.version == GameVersion::Destiny2BeyondLight;
 
-    let mut tmp_map: FxHashMap<u32, FxHashSet<String>> = Default::default();
-    for (t, _) in package_manager()
-        .get_all_by_reference(if package_manager().version.is_d1() {
+    // Strings are kept in the order of the languages, so the first string for a hash is in the first selected language
+    let mut tmp_map: FxHashMap<u32, Vec<String>> = Default::default();
+    let mut sources = StringSourceMap::default();
+    for (t, _) in pm
+        .get_all_by_reference(if pm.version.is_d1() {
             0x8080035A
         } else if prebl {
             0x80809A88
@@ -405,58 +439,59 @@ pub fn create_stringmap_d2() -> anyhow::Result<StringCache> {
         })
         .into_iter()
     {
-        let Ok(textset_header) = package_manager().read_tag_binrw::<StringContainer>(t) else {
+        let Ok(textset_header) = pm.read_tag_binrw::<StringContainer>(t) else {
             continue;
         };
 
-        let Ok(data) = package_manager().read_tag(textset_header.language_english) else {
-            continue;
-        };
-        let mut cur = Cursor::new(&data);
-        let text_data: StringData = cur.read_le_args((prebl, bl))?;
-
-        for (combination, hash) in text_data
-            .string_combinations
-            .iter()
-            .zip(textset_header.string_hashes.iter())
-        {
-            let mut final_string = String::new();
+        for (_, language_tag) in textset_header.selected_languages(languages) {
+            let Ok(data) = pm.read_tag(language_tag) else {
+                continue;
+            };
+            let mut cur = Cursor::new(&data);
+            let text_data: StringData = cur.read_le_args((prebl, bl))?;
+
+            for (combination, hash) in text_data
+                .string_combinations
+                .iter()
+                .zip(textset_header.string_hashes.iter())
+            {
+                let mut final_string = String::new();
+
+                for ip in 0..combination.part_count {
+                    cur.seek(combination.data.into())?;
+                    cur.seek(SeekFrom::Current(ip * 0x20))?;
+                    let part: StringPart = cur.read_le()?;
+                    if part.variable_hash != 0x811c9dc5 {
+                        final_string += &format!("<{:08X}>", part.variable_hash);
+                    } else {
+                        cur.seek(part.data.into())?;
+                        let mut data = vec![0u8; part.byte_length as usize];
+                        cur.read_exact(&mut data)?;
+                        final_string += &decode_text(&data, part.cipher_shift);
+                    }
+                }
 
-            for ip in 0..combination.part_count {
-                cur.seek(combination.data.into())?;
-                cur.seek(SeekFrom::Current(ip * 0x20))?;
-                let part: StringPart = cur.read_le()?;
-                if part.variable_hash != 0x811c9dc5 {
-                    final_string += &format!("<{:08X}>", part.variable_hash);
-                } else {
-                    cur.seek(part.data.into())?;
-                    let mut data = vec![0u8; part.byte_length as usize];
-                    cur.read_exact(&mut data)?;
-                    final_string += &decode_text(&data, part.cipher_shift);
+                let strings = tmp_map.entry(*hash).or_default();
+                if !strings.contains(&final_string) {
+                    strings.push(final_string);
                 }
+                add_string_source(&mut sources, *hash, t);
             }
-
-            tmp_map.entry(*hash).or_default().insert(final_string);
         }
     }
 
-    Ok(tmp_map
-        .into_iter()
-        .map(|(k, v)| (k, v.into_iter().collect()))
-        .collect())
+    Ok((tmp_map, sources))
 }
 
-pub fn create_stringmap_d1() -> anyhow::Result<StringCache> {
+pub fn create_stringmap_d1(pm: &PackageManager) -> anyhow::Result<(StringCache, StringSourceMap)> {
     let mut tmp_map: FxHashMap<u32, FxHashSet<String>> = Default::default();
-    for (t, _) in package_manager()
-        .get_all_by_reference(0x8080035A)
-        .into_iter()
-    {
-        let Ok(textset_header) = package_manager().read_tag_binrw::<StringContainerD1>(t) else {
+    let mut sources = StringSourceMap::default();
+    for (t, _) in pm.get_all_by_reference(0x8080035A).into_iter() {
+        let Ok(textset_header) = pm.read_tag_binrw::<StringContainerD1>(t) else {
             continue;
         };
 
-        let Ok(data) = package_manager().read_tag(textset_header.language_english) else {
+        let Ok(data) = pm.read_tag(textset_header.language_english) else {
             continue;
         };
         let mut cur = Cursor::new(&data);
@@ -490,22 +525,26 @@ pub fn create_stringmap_d1() -> anyhow::Result<StringCache> {
             }
 
             tmp_map.entry(*hash).or_default().insert(final_string);
+            add_string_source(&mut sources, *hash, t);
         }
     }
 
-    Ok(tmp_map
-        .into_iter()
-        .map(|(k, v)| (k, v.into_iter().collect()))
-        .collect())
+    Ok((
+        tmp_map
+            .into_iter()
+            .map(|(k, v)| (k, v.into_iter().collect()))
+            .collect(),
+        sources,
+    ))
 }
 
-pub fn create_stringmap_d1_devalpha() -> anyhow::Result<StringCache> {
+pub fn create_stringmap_d1_devalpha(
+    pm: &PackageManager,
+) -> anyhow::Result<(StringCache, StringSourceMap)> {
     let mut tmp_map: FxHashMap<u32, FxHashSet<String>> = Default::default();
-    for (t, _) in package_manager()
-        .get_all_by_reference(0x808004A8)
-        .into_iter()
-    {
-        let textset_header = match package_manager().read_tag_binrw::<StringContainerD1>(t) {
+    let mut sources = StringSourceMap::default();
+    for (t, _) in pm.get_all_by_reference(0x808004A8).into_iter() {
+        let textset_header = match pm.read_tag_binrw::<StringContainerD1>(t) {
             Ok(data) => data,
             Err(e) => {
                 error!("Failed to read string container: {:?}", e);
@@ -513,7 +552,7 @@ pub fn create_stringmap_d1_devalpha() -> anyhow::Result<StringCache> {
             }
         };
 
-        let Ok(data) = package_manager().read_tag(textset_header.language_english) else {
+        let Ok(data) = pm.read_tag(textset_header.language_english) else {
             continue;
         };
         let mut cur = Cursor::new(&data);
@@ -549,15 +588,177 @@ pub fn create_stringmap_d1_devalpha() -> anyhow::Result<StringCache> {
             }
 
             tmp_map.entry(*hash).or_default().insert(final_string);
+            add_string_source(&mut sources, *hash, t);
         }
     }
 
-    Ok(tmp_map
-        .into_iter()
-        .map(|(k, v)| (k, v.into_iter().collect()))
-        .collect())
+    Ok((
+        tmp_map
+            .into_iter()
+            .map(|(k, v)| (k, v.into_iter().collect()))
+            .collect(),
+        sources,
+    ))
 }
 
 pub type StringCache = FxHashMap<u32, Vec<String>>;
 pub type StringCacheVec = Vec<(u32, Vec<String>)>;
+/// String container tags each localized string hash was read from
+pub type StringSourceMap = FxHashMap<u32, Vec<TagHash>>;
+
+fn add_string_source(sources: &mut StringSourceMap, hash: u32, container: TagHash) {
+    let containers = sources.entry(hash).or_default();
+    if !containers.contains(&container) {
+        containers.push(container);
+    }
+}
+
+/// Writes every localized string hash with the string container it came from to `string_sources.csv`
+pub fn export_string_sources(
+    strings: &StringCache,
+    sources: &StringSourceMap,
+) -> anyhow::Result<()> {
+    let mut f = std::io::BufWriter::new(std::fs::File::create("string_sources.csv")?);
+    writeln!(f, "hash|container|string")?;
+    for (hash, containers) in sources.iter().sorted_by_key(|(h, _)| **h) {
+        let string = strings
+            .get(hash)
+            .and_then(|s| s.first())
+            .map(|s| s.replace('\n', "\\n"))
+            .unwrap_or_default();
+        for container in containers {
+            writeln!(f, "{hash:08X}|{container}|{string}")?;
+        }
+    }
+
+    Ok(())
+}
+/// Candidate strings per FNV1 hash, with a flag indicating whether the string came from the wordlist
 pub type RawStringHashCache = FxHashMap<u32, Vec<(String, bool)>>;
+
+/// Hashes all raw strings found during scanning, plus the embedded wordlist if enabled
+pub fn create_raw_string_hash_cache(cache: &TagCache) -> RawStringHashCache {
+    let mut rsh_cache = RawStringHashCache::default();
+    for s in cache
+        .hashes
+        .iter()
+        .flat_map(|(_, sc)| sc.raw_strings.iter().cloned())
+    {
+        let h = fnv1(s.as_bytes());
+        let entry = rsh_cache.entry(h).or_default();
+        if entry.iter().any(|(s2, _)| s2 == &s) {
+            continue;
+        }
+
+        entry.push((s, false));
+    }
+
+    #[cfg(feature = "wordlist")]
+    {
+        const WORDLIST: &str = include_str!("../wordlist.txt");
+        let load_start = std::time::Instant::now();
+        for s in WORDLIST.lines() {
+            let s = s.to_string();
+            let h = fnv1(s.as_bytes());
+            let entry = rsh_cache.entry(h).or_default();
+            if entry.iter().any(|(s2, _)| s2 == &s) {
+                continue;
+            }
+
+            entry.push((s, true));
+        }
+        log::info!(
+            "Loading {} strings from embedded wordlist in {}ms",
+            WORDLIST.lines().count(),
+            load_start.elapsed().as_millis()
+        );
+    }
+
+    rsh_cache
+}
+
+/// A single candidate string for a hash, as exported to CSV/JSON
+#[derive(serde::Serialize)]
+pub struct RawStringHashEntry<'a> {
+    pub hash: u32,
+    pub string: &'a str,
+    /// Whether the string came from the wordlist rather than being found in scanned raw strings
+    pub is_wordlist: bool,
+}
+
+/// Flattens the raw string hash cache into entries sorted by hash
+pub fn raw_string_hash_entries(cache: &RawStringHashCache) -> Vec<RawStringHashEntry<'_>> {
+    let mut entries: Vec<RawStringHashEntry> = cache
+        .iter()
+        .flat_map(|(hash, strings)| {
+            strings
+                .iter()
+                .map(|(string, is_wordlist)| RawStringHashEntry {
+                    hash: *hash,
+                    string,
+                    is_wordlist: *is_wordlist,
+                })
+        })
+        .collect();
+    entries.sort_by(|a, b| a.hash.cmp(&b.hash).then_with(|| a.string.cmp(b.string)));
+    entries
+}
+
+/// Writes all raw string hashes to `raw_strings.csv` and `raw_strings.json`
+pub fn export_raw_string_hashes(cache: &RawStringHashCache) -> anyhow::Result<()> {
+    export_raw_string_hashes_to(cache, Path::new("."))
+}
+
+fn export_raw_string_hashes_to(cache: &RawStringHashCache, dir: &Path) -> anyhow::Result<()> {
+    let entries = raw_string_hash_entries(cache);
+
+    let mut f = std::io::BufWriter::new(std::fs::File::create(dir.join("raw_strings.csv"))?);
+    writeln!(f, "hash|string|is_wordlist")?;
+    for e in &entries {
+        writeln!(f, "{:08X}|{}|{}", e.hash, e.string, e.is_wordlist)?;
+    }
+
+    let f = std::io::BufWriter::new(std::fs::File::create(dir.join("raw_strings.json"))?);
+    serde_json::to_writer_pretty(f, &entries)?;
+
+    Ok(())
+}
+
+/// A single localized string, as exported to CSV/JSON
+#[derive(serde::Serialize)]
+struct LocalizedStringEntry<'a> {
+    hash: u32,
+    string: &'a str,
+}
+
+/// Writes both string caches to `dir`: the localized strings as `localized_strings.{csv,json}` and the
+/// raw string hashes (including the wordlist) as `raw_strings.{csv,json}`
+pub fn dump_strings(
+    dir: &Path,
+    strings: &StringCache,
+    raw_strings: &RawStringHashCache,
+) -> anyhow::Result<()> {
+    std::fs::create_dir_all(dir)?;
+
+    let entries = strings
+        .iter()
+        .sorted_by_key(|(h, _)| **h)
+        .flat_map(|(hash, strings)| {
+            strings.iter().map(|string| LocalizedStringEntry {
+                hash: *hash,
+                string,
+            })
+        })
+        .collect_vec();
+
+    let mut f = std::io::BufWriter::new(std::fs::File::create(dir.join("localized_strings.csv"))?);
+    writeln!(f, "hash|string")?;
+    for e in &entries {
+        writeln!(f, "{:08X}|{}", e.hash, e.string.replace('\n', "\\n"))?;
+    }
+
+    let f = std::io::BufWriter::new(std::fs::File::create(dir.join("localized_strings.json"))?);
+    serde_json::to_writer_pretty(f, &entries)?;
+
+    export_raw_string_hashes_to(raw_strings, dir)
+}
@@ -2,6 +2,8 @@ use binrw::BinRead;
 use eframe::wgpu;
 use std::mem::transmute;
 
+use crate::settings::settings;
+
 #[allow(non_camel_case_types, dead_code, clippy::upper_case_acronyms)]
 #[repr(u32)]
 #[derive(Debug, Clone, Copy, PartialEq, Hash, BinRead)]
@@ -148,6 +150,15 @@ impl TryFrom<u32> for DxgiFormat {
     }
 }
 
+/// Bungie seems to treat non-sRGB rgba8 textures as sRGB, so they're interpreted as such unless disabled in the settings
+fn rgba8_unorm_format() -> wgpu::TextureFormat {
+    if settings().rgba8_as_srgb {
+        wgpu::TextureFormat::Rgba8UnormSrgb
+    } else {
+        wgpu::TextureFormat::Rgba8Unorm
+    }
+}
+
 impl DxgiFormat {
     pub fn to_wgpu(self) -> anyhow::Result<wgpu::TextureFormat> {
         Ok(match self {
@@ -179,7 +190,7 @@ impl DxgiFormat {
             // DxgiFormat::R8G8_SNORM => VkFormat::R8G8_SNORM,
             // DxgiFormat::R8G8_SINT => VkFormat::R8G8_SINT,
             DxgiFormat::R8G8B8A8_TYPELESS => wgpu::TextureFormat::Rgba8Unorm,
-            DxgiFormat::R8G8B8A8_UNORM => wgpu::TextureFormat::Rgba8UnormSrgb, // cohae: Bungie interprets non-sRGB rgba8 as sRGB??
+            DxgiFormat::R8G8B8A8_UNORM => rgba8_unorm_format(),
             DxgiFormat::R8G8B8A8_UNORM_SRGB => wgpu::TextureFormat::Rgba8UnormSrgb,
             DxgiFormat::R8G8B8A8_UINT => wgpu::TextureFormat::Rgba8Uint,
             DxgiFormat::R8G8B8A8_SNORM => wgpu::TextureFormat::Rgba8Snorm,
@@ -562,7 +573,7 @@ impl GcnSurfaceFormat {
             // GcnSurfaceFormat::Format11_11_10 => todo!(), // No wgpu equivalent
             GcnSurfaceFormat::Format10_10_10_2 => wgpu::TextureFormat::Rgb10a2Unorm,
             GcnSurfaceFormat::Format2_10_10_10 => wgpu::TextureFormat::Rgb10a2Unorm,
-            GcnSurfaceFormat::Format8_8_8_8 => wgpu::TextureFormat::Rgba8UnormSrgb,
+            GcnSurfaceFormat::Format8_8_8_8 => rgba8_unorm_format(),
             GcnSurfaceFormat::Format32_32 => wgpu::TextureFormat::Rg32Float,
             GcnSurfaceFormat::Format16_16_16_16 => wgpu::TextureFormat::Rgba16Unorm,
             // GcnSurfaceFormat::Format32_32_32 => todo!(), // No wgpu equivalent
@@ -781,7 +792,7 @@ impl XenosSurfaceFormat {
             // XenosSurfaceFormat::k_1_5_5_5 => wgpu::TextureFormat::UNKNOWN,
             // XenosSurfaceFormat::k_5_6_5 => wgpu::TextureFormat::UNKNOWN,
             // XenosSurfaceFormat::k_6_5_5 => wgpu::TextureFormat::UNKNOWN,
-            XenosSurfaceFormat::k_8_8_8_8 => wgpu::TextureFormat::Rgba8UnormSrgb,
+            XenosSurfaceFormat::k_8_8_8_8 => rgba8_unorm_format(),
             XenosSurfaceFormat::k_2_10_10_10 => wgpu::TextureFormat::Rgb10a2Unorm,
             XenosSurfaceFormat::k_8_A => wgpu::TextureFormat::R8Unorm,
             XenosSurfaceFormat::k_8_B => wgpu::TextureFormat::R8Unorm,
@@ -942,7 +953,7 @@ impl GcmSurfaceFormat {
     pub fn to_wgpu(self) -> anyhow::Result<wgpu::TextureFormat> {
         Ok(match self {
             GcmSurfaceFormat::B8 => wgpu::TextureFormat::R8Unorm,
-            GcmSurfaceFormat::A8R8G8B8 => wgpu::TextureFormat::Rgba8UnormSrgb,
+            GcmSurfaceFormat::A8R8G8B8 => rgba8_unorm_format(),
             GcmSurfaceFormat::COMPRESSED_DXT1 => wgpu::TextureFormat::Bc1RgbaUnormSrgb,
             GcmSurfaceFormat::COMPRESSED_DXT23 => wgpu::TextureFormat::Bc2RgbaUnormSrgb,
             GcmSurfaceFormat::COMPRESSED_DXT45 => wgpu::TextureFormat::Bc3RgbaUnormSrgb,
@@ -956,7 +967,7 @@ impl GcmSurfaceFormat {
             GcmSurfaceFormat::W16_Z16_Y16_X16_FLOAT => wgpu::TextureFormat::Rgba16Float,
             GcmSurfaceFormat::W32_Z32_Y32_X32_FLOAT => wgpu::TextureFormat::Rgba32Float,
             GcmSurfaceFormat::X32_FLOAT => wgpu::TextureFormat::R32Float,
-            GcmSurfaceFormat::D8R8G8B8 => wgpu::TextureFormat::Rgba8UnormSrgb,
+            GcmSurfaceFormat::D8R8G8B8 => rgba8_unorm_format(),
             GcmSurfaceFormat::Y16_X16_FLOAT => wgpu::TextureFormat::Rg16Float,
             u => anyhow::bail!("Unsupported GCM surface format conversion ({u:?} => ??)"),
         })
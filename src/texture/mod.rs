@@ -5,8 +5,9 @@ mod headers_xbox;
 mod swizzle;
 
 use crate::package_manager::package_manager;
+use crate::settings::settings;
 use crate::texture::texture_capture::capture_texture;
-use crate::util::ui_image_rotated;
+use crate::util::{ui_image_rotated, GameVersionExt};
 use anyhow::Context;
 use binrw::BinReaderExt;
 use destiny_pkg::package::PackagePlatform;
@@ -15,7 +16,7 @@ use dxgi::{GcmSurfaceFormat, GcnSurfaceFormat};
 use eframe::egui::Sense;
 use eframe::egui_wgpu::RenderState;
 use eframe::epaint::mutex::RwLock;
-use eframe::epaint::{vec2, TextureId};
+use eframe::epaint::{vec2, Color32, TextureId};
 use eframe::wgpu;
 use eframe::wgpu::util::DeviceExt;
 use eframe::wgpu::TextureDimension;
@@ -23,7 +24,7 @@ use either::Either::{self, Left};
 use headers_pc::TextureHeaderPC;
 use headers_ps::{TextureHeaderD2Ps4, TextureHeaderPs3, TextureHeaderRoiPs4};
 use headers_xbox::{TextureHeaderDevAlphaX360, TextureHeaderRoiXbox};
-use image::{DynamicImage, GenericImageView};
+use image::DynamicImage;
 use swizzle::swizzle_ps::{GcmDeswizzler, GcnDeswizzler};
 use swizzle::swizzle_xbox::XenosDetiler;
 use swizzle::Deswizzler;
@@ -96,6 +97,8 @@ pub struct Texture {
     pub desc: TextureDesc,
 
     pub comment: Option<String>,
+    /// What was missing if the texture data could only be partially loaded
+    pub partial: PartialTextureData,
 }
 
 pub struct TextureDesc {
@@ -105,6 +108,7 @@ pub struct TextureDesc {
     pub depth: u32,
     pub array_size: u32,
     /// Should the alpha channel be pre-multiplied on creation?
+    /// After creation, this is only true if the data was actually pre-multiplied
     pub premultiply_alpha: bool,
 }
 
@@ -122,11 +126,107 @@ impl TextureDesc {
     }
 }
 
+/// Describes a texture data buffer that is smaller than the header says it should be
+#[derive(Debug, Clone)]
+pub struct TextureSizeMismatch {
+    pub hash: TagHash,
+    pub expected: usize,
+    pub actual: usize,
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+    pub format: String,
+    /// The large buffer the data was read from, if any
+    pub large_buffer: Option<TagHash>,
+}
+
+impl std::fmt::Display for TextureSizeMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Texture data size mismatch for {} ({}x{}x{} {}): expected {}, got {}",
+            self.hash, self.width, self.height, self.depth, self.format, self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for TextureSizeMismatch {}
+
+/// Textures are still displayed when some of their data is missing, this keeps track of what couldn't be loaded
+#[derive(Debug, Clone, Default)]
+pub struct PartialTextureData {
+    /// Data buffers that couldn't be read, with the reason
+    pub read_errors: Vec<String>,
+    /// Set if the data was smaller than expected. The missing data is filled with zeroes
+    pub size_mismatch: Option<TextureSizeMismatch>,
+}
+
+impl PartialTextureData {
+    pub fn is_complete(&self) -> bool {
+        self.read_errors.is_empty() && self.size_mismatch.is_none()
+    }
+
+    /// Zero-fills `data` up to `expected` bytes if it's too short, so whatever is there can still be displayed
+    fn pad_data(&mut self, data: &mut Vec<u8>, expected: usize, mismatch: TextureSizeMismatch) {
+        if data.len() < expected {
+            self.size_mismatch = Some(mismatch);
+            data.resize(expected, 0);
+        }
+    }
+}
+
+/// Reads the texture data from the large buffer if there is one, falling back to the header's data buffer if it can't be read.
+/// Returns the data and whether it came from the large buffer
+fn read_texture_buffers(
+    texture_header_ref: TagHash,
+    large_buffer: Option<TagHash>,
+    partial: &mut PartialTextureData,
+) -> anyhow::Result<(Vec<u8>, bool)> {
+    if let Some(large_buffer) = large_buffer {
+        match package_manager().read_tag(large_buffer) {
+            Ok(data) => return Ok((data.to_vec(), true)),
+            Err(e) => partial
+                .read_errors
+                .push(format!("Failed to read large buffer {large_buffer}: {e}")),
+        }
+    }
+
+    let data = package_manager()
+        .read_tag(texture_header_ref)
+        .context("Failed to read texture data")?;
+    Ok((data.to_vec(), false))
+}
+
+/// Makes sure the texture data buffers don't point back at the header tag or at each other
+fn validate_texture_buffers(
+    hash: TagHash,
+    texture_header_ref: TagHash,
+    large_buffer: Option<TagHash>,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        texture_header_ref != hash,
+        "Texture header {hash} references itself as its data buffer"
+    );
+
+    if let Some(large_buffer) = large_buffer {
+        anyhow::ensure!(
+            large_buffer != hash,
+            "Texture header {hash} references itself as its large buffer"
+        );
+        anyhow::ensure!(
+            large_buffer != texture_header_ref,
+            "Texture header {hash} uses {large_buffer} as both its data buffer and large buffer"
+        );
+    }
+
+    Ok(())
+}
+
 impl Texture {
     pub fn load_data_d2(
         hash: TagHash,
         load_full_mip: bool,
-    ) -> anyhow::Result<(TextureHeaderGeneric, Vec<u8>, String)> {
+    ) -> anyhow::Result<(TextureHeaderGeneric, Vec<u8>, String, PartialTextureData)> {
         let texture_header_ref = package_manager()
             .get_entry(hash)
             .context("Texture header entry not found")?
@@ -136,13 +236,7 @@ impl Texture {
             .read_tag(hash)
             .context("Failed to read texture header")?;
 
-        // TODO(cohae): add a method to GameVersion to check for prebl
-        let is_prebl = matches!(
-            package_manager().version,
-            GameVersion::Destiny2Beta
-                | GameVersion::Destiny2Forsaken
-                | GameVersion::Destiny2Shadowkeep
-        );
+        let is_prebl = package_manager().version.is_prebl();
 
         let mut cur = std::io::Cursor::new(header_data);
         let texture: TextureHeaderGeneric = match package_manager().platform {
@@ -156,24 +250,24 @@ impl Texture {
             }
             _ => unreachable!("Unsupported platform for D2 textures"),
         };
-        let mut texture_data = if let Some(t) = texture.large_buffer {
-            package_manager()
-                .read_tag(t)
-                .context("Failed to read texture data")?
-        } else {
-            package_manager()
-                .read_tag(texture_header_ref)
-                .context("Failed to read texture data")?
-                .to_vec()
-        };
 
-        if load_full_mip && texture.large_buffer.is_some() {
-            let ab = package_manager()
-                .read_tag(texture_header_ref)
-                .context("Failed to read large texture buffer")?
-                .to_vec();
-
-            texture_data.extend(ab);
+        validate_texture_buffers(hash, TagHash(texture_header_ref), texture.large_buffer)?;
+
+        let mut partial = PartialTextureData::default();
+        let (mut texture_data, from_large_buffer) = read_texture_buffers(
+            TagHash(texture_header_ref),
+            texture.large_buffer,
+            &mut partial,
+        )?;
+
+        if load_full_mip && from_large_buffer {
+            match package_manager().read_tag(TagHash(texture_header_ref)) {
+                Ok(ab) => texture_data.extend(ab),
+                Err(e) => partial.read_errors.push(format!(
+                    "Failed to read texture data buffer {}: {e}",
+                    TagHash(texture_header_ref)
+                )),
+            }
         }
 
         let comment = format!("{texture:#X?}");
@@ -187,13 +281,20 @@ impl Texture {
                 let expected_size =
                     (texture.width as usize * texture.height as usize * psformat.bpp()) / 8;
 
-                if texture_data.len() < expected_size {
-                    anyhow::bail!(
-                        "Texture data size mismatch for {hash} ({}x{}x{} {:?}): expected {expected_size}, got {}",
-                        texture.width, texture.height, texture.depth, texture.format,
-                        texture_data.len()
-                    );
-                }
+                partial.pad_data(
+                    &mut texture_data,
+                    expected_size,
+                    TextureSizeMismatch {
+                        hash,
+                        expected: expected_size,
+                        actual: texture_data.len(),
+                        width: texture.width as u32,
+                        height: texture.height as u32,
+                        depth: texture.depth as u32,
+                        format: format!("{:?}", texture.format),
+                        large_buffer: texture.large_buffer,
+                    },
+                );
 
                 if texture.deswizzle {
                     let unswizzled = GcnDeswizzler
@@ -210,19 +311,19 @@ impl Texture {
                             false,
                         )
                         .context("Failed to deswizzle texture")?;
-                    Ok((texture, unswizzled, comment))
+                    Ok((texture, unswizzled, comment, partial))
                 } else {
-                    Ok((texture, texture_data, comment))
+                    Ok((texture, texture_data, comment, partial))
                 }
             }
-            _ => Ok((texture, texture_data, comment)),
+            _ => Ok((texture, texture_data, comment, partial)),
         }
     }
 
     pub fn load_data_roi_ps4(
         hash: TagHash,
         _load_full_mip: bool,
-    ) -> anyhow::Result<(TextureHeaderRoiPs4, Vec<u8>, String)> {
+    ) -> anyhow::Result<(TextureHeaderRoiPs4, Vec<u8>, String, PartialTextureData)> {
         let texture_header_ref = package_manager()
             .get_entry(hash)
             .context("Texture header entry not found")?
@@ -235,27 +336,36 @@ impl Texture {
             .map(|v| TagHash(v.reference))
             .unwrap_or_default();
 
-        let texture_data = if large_buffer.is_some() {
-            package_manager()
-                .read_tag(large_buffer)
-                .context("Failed to read texture data")?
-        } else {
-            package_manager()
-                .read_tag(texture_header_ref)
-                .context("Failed to read texture data")?
-                .to_vec()
-        };
+        validate_texture_buffers(
+            hash,
+            TagHash(texture_header_ref),
+            large_buffer.is_some().then_some(large_buffer),
+        )?;
+
+        let mut partial = PartialTextureData::default();
+        let (mut texture_data, _) = read_texture_buffers(
+            TagHash(texture_header_ref),
+            large_buffer.is_some().then_some(large_buffer),
+            &mut partial,
+        )?;
 
         let expected_size =
             (texture.width as usize * texture.height as usize * texture.format.bpp()) / 8;
 
-        if texture_data.len() < expected_size {
-            anyhow::bail!(
-                "Texture data size mismatch for {hash} ({}x{}x{} {:?}): expected {expected_size}, got {}",
-                texture.width, texture.height, texture.depth, texture.format,
-                texture_data.len()
-            );
-        }
+        partial.pad_data(
+            &mut texture_data,
+            expected_size,
+            TextureSizeMismatch {
+                hash,
+                expected: expected_size,
+                actual: texture_data.len(),
+                width: texture.width as u32,
+                height: texture.height as u32,
+                depth: texture.depth as u32,
+                format: format!("{:?}", texture.format),
+                large_buffer: large_buffer.is_some().then_some(large_buffer),
+            },
+        );
 
         let comment = format!("{texture:#X?}");
         if (texture.flags1 & 0xc00) != 0x400 {
@@ -274,16 +384,21 @@ impl Texture {
                 )
                 .context("Failed to deswizzle texture")?;
 
-            Ok((texture, unswizzled, comment))
+            Ok((texture, unswizzled, comment, partial))
         } else {
-            Ok((texture, texture_data, comment))
+            Ok((texture, texture_data, comment, partial))
         }
     }
 
     pub fn load_data_devalpha_x360(
         hash: TagHash,
         _load_full_mip: bool,
-    ) -> anyhow::Result<(TextureHeaderDevAlphaX360, Vec<u8>, String)> {
+    ) -> anyhow::Result<(
+        TextureHeaderDevAlphaX360,
+        Vec<u8>,
+        String,
+        PartialTextureData,
+    )> {
         let texture_header_ref = package_manager()
             .get_entry(hash)
             .context("Texture header entry not found")?
@@ -296,27 +411,36 @@ impl Texture {
             .map(|v| TagHash(v.reference))
             .unwrap_or_default();
 
-        let texture_data = if large_buffer.is_some() {
-            package_manager()
-                .read_tag(large_buffer)
-                .context("Failed to read texture data")?
-        } else {
-            package_manager()
-                .read_tag(texture_header_ref)
-                .context("Failed to read texture data")?
-                .to_vec()
-        };
+        validate_texture_buffers(
+            hash,
+            TagHash(texture_header_ref),
+            large_buffer.is_some().then_some(large_buffer),
+        )?;
+
+        let mut partial = PartialTextureData::default();
+        let (mut texture_data, _) = read_texture_buffers(
+            TagHash(texture_header_ref),
+            large_buffer.is_some().then_some(large_buffer),
+            &mut partial,
+        )?;
 
         let expected_size =
             (texture.width as usize * texture.height as usize * texture.format.bpp() as usize) / 8;
 
-        if texture_data.len() < expected_size {
-            anyhow::bail!(
-                "Texture data size mismatch for {hash} ({}x{}x{} {:?}): expected {expected_size}, got {}",
-                texture.width, texture.height, texture.depth, texture.format,
-                texture_data.len()
-            );
-        }
+        partial.pad_data(
+            &mut texture_data,
+            expected_size,
+            TextureSizeMismatch {
+                hash,
+                expected: expected_size,
+                actual: texture_data.len(),
+                width: texture.width as u32,
+                height: texture.height as u32,
+                depth: texture.depth as u32,
+                format: format!("{:?}", texture.format),
+                large_buffer: large_buffer.is_some().then_some(large_buffer),
+            },
+        );
 
         let comment = format!("{texture:#X?}");
 
@@ -335,13 +459,13 @@ impl Texture {
             )
             .context("Failed to deswizzle texture")?;
 
-        Ok((texture, untiled, comment))
+        Ok((texture, untiled, comment, partial))
     }
 
     pub fn load_data_roi_xone(
         hash: TagHash,
         _load_full_mip: bool,
-    ) -> anyhow::Result<(TextureHeaderRoiXbox, Vec<u8>, String)> {
+    ) -> anyhow::Result<(TextureHeaderRoiXbox, Vec<u8>, String, PartialTextureData)> {
         let texture_header_ref = package_manager()
             .get_entry(hash)
             .context("Texture header entry not found")?
@@ -354,27 +478,36 @@ impl Texture {
             .map(|v| TagHash(v.reference))
             .unwrap_or_default();
 
-        let texture_data = if large_buffer.is_some() {
-            package_manager()
-                .read_tag(large_buffer)
-                .context("Failed to read texture data")?
-        } else {
-            package_manager()
-                .read_tag(texture_header_ref)
-                .context("Failed to read texture data")?
-                .to_vec()
-        };
+        validate_texture_buffers(
+            hash,
+            TagHash(texture_header_ref),
+            large_buffer.is_some().then_some(large_buffer),
+        )?;
+
+        let mut partial = PartialTextureData::default();
+        let (mut texture_data, _) = read_texture_buffers(
+            TagHash(texture_header_ref),
+            large_buffer.is_some().then_some(large_buffer),
+            &mut partial,
+        )?;
 
         let expected_size =
             (texture.width as usize * texture.height as usize * texture.format.bpp()) / 8;
 
-        if texture_data.len() < expected_size {
-            anyhow::bail!(
-                "Texture data size mismatch for {hash} ({}x{}x{} {:?}): expected {expected_size}, got {}",
-                texture.width, texture.height, texture.depth, texture.format,
-                texture_data.len()
-            );
-        }
+        partial.pad_data(
+            &mut texture_data,
+            expected_size,
+            TextureSizeMismatch {
+                hash,
+                expected: expected_size,
+                actual: texture_data.len(),
+                width: texture.width as u32,
+                height: texture.height as u32,
+                depth: texture.depth as u32,
+                format: format!("{:?}", texture.format),
+                large_buffer: large_buffer.is_some().then_some(large_buffer),
+            },
+        );
 
         let comment = format!("{texture:#X?}");
         // if (texture.flags1 & 0xc00) != 0x400 {
@@ -399,13 +532,13 @@ impl Texture {
         //     texture.height as usize,
         //     texture.format,
         // );
-        Ok((texture, texture_data, comment))
+        Ok((texture, texture_data, comment, partial))
     }
 
     pub fn load_data_ps3_ttk(
         hash: TagHash,
         _load_full_mip: bool,
-    ) -> anyhow::Result<(TextureHeaderPs3, Vec<u8>, String)> {
+    ) -> anyhow::Result<(TextureHeaderPs3, Vec<u8>, String, PartialTextureData)> {
         let texture_header_ref = package_manager()
             .get_entry(hash)
             .context("Texture header entry not found")?
@@ -418,27 +551,36 @@ impl Texture {
             .map(|v| TagHash(v.reference))
             .unwrap_or_default();
 
-        let texture_data = if large_buffer.is_some() {
-            package_manager()
-                .read_tag(large_buffer)
-                .context("Failed to read texture data")?
-        } else {
-            package_manager()
-                .read_tag(texture_header_ref)
-                .context("Failed to read texture data")?
-                .to_vec()
-        };
+        validate_texture_buffers(
+            hash,
+            TagHash(texture_header_ref),
+            large_buffer.is_some().then_some(large_buffer),
+        )?;
+
+        let mut partial = PartialTextureData::default();
+        let (mut texture_data, _) = read_texture_buffers(
+            TagHash(texture_header_ref),
+            large_buffer.is_some().then_some(large_buffer),
+            &mut partial,
+        )?;
 
         let expected_size =
             (texture.width as usize * texture.height as usize * texture.format.bpp()) / 8;
 
-        if texture_data.len() < expected_size {
-            anyhow::bail!(
-                "Texture data size mismatch for {hash} ({}x{}x{} {:?}): expected {expected_size}, got {}",
-                texture.width, texture.height, texture.depth, texture.format,
-                texture_data.len()
-            );
-        }
+        partial.pad_data(
+            &mut texture_data,
+            expected_size,
+            TextureSizeMismatch {
+                hash,
+                expected: expected_size,
+                actual: texture_data.len(),
+                width: texture.width as u32,
+                height: texture.height as u32,
+                depth: texture.depth as u32,
+                format: format!("{:?}", texture.format),
+                large_buffer: large_buffer.is_some().then_some(large_buffer),
+            },
+        );
 
         let mut data = texture_data.clone();
         let comment = format!("{texture:#X?}");
@@ -464,10 +606,49 @@ impl Texture {
         }
 
         let unswizzled = GcmDeswizzler::color_deswizzle(&data, texture.format);
-        Ok((texture, unswizzled, comment))
+        Ok((texture, unswizzled, comment, partial))
+    }
+
+    /// Whether texture loading is implemented for the given game version and package platform
+    pub fn is_supported(version: GameVersion, platform: PackagePlatform) -> bool {
+        match version {
+            GameVersion::DestinyInternalAlpha | GameVersion::DestinyTheTakenKing => {
+                matches!(platform, PackagePlatform::X360 | PackagePlatform::PS3)
+            }
+            GameVersion::DestinyRiseOfIron => {
+                matches!(platform, PackagePlatform::PS4 | PackagePlatform::XboxOne)
+            }
+            GameVersion::Destiny2Beta
+            | GameVersion::Destiny2Forsaken
+            | GameVersion::Destiny2Shadowkeep
+            | GameVersion::Destiny2BeyondLight
+            | GameVersion::Destiny2WitchQueen
+            | GameVersion::Destiny2Lightfall
+            | GameVersion::Destiny2TheFinalShape => {
+                matches!(platform, PackagePlatform::PS4 | PackagePlatform::Win64)
+            }
+        }
+    }
+
+    /// Returns a user-facing message if textures can't be loaded for the current package set
+    pub fn unsupported_reason() -> Option<String> {
+        let version = package_manager().version;
+        let platform = package_manager().platform;
+        if Self::is_supported(version, platform) {
+            None
+        } else {
+            Some(format!(
+                "Textures are not supported for {} on {platform:?}",
+                version.name()
+            ))
+        }
     }
 
     pub fn load_desc(hash: TagHash) -> anyhow::Result<TextureDesc> {
+        if let Some(reason) = Self::unsupported_reason() {
+            anyhow::bail!(reason);
+        }
+
         match package_manager().version {
             GameVersion::DestinyInternalAlpha | GameVersion::DestinyTheTakenKing => {
                 match package_manager().platform {
@@ -534,12 +715,7 @@ impl Texture {
                         .read_tag(hash)
                         .context("Failed to read texture header")?;
 
-                    let is_prebl = matches!(
-                        package_manager().version,
-                        GameVersion::Destiny2Beta
-                            | GameVersion::Destiny2Forsaken
-                            | GameVersion::Destiny2Shadowkeep
-                    );
+                    let is_prebl = package_manager().version.is_prebl();
 
                     let mut cur = std::io::Cursor::new(header_data);
                     let texture: TextureHeaderD2Ps4 = cur.read_le_args((is_prebl,))?;
@@ -558,12 +734,7 @@ impl Texture {
                         .read_tag(hash)
                         .context("Failed to read texture header")?;
 
-                    let is_prebl = matches!(
-                        package_manager().version,
-                        GameVersion::Destiny2Beta
-                            | GameVersion::Destiny2Forsaken
-                            | GameVersion::Destiny2Shadowkeep
-                    );
+                    let is_prebl = package_manager().version.is_prebl();
 
                     let mut cur = std::io::Cursor::new(header_data);
                     let texture: TextureHeaderPC = cur.read_le_args((is_prebl,))?;
@@ -587,11 +758,15 @@ impl Texture {
         hash: TagHash,
         premultiply_alpha: bool,
     ) -> anyhow::Result<Texture> {
+        if let Some(reason) = Self::unsupported_reason() {
+            anyhow::bail!(reason);
+        }
+
         match package_manager().version {
             GameVersion::DestinyInternalAlpha | GameVersion::DestinyTheTakenKing => {
                 match package_manager().platform {
                     PackagePlatform::X360 => {
-                        let (texture, texture_data, comment) =
+                        let (texture, texture_data, comment, partial) =
                             Self::load_data_devalpha_x360(hash, true)?;
                         Self::create_texture(
                             rs,
@@ -607,9 +782,11 @@ impl Texture {
                             texture_data,
                             Some(comment),
                         )
+                        .map(|t| t.with_partial(partial))
                     }
                     PackagePlatform::PS3 => {
-                        let (texture, texture_data, comment) = Self::load_data_ps3_ttk(hash, true)?;
+                        let (texture, texture_data, comment, partial) =
+                            Self::load_data_ps3_ttk(hash, true)?;
                         Self::create_texture(
                             rs,
                             hash,
@@ -624,13 +801,15 @@ impl Texture {
                             texture_data,
                             Some(comment),
                         )
+                        .map(|t| t.with_partial(partial))
                     }
                     _ => anyhow::bail!("Unsupported platform for legacy D1 textures"),
                 }
             }
             GameVersion::DestinyRiseOfIron => match package_manager().platform {
                 PackagePlatform::PS4 => {
-                    let (texture, texture_data, comment) = Self::load_data_roi_ps4(hash, true)?;
+                    let (texture, texture_data, comment, partial) =
+                        Self::load_data_roi_ps4(hash, true)?;
                     Self::create_texture(
                         rs,
                         hash,
@@ -645,10 +824,12 @@ impl Texture {
                         texture_data,
                         Some(comment),
                     )
+                    .map(|t| t.with_partial(partial))
                 }
                 PackagePlatform::XboxOne => {
                     // anyhow::bail!("Xbox One textures are not supported yet");
-                    let (texture, texture_data, comment) = Self::load_data_roi_xone(hash, true)?;
+                    let (texture, texture_data, comment, partial) =
+                        Self::load_data_roi_xone(hash, true)?;
                     Self::create_texture(
                         rs,
                         hash,
@@ -663,6 +844,7 @@ impl Texture {
                         texture_data,
                         Some(comment),
                     )
+                    .map(|t| t.with_partial(partial))
                 }
                 _ => unreachable!("Unsupported platform for RoI textures"),
             },
@@ -673,7 +855,7 @@ impl Texture {
             | GameVersion::Destiny2WitchQueen
             | GameVersion::Destiny2Lightfall
             | GameVersion::Destiny2TheFinalShape => {
-                let (texture, texture_data, comment) = Self::load_data_d2(hash, true)?;
+                let (texture, texture_data, comment, partial) = Self::load_data_d2(hash, true)?;
                 Self::create_texture(
                     rs,
                     hash,
@@ -688,6 +870,7 @@ impl Texture {
                     texture_data,
                     Some(comment),
                 )
+                .map(|t| t.with_partial(partial))
             }
         }
     }
@@ -696,7 +879,7 @@ impl Texture {
     fn create_texture(
         rs: &RenderState,
         hash: TagHash,
-        desc: TextureDesc,
+        mut desc: TextureDesc,
         // cohae: Take ownership of the data so we don't have to clone it for premultiplication
         mut data: Vec<u8>,
         comment: Option<String>,
@@ -706,14 +889,22 @@ impl Texture {
         }
 
         // Pre-multiply alpha where possible
-        if matches!(
-            desc.format,
-            wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb
-        ) {
-            for c in data.chunks_exact_mut(4) {
-                c[0] = (c[0] as f32 * c[3] as f32 / 255.) as u8;
-                c[1] = (c[1] as f32 * c[3] as f32 / 255.) as u8;
-                c[2] = (c[2] as f32 * c[3] as f32 / 255.) as u8;
+        if desc.premultiply_alpha {
+            if matches!(
+                desc.format,
+                wgpu::TextureFormat::Rgba8Unorm
+                    | wgpu::TextureFormat::Rgba8UnormSrgb
+                    | wgpu::TextureFormat::Bgra8Unorm
+                    | wgpu::TextureFormat::Bgra8UnormSrgb
+            ) {
+                for c in data.chunks_exact_mut(4) {
+                    c[0] = (c[0] as f32 * c[3] as f32 / 255.) as u8;
+                    c[1] = (c[1] as f32 * c[3] as f32 / 255.) as u8;
+                    c[2] = (c[2] as f32 * c[3] as f32 / 255.) as u8;
+                }
+            } else {
+                // Make sure the descriptor reflects what actually happened to the data
+                desc.premultiply_alpha = false;
             }
         }
 
@@ -723,7 +914,7 @@ impl Texture {
             depth_or_array_layers: 1,
         };
 
-        {
+        let layer_size = {
             let block_size = desc.format.block_copy_size(None).unwrap_or(4);
             let (block_width, block_height) = desc.format.block_dimensions();
             let physical_size = image_size.physical_size(desc.format);
@@ -741,7 +932,9 @@ impl Texture {
                 expected_data_size,
                 data.len()
             );
-        }
+
+            expected_data_size as usize
+        };
 
         let handle = rs.device.create_texture_with_data(
             &rs.queue,
@@ -767,6 +960,11 @@ impl Texture {
         });
 
         let full_texture = if desc.array_size > 1 {
+            // Missing layers are left black instead of failing the whole texture
+            if data.len() < layer_size * desc.array_size as usize {
+                data.resize(layer_size * desc.array_size as usize, 0);
+            }
+
             let handle = rs.device.create_texture_with_data(
                 &rs.queue,
                 &wgpu::TextureDescriptor {
@@ -798,13 +996,26 @@ impl Texture {
             aspect_ratio: desc.width as f32 / desc.height as f32,
             desc,
             comment,
+            partial: PartialTextureData::default(),
         })
     }
 
+    fn with_partial(mut self, partial: PartialTextureData) -> Self {
+        self.partial = partial;
+        self
+    }
+
     fn load_png(render_state: &RenderState, bytes: &[u8]) -> anyhow::Result<Texture> {
         let img = image::load_from_memory(bytes)?;
-        let rgba = img.to_rgba8();
-        let (width, height) = img.dimensions();
+        Self::from_rgba8(render_state, img.to_rgba8(), true)
+    }
+
+    pub fn from_rgba8(
+        render_state: &RenderState,
+        image: image::RgbaImage,
+        premultiply_alpha: bool,
+    ) -> anyhow::Result<Texture> {
+        let (width, height) = image.dimensions();
         Self::create_texture(
             render_state,
             TagHash::NONE,
@@ -814,20 +1025,82 @@ impl Texture {
                 height,
                 array_size: 1,
                 depth: 1,
-                premultiply_alpha: true,
+                premultiply_alpha,
             },
-            rgba.into_raw(),
+            image.into_raw(),
             None,
         )
     }
 
     pub fn to_image(&self, rs: &RenderState, layer: u32) -> anyhow::Result<DynamicImage> {
-        let (rgba_data, padded_width, padded_height) = capture_texture(rs, self, layer)?;
+        let (rgba_data, padded_width, padded_height) =
+            capture_texture(rs, self, layer, wgpu::TextureFormat::Rgba8UnormSrgb)?;
         let image = image::RgbaImage::from_raw(padded_width, padded_height, rgba_data)
             .context("Failed to create image")?;
 
         Ok(DynamicImage::from(image).crop(0, 0, self.desc.width, self.desc.height))
     }
+
+    /// Decodes a single layer of the texture to a plain RGBA8 texture, bypassing block compression sampling
+    pub fn to_decoded(&self, rs: &RenderState, layer: u32) -> anyhow::Result<Texture> {
+        let image = self.to_image(rs, layer)?.to_rgba8();
+        Self::create_texture(
+            rs,
+            TagHash::NONE,
+            TextureDesc {
+                format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                width: self.desc.width,
+                height: self.desc.height,
+                array_size: 1,
+                depth: 1,
+                premultiply_alpha: false,
+            },
+            image.into_raw(),
+            None,
+        )
+    }
+
+    /// Captures the texture through a 32-bit float target, preserving values outside of the 0-1 range
+    pub fn to_image_hdr(&self, rs: &RenderState, layer: u32) -> anyhow::Result<DynamicImage> {
+        let (data, padded_width, padded_height) =
+            capture_texture(rs, self, layer, wgpu::TextureFormat::Rgba32Float)?;
+        let floats: Vec<f32> = data
+            .chunks_exact(4)
+            .map(|c| f32::from_ne_bytes(c.try_into().unwrap()))
+            .collect();
+        let image = image::Rgba32FImage::from_raw(padded_width, padded_height, floats)
+            .context("Failed to create image")?;
+
+        Ok(DynamicImage::from(image).crop(0, 0, self.desc.width, self.desc.height))
+    }
+
+    /// Whether the texture can store values outside of the 0-1 range
+    pub fn is_hdr(&self) -> bool {
+        matches!(
+            self.desc.format,
+            wgpu::TextureFormat::Bc6hRgbUfloat
+                | wgpu::TextureFormat::Bc6hRgbFloat
+                | wgpu::TextureFormat::Rgba16Float
+                | wgpu::TextureFormat::Rg16Float
+                | wgpu::TextureFormat::R16Float
+                | wgpu::TextureFormat::Rgba32Float
+                | wgpu::TextureFormat::Rg32Float
+                | wgpu::TextureFormat::R32Float
+                | wgpu::TextureFormat::Rg11b10Float
+        )
+    }
+
+    /// Whether the texture has more than 8 bits of precision per channel
+    pub fn is_high_precision(&self) -> bool {
+        self.is_hdr()
+            || matches!(
+                self.desc.format,
+                wgpu::TextureFormat::Rgb10a2Unorm
+                    | wgpu::TextureFormat::Rgba16Unorm
+                    | wgpu::TextureFormat::Rg16Unorm
+                    | wgpu::TextureFormat::R16Unorm
+            )
+    }
 }
 
 pub type LoadedTexture = (Arc<Texture>, TextureId);
@@ -870,6 +1143,26 @@ impl TextureCache {
             .any(|(_, v)| matches!(v, Either::Right(_)))
     }
 
+    /// Number of textures that are still being loaded
+    pub fn loading_texture_count(&self) -> usize {
+        self.cache
+            .read()
+            .iter()
+            .filter(|(_, v)| matches!(v, Either::Right(_)))
+            .count()
+    }
+
+    /// Frees all cached textures, so they're reloaded with the current settings on next use
+    pub fn clear(&self) {
+        let mut cache = self.cache.write();
+        let mut renderer = self.render_state.renderer.write();
+        while let Some((_, v)) = cache.pop_front() {
+            if let Either::Left(Some((_, tid))) = v {
+                renderer.free_texture(&tid);
+            }
+        }
+    }
+
     pub fn get_or_default(&self, hash: TagHash) -> LoadedTexture {
         self.get_or_load(hash)
             .unwrap_or_else(|| self.loading_placeholder.clone())
@@ -912,7 +1205,8 @@ impl TextureCache {
     }
 
     async fn load_texture_task(render_state: RenderState, hash: TagHash) -> Option<LoadedTexture> {
-        let texture = match Texture::load(&render_state, hash, true) {
+        let premultiply_alpha = settings().premultiply_alpha;
+        let texture = match Texture::load(&render_state, hash, premultiply_alpha) {
             Ok(t) => t,
             Err(e) => {
                 log::error!("Failed to load texture {hash}: {e}");
@@ -958,6 +1252,9 @@ impl TextureCache {
             );
 
             ui.label(tex.desc.info());
+            if !tex.partial.is_complete() {
+                ui.colored_label(Color32::YELLOW, "⚠ Texture data is incomplete");
+            }
         }
     }
 }
@@ -975,11 +1272,15 @@ impl TextureCache {
 }
 
 mod texture_capture {
-    /// Capture a texture to a raw RGBA buffer
+    /// Capture a texture to a raw buffer in the given target format
+    ///
+    /// `target_format` must be either `Rgba8UnormSrgb` or `Rgba32Float`.
+    /// This blocks until the GPU readback has finished, so avoid calling it from the UI thread
     pub fn capture_texture(
         rs: &super::RenderState,
         texture: &super::Texture,
         layer: u32,
+        target_format: eframe::wgpu::TextureFormat,
     ) -> anyhow::Result<(Vec<u8>, u32, u32)> {
         use eframe::wgpu::*;
 
@@ -990,6 +1291,19 @@ mod texture_capture {
 
         let super::RenderState { device, queue, .. } = rs;
 
+        let bytes_per_pixel = match target_format {
+            TextureFormat::Rgba8UnormSrgb => 4,
+            TextureFormat::Rgba32Float => 16,
+            f => anyhow::bail!("Unsupported capture format {f:?}"),
+        };
+
+        // Float targets aren't blendable, so those are written as-is
+        let blend = if target_format == TextureFormat::Rgba8UnormSrgb {
+            Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING)
+        } else {
+            None
+        };
+
         let texture_wgpu = device.create_texture(&TextureDescriptor {
             label: None,
             size: Extent3d {
@@ -1000,14 +1314,14 @@ mod texture_capture {
             mip_level_count: 1,
             sample_count: 1,
             dimension: TextureDimension::D2,
-            format: TextureFormat::Rgba8UnormSrgb,
+            format: target_format,
             usage: TextureUsages::COPY_SRC | TextureUsages::RENDER_ATTACHMENT,
-            view_formats: &[TextureFormat::Rgba8UnormSrgb],
+            view_formats: &[target_format],
         });
 
         let texture_view_wgpu = texture_wgpu.create_view(&TextureViewDescriptor {
             label: None,
-            format: Some(TextureFormat::Rgba8UnormSrgb),
+            format: Some(target_format),
             dimension: Some(TextureViewDimension::D2),
             aspect: TextureAspect::All,
             base_mip_level: 0,
@@ -1019,7 +1333,7 @@ mod texture_capture {
         // Create a buffer to hold the result of copying the texture to CPU memory
         let padded_width = (256.0 * (texture.desc.width as f32 / 256.0).ceil()) as u32;
         let padded_height = (256.0 * (texture.desc.height as f32 / 256.0).ceil()) as u32;
-        let buffer_size = (padded_width * padded_height * 4) as usize;
+        let buffer_size = (padded_width * padded_height * bytes_per_pixel) as usize;
         let buffer = device.create_buffer(&BufferDescriptor {
             label: Some("Output Buffer"),
             size: buffer_size as BufferAddress,
@@ -1049,7 +1363,7 @@ mod texture_capture {
             ],
         });
 
-        // Create a render pipeline to copy the texture to an RGBA8 texture
+        // Create a render pipeline to copy the texture to the target texture
         let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
             label: Some("Render Pipeline Layout"),
             bind_group_layouts: &[&bind_group_layout],
@@ -1109,8 +1423,8 @@ mod texture_capture {
                 module: &copy_shader,
                 entry_point: "fs_main",
                 targets: &[Some(ColorTargetState {
-                    format: TextureFormat::Rgba8UnormSrgb,
-                    blend: Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    format: target_format,
+                    blend,
                     write_mask: ColorWrites::all(),
                 })],
                 compilation_options: Default::default(),
@@ -1132,7 +1446,7 @@ mod texture_capture {
             },
         });
 
-        // Copy the original texture to the RGBA8 texture using the render pipeline
+        // Copy the original texture to the target texture using the render pipeline
         let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor { label: None });
         {
             let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
@@ -1172,7 +1486,7 @@ mod texture_capture {
                     buffer: &buffer,
                     layout: ImageDataLayout {
                         offset: 0,
-                        bytes_per_row: Some(4 * padded_width),
+                        bytes_per_row: Some(bytes_per_pixel * padded_width),
                         rows_per_image: Some(padded_height),
                     },
                 },
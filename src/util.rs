@@ -1,9 +1,29 @@
 use binrw::Endian;
+use destiny_pkg::{GameVersion, TagHash, TagHash64};
 use eframe::{
     egui::{self, Pos2, Vec2},
     emath::Rot2,
 };
 
+use crate::classes::{get_class_by_name, TagClass};
+use crate::package_manager::package_manager;
+
+pub trait GameVersionExt {
+    /// Destiny 2 versions prior to Beyond Light
+    fn is_prebl(&self) -> bool;
+}
+
+impl GameVersionExt for GameVersion {
+    fn is_prebl(&self) -> bool {
+        matches!(
+            self,
+            GameVersion::Destiny2Beta
+                | GameVersion::Destiny2Forsaken
+                | GameVersion::Destiny2Shadowkeep
+        )
+    }
+}
+
 pub fn u64_from_endian(endian: Endian, bytes: [u8; 8]) -> u64 {
     match endian {
         Endian::Big => u64::from_be_bytes(bytes),
@@ -18,6 +38,175 @@ pub fn u32_from_endian(endian: Endian, bytes: [u8; 4]) -> u32 {
     }
 }
 
+/// How a tag hash in user input was interpreted, see [`parse_tag_input`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParsedTag {
+    /// 32-bit hash, either hex as it appears in tag data or decimal
+    Tag32(TagHash),
+    /// 64-bit hex hash, along with the 32-bit hash it maps to if it exists
+    Tag64 {
+        hash64: TagHash64,
+        tag: Option<TagHash>,
+    },
+    /// Both the regular and the byte-flipped form of a 32-bit hex hash exist. The regular form is preferred
+    Ambiguous {
+        tag: TagHash,
+        flipped: TagHash,
+    },
+    /// Byte-flipped 32-bit hex hash, only used when the regular form doesn't exist
+    Flipped(TagHash),
+    Invalid,
+}
+
+impl ParsedTag {
+    /// The tag to open for this input, if any
+    pub fn tag(&self) -> Option<TagHash> {
+        match *self {
+            ParsedTag::Tag32(tag) | ParsedTag::Flipped(tag) => Some(tag),
+            ParsedTag::Ambiguous { tag, .. } => Some(tag),
+            ParsedTag::Tag64 { tag, .. } => tag,
+            ParsedTag::Invalid => None,
+        }
+    }
+}
+
+/// Lookups needed to interpret tag input, kept separate from the globals so parsing can be tested without packages
+pub trait TagLookup {
+    /// The 32-bit tag a 64-bit hash maps to
+    fn hash64_to_tag(&self, hash64: u64) -> Option<TagHash>;
+    fn tag_exists(&self, tag: TagHash) -> bool;
+    /// Class with the given (case-insensitive) name
+    fn class_by_name(&self, name: &str) -> Option<TagClass>;
+}
+
+/// Looks tags up in the loaded packages, and classes in the class map and schema
+pub struct PackageLookup;
+
+impl TagLookup for PackageLookup {
+    fn hash64_to_tag(&self, hash64: u64) -> Option<TagHash> {
+        package_manager()
+            .hash64_table
+            .get(&hash64)
+            .map(|t| t.hash32)
+    }
+
+    fn tag_exists(&self, tag: TagHash) -> bool {
+        package_manager().get_entry(tag).is_some()
+    }
+
+    fn class_by_name(&self, name: &str) -> Option<TagClass> {
+        get_class_by_name(name)
+    }
+}
+
+/// Parses a tag hash from user input, see [`parse_tag_input_with`]
+pub fn parse_tag_input(input: &str) -> ParsedTag {
+    parse_tag_input_with(input, &PackageLookup)
+}
+
+/// Parses a tag hash from user input.
+/// Accepts 32-bit hex (as it appears in tag data), byte-flipped 32-bit hex, 64-bit hex and decimal hashes.
+/// Both 32-bit hex forms are looked up to tell them apart
+pub fn parse_tag_input_with(input: &str, lookup: &impl TagLookup) -> ParsedTag {
+    let input = input.trim();
+    let input = input
+        .strip_prefix("0x")
+        .or_else(|| input.strip_prefix("0X"))
+        .unwrap_or(input);
+
+    if input.is_empty() {
+        return ParsedTag::Invalid;
+    }
+
+    if input.len() >= 16 {
+        let Ok(hash) = u64::from_str_radix(input, 16) else {
+            return ParsedTag::Invalid;
+        };
+        let hash64 = TagHash64(u64::from_be(hash));
+        let tag = lookup.hash64_to_tag(hash64.0);
+        return ParsedTag::Tag64 { hash64, tag };
+    }
+
+    if input.len() > 8 && input.chars().all(|c| c.is_ascii_digit()) {
+        return input
+            .parse()
+            .map(|h| ParsedTag::Tag32(TagHash(h)))
+            .unwrap_or(ParsedTag::Invalid);
+    }
+
+    let Ok(hash) = u32::from_str_radix(input, 16) else {
+        return ParsedTag::Invalid;
+    };
+    let tag = TagHash(u32::from_be(hash));
+    let flipped = TagHash(hash);
+
+    if tag == flipped {
+        return ParsedTag::Tag32(tag);
+    }
+
+    match (lookup.tag_exists(tag), lookup.tag_exists(flipped)) {
+        (true, true) => ParsedTag::Ambiguous { tag, flipped },
+        // Fall back to the flipped form if that's the only one that exists
+        (false, true) => ParsedTag::Flipped(flipped),
+        _ => ParsedTag::Tag32(tag),
+    }
+}
+
+/// Shorthand for [`parse_tag_input`] when only the resulting tag matters
+pub fn parse_tag_hash(input: &str) -> Option<TagHash> {
+    parse_tag_input(input).tag()
+}
+
+/// Parses the split pkg/entry input (hex package ID and decimal entry index). Empty input gives [`TagHash::NONE`]
+pub fn parse_split_tag(pkg_id: &str, entry_index: &str) -> TagHash {
+    let (pkg_id, entry_index) = (pkg_id.trim(), entry_index.trim());
+    if pkg_id.is_empty() || entry_index.is_empty() {
+        return TagHash::NONE;
+    }
+
+    let pkg_id = u16::from_str_radix(pkg_id, 16).unwrap_or_default();
+    let entry_index = entry_index.parse().unwrap_or_default();
+    TagHash::new(pkg_id, entry_index)
+}
+
+/// Less than 8 hex digits (with an optional 0x prefix), returned uppercase to match formatted tag hashes
+pub fn parse_partial_hash(input: &str) -> Option<String> {
+    let input = input.trim();
+    let input = input
+        .strip_prefix("0x")
+        .or_else(|| input.strip_prefix("0X"))
+        .unwrap_or(input);
+
+    (!input.is_empty() && input.len() < 8 && input.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| input.to_uppercase())
+}
+
+/// What the tag input box does with its input, see [`parse_tag_box_input`]
+pub enum TagBoxInput {
+    /// Lists the instances of a class
+    Class(TagClass),
+    /// Searches for tags whose hash contains the given hex digits
+    PartialHash(String),
+    Tag(ParsedTag),
+}
+
+/// Interprets the tag input box. Input with non-hex characters is looked up as a class name first,
+/// short hex input is a partial hash, and anything else is parsed as a tag hash
+pub fn parse_tag_box_input(input: &str, lookup: &impl TagLookup) -> TagBoxInput {
+    let input = input.trim();
+    if !input.chars().all(|c| c.is_ascii_hexdigit()) {
+        if let Some(class) = lookup.class_by_name(input) {
+            return TagBoxInput::Class(class);
+        }
+    }
+
+    if let Some(query) = parse_partial_hash(input) {
+        return TagBoxInput::PartialHash(query);
+    }
+
+    TagBoxInput::Tag(parse_tag_input_with(input, lookup))
+}
+
 pub fn format_file_size(size: usize) -> String {
     const KB: usize = 1024;
     const MB: usize = KB * 1024;
@@ -61,3 +250,167 @@ pub fn ui_image_rotated(
 
     painter.add(egui::Shape::mesh(mesh));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tag 0x80801B4C exists, as does its byte-flipped form 0x4C1B8080 when `flipped_exists` is set
+    struct TestLookup {
+        flipped_exists: bool,
+    }
+
+    const TAG: TagHash = TagHash(0x80801B4C);
+    const FLIPPED: TagHash = TagHash(0x4C1B8080);
+    const HASH64: u64 = 0xEFCDAB8967452301;
+
+    impl TagLookup for TestLookup {
+        fn hash64_to_tag(&self, hash64: u64) -> Option<TagHash> {
+            (hash64 == HASH64).then_some(TAG)
+        }
+
+        fn tag_exists(&self, tag: TagHash) -> bool {
+            tag == TAG || (self.flipped_exists && tag == FLIPPED)
+        }
+
+        fn class_by_name(&self, name: &str) -> Option<TagClass> {
+            name.eq_ignore_ascii_case("s_technique").then(|| TagClass {
+                id: 0x80806DAA,
+                name: "s_technique".into(),
+                size: None,
+                pretty_parser: None,
+                block_tags: false,
+                skip_traversal: false,
+            })
+        }
+    }
+
+    const LOOKUP: TestLookup = TestLookup {
+        flipped_exists: false,
+    };
+
+    #[test]
+    fn hex32() {
+        assert_eq!(
+            parse_tag_input_with("4C1B8080", &LOOKUP),
+            ParsedTag::Tag32(TAG)
+        );
+        assert_eq!(
+            parse_tag_input_with(" 0x4c1b8080 ", &LOOKUP),
+            ParsedTag::Tag32(TAG)
+        );
+        // Neither form exists, the regular form is used
+        assert_eq!(
+            parse_tag_input_with("12345678", &LOOKUP),
+            ParsedTag::Tag32(TagHash(0x78563412))
+        );
+        // Byte-flipping doesn't change the hash
+        assert_eq!(
+            parse_tag_input_with("80808080", &LOOKUP),
+            ParsedTag::Tag32(TagHash(0x80808080))
+        );
+    }
+
+    #[test]
+    fn hex32_flipped() {
+        assert_eq!(
+            parse_tag_input_with("80801B4C", &LOOKUP),
+            ParsedTag::Flipped(TAG)
+        );
+        assert_eq!(
+            parse_tag_input_with(
+                "4C1B8080",
+                &TestLookup {
+                    flipped_exists: true
+                }
+            ),
+            ParsedTag::Ambiguous {
+                tag: TAG,
+                flipped: FLIPPED
+            }
+        );
+    }
+
+    #[test]
+    fn hex64() {
+        assert_eq!(
+            parse_tag_input_with("0123456789ABCDEF", &LOOKUP),
+            ParsedTag::Tag64 {
+                hash64: TagHash64(HASH64),
+                tag: Some(TAG)
+            }
+        );
+        assert_eq!(
+            parse_tag_input_with("0x1111111111111111", &LOOKUP),
+            ParsedTag::Tag64 {
+                hash64: TagHash64(0x1111111111111111),
+                tag: None
+            }
+        );
+        assert_eq!(
+            parse_tag_input_with("0123456789ABCDEZ", &LOOKUP),
+            ParsedTag::Invalid
+        );
+    }
+
+    #[test]
+    fn decimal() {
+        assert_eq!(
+            parse_tag_input_with("2155879244", &LOOKUP),
+            ParsedTag::Tag32(TAG)
+        );
+        // Too large for 32 bits
+        assert_eq!(
+            parse_tag_input_with("99999999999", &LOOKUP),
+            ParsedTag::Invalid
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(parse_tag_input_with("", &LOOKUP), ParsedTag::Invalid);
+        assert_eq!(parse_tag_input_with("0x", &LOOKUP), ParsedTag::Invalid);
+        assert_eq!(parse_tag_input_with("xyz", &LOOKUP), ParsedTag::Invalid);
+        assert_eq!(ParsedTag::Invalid.tag(), None);
+    }
+
+    #[test]
+    fn split_pkg_entry() {
+        assert_eq!(parse_split_tag("0400", "12"), TagHash::new(0x400, 12));
+        assert_eq!(parse_split_tag(" 3e3 ", " 7 "), TagHash::new(0x3E3, 7));
+        assert_eq!(parse_split_tag("", "12"), TagHash::NONE);
+        assert_eq!(parse_split_tag("0400", ""), TagHash::NONE);
+        assert_eq!(parse_split_tag("zz", "12"), TagHash::new(0, 12));
+    }
+
+    #[test]
+    fn class_name() {
+        assert!(matches!(
+            parse_tag_box_input("S_Technique", &LOOKUP),
+            TagBoxInput::Class(c) if c.id == 0x80806DAA
+        ));
+        // Unknown names fall through to tag parsing
+        assert!(matches!(
+            parse_tag_box_input("s_unknown", &LOOKUP),
+            TagBoxInput::Tag(ParsedTag::Invalid)
+        ));
+    }
+
+    #[test]
+    fn partial_hash() {
+        assert!(matches!(
+            parse_tag_box_input("1b4c", &LOOKUP),
+            TagBoxInput::PartialHash(q) if q == "1B4C"
+        ));
+        assert!(matches!(
+            parse_tag_box_input("0xab", &LOOKUP),
+            TagBoxInput::PartialHash(q) if q == "AB"
+        ));
+        assert!(matches!(
+            parse_tag_box_input("4C1B8080", &LOOKUP),
+            TagBoxInput::Tag(ParsedTag::Tag32(t)) if t == TAG
+        ));
+        assert_eq!(parse_partial_hash(""), None);
+        assert_eq!(parse_partial_hash("1234567g"), None);
+    }
+}